@@ -16,9 +16,10 @@ mod lib_off;
 /// # Parameters
 ///
 /// * `percentiles` - Array of percentile values (0-100) to display in the report. Default: `[95]`
-/// * `format` - Output format as a string: `"table"` (default), `"json"`, or `"json-pretty"`
+/// * `format` - Output format as a string: `"table"` (default), `"json"`, `"json-pretty"`, or `"folded"`
 /// * `limit` - Maximum number of functions to display in the report (0 = show all). Default: `15`
 /// * `timeout` - Optional timeout in milliseconds. If specified, the program will print the report and exit after the timeout.
+/// * `report_interval` - Optional interval in milliseconds. If specified, the report is also printed periodically while the program runs.
 ///
 /// # Examples
 ///
@@ -68,6 +69,15 @@ mod lib_off;
 /// }
 /// ```
 ///
+/// Periodic report every 60 seconds for a long-running service:
+///
+/// ```rust,no_run
+/// #[hotpath::main(format = "json", report_interval = 60000)]
+/// fn main() {
+///     // Your code here
+/// }
+/// ```
+///
 /// # Usage with Tokio
 ///
 /// When using with tokio, place `#[tokio::main]` before `#[hotpath::main]`:
@@ -118,27 +128,19 @@ pub fn main(attr: TokenStream, item: TokenStream) -> TokenStream {
 ///   - `hotpath-alloc` - Total bytes allocated
 ///   - `hotpath-alloc` - Total allocation count
 ///
-/// # Async Function Limitations
-///
-/// When using allocation profiling features with async functions, you must use the
-/// `tokio` runtime in `current_thread` mode:
+/// # Async Functions
 ///
-/// ```rust,no_run
-/// #[tokio::main(flavor = "current_thread")]
-/// async fn main() {
-///     // Your async code here
-/// }
-/// ```
-///
-/// This limitation exists because allocation tracking uses thread-local storage. In multi-threaded
-/// runtimes, async tasks can migrate between threads, making it impossible to accurately
-/// attribute allocations to specific function calls. Time-based profiling works with any runtime flavor.
+/// Allocations of async functions are tracked on every poll, on whichever thread polls
+/// the future, so they are attributed correctly on multi-threaded runtimes too. Allocations
+/// made by other tasks while the future is waiting are not counted.
 ///
 /// When the `hotpath` feature is disabled, this macro compiles to zero overhead (no instrumentation).
 ///
 /// # Parameters
 ///
 /// * `log` - If `true`, logs the result value when the function returns (requires `Debug` on return type)
+/// * `sample` - Measure only 1 in N calls. Calls are still counted exactly and totals are scaled to all calls. Default: `HOTPATH_SAMPLE_RATE` or every call
+/// * `tag` - Expression evaluated on every call, e.g. a function argument, whose string value (`AsRef<str>`) tags the call. Stats are also broken down per tag. Keep the number of distinct values low
 ///
 /// # Examples
 ///
@@ -152,6 +154,24 @@ pub fn main(attr: TokenStream, item: TokenStream) -> TokenStream {
 /// }
 /// ```
 ///
+/// Measuring 1 in 100 calls of a very hot function:
+///
+/// ```rust,no_run
+/// #[hotpath::measure(sample = 100)]
+/// fn hash(value: u64) -> u64 {
+///     value.wrapping_mul(0x9E37_79B9_7F4A_7C15)
+/// }
+/// ```
+///
+/// Breaking the stats of a function down by endpoint:
+///
+/// ```rust,no_run
+/// #[hotpath::measure(tag = endpoint)]
+/// fn handle_request(endpoint: &str) {
+///     // ...
+/// }
+/// ```
+///
 /// # See Also
 ///
 /// * [`main`](macro@main) - Attribute macro that initializes profiling
@@ -8,6 +8,7 @@ pub(crate) enum Format {
     Table,
     Json,
     JsonPretty,
+    Folded,
 }
 
 impl Format {
@@ -16,6 +17,7 @@ impl Format {
             Format::Table => quote!(hotpath::Format::Table),
             Format::Json => quote!(hotpath::Format::Json),
             Format::JsonPretty => quote!(hotpath::Format::JsonPretty),
+            Format::Folded => quote!(hotpath::Format::Folded),
         }
     }
 }
@@ -30,9 +32,10 @@ impl Format {
 /// # Parameters
 ///
 /// * `percentiles` - Array of percentile values (0-100) to display in the report. Default: `[95]`
-/// * `format` - Output format as a string: `"table"` (default), `"json"`, or `"json-pretty"`
+/// * `format` - Output format as a string: `"table"` (default), `"json"`, `"json-pretty"`, or `"folded"`
 /// * `limit` - Maximum number of functions to display in the report (0 = show all). Default: `15`
 /// * `timeout` - Optional timeout in milliseconds. If specified, the program will print the report and exit after the timeout.
+/// * `report_interval` - Optional interval in milliseconds. If specified, the report is also printed periodically while the program runs.
 ///
 /// # Examples
 ///
@@ -82,6 +85,15 @@ impl Format {
 /// }
 /// ```
 ///
+/// Periodic report every 60 seconds for a long-running service:
+///
+/// ```rust,no_run
+/// #[hotpath::main(format = "json", report_interval = 60000)]
+/// fn main() {
+///     // Your code here
+/// }
+/// ```
+///
 /// # Usage with Tokio
 ///
 /// When using with tokio, place `#[tokio::main]` before `#[hotpath::main]`:
@@ -115,6 +127,7 @@ pub fn main_impl(attr: TokenStream, item: TokenStream) -> TokenStream {
     let mut format = Format::Table;
     let mut limit: usize = 15;
     let mut timeout: Option<u64> = None;
+    let mut report_interval: Option<u64> = None;
 
     // Parse named args like: percentiles=[..], format=".."
     if !attr.is_empty() {
@@ -152,8 +165,9 @@ pub fn main_impl(attr: TokenStream, item: TokenStream) -> TokenStream {
                         "table" => Format::Table,
                         "json" => Format::Json,
                         "json-pretty" => Format::JsonPretty,
+                        "folded" => Format::Folded,
                         other => return Err(meta.error(format!(
-                            "Unknown format {:?}. Expected one of: \"table\", \"json\", \"json-pretty\"",
+                            "Unknown format {:?}. Expected one of: \"table\", \"json\", \"json-pretty\", \"folded\"",
                             other
                         ))),
                     };
@@ -174,8 +188,19 @@ pub fn main_impl(attr: TokenStream, item: TokenStream) -> TokenStream {
                 return Ok(());
             }
 
+            if meta.path.is_ident("report_interval") {
+                meta.input.parse::<syn::Token![=]>()?;
+                let li: LitInt = meta.input.parse()?;
+                let ms: u64 = li.base10_parse()?;
+                if ms == 0 {
+                    return Err(meta.error("report_interval must be greater than 0"));
+                }
+                report_interval = Some(ms);
+                return Ok(());
+            }
+
             Err(meta.error(
-                "Unknown parameter. Supported: percentiles=[..], format=\"..\", limit=N, timeout=N",
+                "Unknown parameter. Supported: percentiles=[..], format=\"..\", limit=N, timeout=N, report_interval=N",
             ))
         });
 
@@ -190,6 +215,10 @@ pub fn main_impl(attr: TokenStream, item: TokenStream) -> TokenStream {
     let asyncness = sig.asyncness.is_some();
     let fn_name = &sig.ident;
 
+    let report_interval_call = report_interval.map(|interval_ms| {
+        quote! { .report_interval(std::time::Duration::from_millis(#interval_ms)) }
+    });
+
     let base_builder = quote! {
         let caller_name: &'static str =
             concat!(module_path!(), "::", stringify!(#fn_name));
@@ -198,6 +227,7 @@ pub fn main_impl(attr: TokenStream, item: TokenStream) -> TokenStream {
             .percentiles(#percentiles_array)
             .limit(#limit)
             .format(#format_token)
+            #report_interval_call
     };
 
     let guard_init = if let Some(timeout_ms) = timeout {
@@ -251,27 +281,19 @@ pub fn main_impl(attr: TokenStream, item: TokenStream) -> TokenStream {
 ///   - `hotpath-alloc` - Total bytes allocated
 ///   - `hotpath-alloc` - Total allocation count
 ///
-/// # Async Function Limitations
-///
-/// When using allocation profiling features with async functions, you must use the
-/// `tokio` runtime in `current_thread` mode:
-///
-/// ```rust,no_run
-/// #[tokio::main(flavor = "current_thread")]
-/// async fn main() {
-///     // Your async code here
-/// }
-/// ```
+/// # Async Functions
 ///
-/// This limitation exists because allocation tracking uses thread-local storage. In multi-threaded
-/// runtimes, async tasks can migrate between threads, making it impossible to accurately
-/// attribute allocations to specific function calls. Time-based profiling works with any runtime flavor.
+/// Allocations of async functions are tracked on every poll, on whichever thread polls
+/// the future, so they are attributed correctly on multi-threaded runtimes too. Allocations
+/// made by other tasks while the future is waiting are not counted.
 ///
 /// When the `hotpath` feature is disabled, this macro compiles to zero overhead (no instrumentation).
 ///
 /// # Parameters
 ///
 /// * `log` - If `true`, logs the result value when the function returns (requires `Debug` on return type)
+/// * `sample` - Measure only 1 in N calls. Calls are still counted exactly and totals are scaled to all calls. Default: `HOTPATH_SAMPLE_RATE` or every call
+/// * `tag` - Expression evaluated on every call, e.g. a function argument, whose string value (`AsRef<str>`) tags the call. Stats are also broken down per tag. Keep the number of distinct values low
 ///
 /// # Examples
 ///
@@ -285,6 +307,24 @@ pub fn main_impl(attr: TokenStream, item: TokenStream) -> TokenStream {
 /// }
 /// ```
 ///
+/// Measuring 1 in 100 calls of a very hot function:
+///
+/// ```rust,no_run
+/// #[hotpath::measure(sample = 100)]
+/// fn hash(value: u64) -> u64 {
+///     value.wrapping_mul(0x9E37_79B9_7F4A_7C15)
+/// }
+/// ```
+///
+/// Breaking the stats of a function down by endpoint:
+///
+/// ```rust,no_run
+/// #[hotpath::measure(tag = endpoint)]
+/// fn handle_request(endpoint: &str) {
+///     // ...
+/// }
+/// ```
+///
 /// # See Also
 ///
 /// * [`main`](macro@main) - Attribute macro that initializes profiling
@@ -300,8 +340,11 @@ pub fn measure_impl(attr: TokenStream, item: TokenStream) -> TokenStream {
     let name = sig.ident.to_string();
     let asyncness = sig.asyncness.is_some();
 
-    // Parse optional `log = true` attribute
+    // Parse optional `log = true`, `sample = N` and `tag = expr` attributes
     let mut log_result = false;
+    // 0 uses HOTPATH_SAMPLE_RATE
+    let mut sample: u64 = 0;
+    let mut tag: Option<syn::Expr> = None;
 
     if !attr.is_empty() {
         let parser = syn::meta::parser(|meta| {
@@ -312,7 +355,22 @@ pub fn measure_impl(attr: TokenStream, item: TokenStream) -> TokenStream {
                 return Ok(());
             }
 
-            Err(meta.error("Unknown parameter. Supported: log = true"))
+            if meta.path.is_ident("sample") {
+                meta.input.parse::<syn::Token![=]>()?;
+                let li: LitInt = meta.input.parse()?;
+                sample = li.base10_parse()?;
+                if sample == 0 {
+                    return Err(meta.error("sample must be greater than 0"));
+                }
+                return Ok(());
+            }
+
+            if meta.path.is_ident("tag") {
+                tag = Some(meta.value()?.parse()?);
+                return Ok(());
+            }
+
+            Err(meta.error("Unknown parameter. Supported: log = true, sample = N, tag = expr"))
         });
 
         if let Err(e) = parser.parse2(proc_macro2::TokenStream::from(attr)) {
@@ -320,37 +378,37 @@ pub fn measure_impl(attr: TokenStream, item: TokenStream) -> TokenStream {
         }
     }
 
+    let tag = match tag {
+        Some(tag) => quote! { Some(::core::convert::AsRef::<str>::as_ref(&(#tag))) },
+        None => quote! { None },
+    };
+
     let wrapped = if log_result {
-        let loc = quote! { concat!(module_path!(), "::", #name) };
         if asyncness {
             quote! {
-                hotpath::functions::measure_with_log_async(#loc, || async #block).await
+                HOTPATH_SAMPLER.measure_with_log_async(#tag, || async #block).await
             }
         } else {
             quote! {
-                hotpath::functions::measure_with_log(#loc, false, false, || #block)
+                HOTPATH_SAMPLER.measure_with_log(#tag, || #block)
             }
         }
+    } else if asyncness {
+        quote! {
+            HOTPATH_SAMPLER.measure_async(#tag, async #block).await
+        }
     } else {
-        let guard_init = quote! {
-            let _guard = hotpath::functions::MeasurementGuard::build(
-                concat!(module_path!(), "::", #name),
-                false,
-                #asyncness
-            );
+        quote! {
+            let _guard = HOTPATH_SAMPLER.guard(#tag);
             #block
-        };
-
-        if asyncness {
-            quote! { async { #guard_init }.await }
-        } else {
-            guard_init
         }
     };
 
     let output = quote! {
         #(#attrs)*
         #vis #sig {
+            static HOTPATH_SAMPLER: hotpath::functions::Sampler =
+                hotpath::functions::Sampler::new(concat!(module_path!(), "::", #name), #sample);
             #wrapped
         }
     };
@@ -363,12 +363,7 @@ fn format_comparison_markdown(
 
     let mut table = Table::new();
 
-    let mut header_cells = vec![Cell::new("Function"), Cell::new("Calls"), Cell::new("Avg")];
-    for &p in &metrics.percentiles {
-        header_cells.push(Cell::new(&format!("P{}", p)));
-    }
-    header_cells.push(Cell::new("Total"));
-    header_cells.push(Cell::new("% Total"));
+    let header_cells = metrics.headers().iter().map(|h| Cell::new(h)).collect();
     table.add_row(Row::new(header_cells));
 
     for func_diff in &comparison.function_diffs {
@@ -446,8 +441,13 @@ mod test {
             total_elapsed: 140515884,
             caller_name: "basic::main".to_string(),
             percentiles: vec![95],
+            headers: ["Function", "Calls", "Avg", "P95", "Total", "% Total"]
+                .map(String::from)
+                .to_vec(),
             description: "Time metrics".to_string(),
             data: FunctionsDataJson(pr_data),
+            call_tree: vec![],
+            tags: vec![],
         };
 
         let mut main_data = HashMap::new();
@@ -487,8 +487,13 @@ mod test {
             total_elapsed: 126464296,
             caller_name: "basic::main".to_string(),
             percentiles: vec![95],
+            headers: ["Function", "Calls", "Avg", "P95", "Total", "% Total"]
+                .map(String::from)
+                .to_vec(),
             description: "Time metrics".to_string(),
             data: FunctionsDataJson(main_data),
+            call_tree: vec![],
+            tags: vec![],
         };
 
         let comparison = compare_metrics(&main_metrics, &pr_metrics);
@@ -529,8 +534,13 @@ mod test {
             total_elapsed: 100000000,
             caller_name: "test::main".to_string(),
             percentiles: vec![95],
+            headers: ["Function", "Calls", "Avg", "P95", "Total", "% Total"]
+                .map(String::from)
+                .to_vec(),
             description: "Time metrics".to_string(),
             data: FunctionsDataJson(pr_data),
+            call_tree: vec![],
+            tags: vec![],
         };
 
         let mut main_data = HashMap::new();
@@ -560,8 +570,13 @@ mod test {
             total_elapsed: 120000000,
             caller_name: "test::main".to_string(),
             percentiles: vec![95],
+            headers: ["Function", "Calls", "Avg", "P95", "Total", "% Total"]
+                .map(String::from)
+                .to_vec(),
             description: "Time metrics".to_string(),
             data: FunctionsDataJson(main_data),
+            call_tree: vec![],
+            tags: vec![],
         };
 
         let comparison = compare_metrics(&main_metrics, &pr_metrics);
@@ -620,8 +635,13 @@ mod test {
             total_elapsed: 150000000,
             caller_name: "test::main".to_string(),
             percentiles: vec![95],
+            headers: ["Function", "Calls", "Avg", "P95", "Total", "% Total"]
+                .map(String::from)
+                .to_vec(),
             description: "Time metrics".to_string(),
             data: FunctionsDataJson(pr_data),
+            call_tree: vec![],
+            tags: vec![],
         };
 
         let mut main_data = HashMap::new();
@@ -641,8 +661,13 @@ mod test {
             total_elapsed: 120000000,
             caller_name: "test::main".to_string(),
             percentiles: vec![95],
+            headers: ["Function", "Calls", "Avg", "P95", "Total", "% Total"]
+                .map(String::from)
+                .to_vec(),
             description: "Time metrics".to_string(),
             data: FunctionsDataJson(main_data),
+            call_tree: vec![],
+            tags: vec![],
         };
 
         let comparison = compare_metrics(&main_metrics, &pr_metrics);
@@ -702,8 +727,13 @@ mod test {
             total_elapsed: 140000000,
             caller_name: "test::main".to_string(),
             percentiles: vec![95],
+            headers: ["Function", "Calls", "Avg", "P95", "Total", "% Total"]
+                .map(String::from)
+                .to_vec(),
             description: "Time metrics".to_string(),
             data: FunctionsDataJson(pr_data),
+            call_tree: vec![],
+            tags: vec![],
         };
 
         // Base has function_a (updated) and function_b (removed)
@@ -734,8 +764,13 @@ mod test {
             total_elapsed: 120000000,
             caller_name: "test::main".to_string(),
             percentiles: vec![95],
+            headers: ["Function", "Calls", "Avg", "P95", "Total", "% Total"]
+                .map(String::from)
+                .to_vec(),
             description: "Time metrics".to_string(),
             data: FunctionsDataJson(main_data),
+            call_tree: vec![],
+            tags: vec![],
         };
 
         let comparison = compare_metrics(&main_metrics, &pr_metrics);
@@ -7,8 +7,9 @@
 //! - `keys`: Keyboard input handling
 
 use hotpath::json::{
-    ChannelLogs, ChannelsJson, FunctionLogsJson, FunctionsJson, FutureCall, FutureCalls,
-    FuturesJson as FuturesJsonData, LogEntry, StreamLogs, StreamsJson, ThreadsJson,
+    ChannelLogs, ChannelsJson, FunctionLogsJson, FunctionsJson, FunctionsTreeJson, FutureCall,
+    FutureCalls, FuturesJson as FuturesJsonData, LogEntry, RuntimeJson, StreamLogs, StreamsJson,
+    ThreadsJson,
 };
 use ratatui::widgets::TableState;
 use std::collections::HashMap;
@@ -28,6 +29,7 @@ pub(crate) enum SelectedTab {
     Channels,
     Streams,
     Threads,
+    Runtime,
 }
 
 impl SelectedTab {
@@ -39,6 +41,7 @@ impl SelectedTab {
             SelectedTab::Channels => 4,
             SelectedTab::Streams => 5,
             SelectedTab::Threads => 6,
+            SelectedTab::Runtime => 7,
         }
     }
 
@@ -50,6 +53,7 @@ impl SelectedTab {
             SelectedTab::Channels => "Channels",
             SelectedTab::Streams => "Streams",
             SelectedTab::Threads => "Threads",
+            SelectedTab::Runtime => "Runtime",
         }
     }
 
@@ -134,6 +138,9 @@ pub(crate) struct App {
     pub(crate) memory_functions: FunctionsJson,
     /// Whether memory profiling is available (hotpath-alloc feature enabled)
     pub(crate) memory_available: bool,
+    /// Average bytes retained per call by function (from /functions_alloc/retained endpoint),
+    /// None unless hotpath-alloc-retained is enabled
+    pub(crate) memory_retained: Option<HashMap<String, u64>>,
     /// Current channels data
     pub(crate) channels: ChannelsJson,
     /// Current streams data
@@ -174,6 +181,18 @@ pub(crate) struct App {
     pub(crate) pinned_function: Option<String>,
     /// Function log entry being inspected in popup
     pub(crate) inspected_function_log: Option<InspectedFunctionLog>,
+    /// Rolling window of the functions tables, `None` for since-start stats
+    pub(crate) functions_window: Option<Duration>,
+    /// Tag the functions tables are limited to, `None` for all calls
+    pub(crate) functions_tag: Option<String>,
+
+    // Call tree view (Timing tab)
+    /// Whether the caller→callee tree replaces the timing table
+    pub(crate) show_call_tree: bool,
+    /// Caller→callee tree data (from /functions_timing/tree endpoint)
+    pub(crate) call_tree: FunctionsTreeJson,
+    /// Selection state for call tree table
+    pub(crate) call_tree_table_state: TableState,
 
     // HTTP client and configuration
     /// HTTP client for fetching data from metrics server
@@ -210,6 +229,10 @@ pub(crate) struct App {
     pub(crate) threads: ThreadsJson,
     /// Selection state for threads tab table
     pub(crate) threads_table_state: TableState,
+    /// Current Tokio runtime data
+    pub(crate) runtime: RuntimeJson,
+    /// Selection state for runtime tab workers table
+    pub(crate) runtime_table_state: TableState,
 
     // Futures tab specific state
     /// Current futures data
@@ -243,13 +266,17 @@ impl App {
             description: "Waiting for data...".to_string(),
             caller_name: "unknown".to_string(),
             percentiles: vec![95],
+            headers: FunctionsJson::default_headers(&hotpath::ProfilingMode::Timing, &[95]),
             data: hotpath::FunctionsDataJson(std::collections::HashMap::new()),
+            call_tree: vec![],
+            tags: vec![],
         };
 
         Self {
             timing_functions: empty_functions.clone(),
             memory_functions: empty_functions,
             memory_available: true, // Assume available until we know otherwise
+            memory_retained: None,
             channels: hotpath::json::ChannelsJson {
                 current_elapsed_ns: 0,
                 channels: vec![],
@@ -273,6 +300,15 @@ impl App {
             current_function_logs: None,
             pinned_function: None,
             inspected_function_log: None,
+            functions_window: None,
+            functions_tag: None,
+            show_call_tree: false,
+            call_tree: FunctionsTreeJson {
+                total_elapsed: 0,
+                caller_name: "unknown".to_string(),
+                roots: vec![],
+            },
+            call_tree_table_state: TableState::default().with_selected(0),
             agent,
             metrics_port,
             exit: false,
@@ -294,6 +330,19 @@ impl App {
                 rss_bytes: None,
             },
             threads_table_state: TableState::default().with_selected(0),
+            runtime: RuntimeJson {
+                current_elapsed_ns: 0,
+                sample_interval_ms: 1000,
+                flavor: String::new(),
+                num_workers: 0,
+                num_alive_tasks: 0,
+                global_queue_depth: 0,
+                num_blocking_threads: None,
+                num_idle_blocking_threads: None,
+                blocking_queue_depth: None,
+                workers: vec![],
+            },
+            runtime_table_state: TableState::default().with_selected(0),
             futures: FuturesJsonData {
                 current_elapsed_ns: 0,
                 futures: vec![],
@@ -329,6 +378,7 @@ impl App {
             SelectedTab::Channels => &mut self.channels_table_state,
             SelectedTab::Streams => &mut self.streams_table_state,
             SelectedTab::Threads => &mut self.threads_table_state,
+            SelectedTab::Runtime => &mut self.runtime_table_state,
             SelectedTab::Futures => &mut self.futures_table_state,
         }
     }
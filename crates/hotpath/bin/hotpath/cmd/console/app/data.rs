@@ -2,7 +2,8 @@
 
 use super::{App, CachedLogs, CachedStreamLogs, SelectedTab};
 use hotpath::json::{
-    FunctionLogsJson, FunctionsJson, FuturesJson as FuturesJsonData, StreamsJson, ThreadsJson,
+    FunctionLogsJson, FunctionsJson, FunctionsTreeJson, FuturesJson as FuturesJsonData, ResetScope,
+    RuntimeJson, StreamsJson, ThreadsJson,
 };
 use std::collections::HashMap;
 use std::time::Instant;
@@ -45,6 +46,21 @@ impl App {
         }
     }
 
+    pub(crate) fn update_call_tree(&mut self, tree: FunctionsTreeJson) {
+        self.call_tree = tree;
+        self.last_successful_fetch = Some(Instant::now());
+        self.error_message = None;
+
+        let count = self.call_tree_len();
+        match self.call_tree_table_state.selected() {
+            Some(selected) if selected >= count && count > 0 => {
+                self.call_tree_table_state.select(Some(count - 1));
+            }
+            None if count > 0 => self.call_tree_table_state.select(Some(0)),
+            _ => {}
+        }
+    }
+
     pub(crate) fn update_memory_metrics(&mut self, metrics: FunctionsJson) {
         // Capture the currently selected function name (not index!)
         let selected_function_name = self.selected_function_name();
@@ -382,6 +398,35 @@ impl App {
         }
     }
 
+    pub(crate) fn update_runtime(&mut self, runtime: RuntimeJson) {
+        self.runtime = runtime;
+        self.last_successful_fetch = Some(Instant::now());
+        self.error_message = None;
+
+        let count = self.runtime.workers.len();
+        match self.runtime_table_state.selected() {
+            Some(selected) if selected >= count && count > 0 => {
+                self.runtime_table_state.select(Some(count - 1));
+            }
+            None if count > 0 => self.runtime_table_state.select(Some(0)),
+            _ => {}
+        }
+    }
+
+    fn refresh_memory_retained(&mut self) {
+        self.memory_retained =
+            super::super::http::fetch_functions_alloc_retained(&self.agent, self.metrics_port)
+                .ok()
+                .flatten()
+                .map(|retained| {
+                    retained
+                        .functions
+                        .into_iter()
+                        .map(|f| (f.name, f.avg_retained_bytes))
+                        .collect()
+                });
+    }
+
     pub(crate) fn refresh_stream_logs(&mut self) {
         if self.paused {
             return;
@@ -411,10 +456,46 @@ impl App {
         }
     }
 
+    /// Clears the stats shown in the selected tab on the profiled process
+    pub(crate) fn reset_stats(&mut self) {
+        let scope = match self.selected_tab {
+            SelectedTab::Timing | SelectedTab::Memory => ResetScope::Functions,
+            SelectedTab::Futures => ResetScope::Futures,
+            SelectedTab::Channels => ResetScope::Channels,
+            SelectedTab::Streams => ResetScope::Streams,
+            SelectedTab::Threads | SelectedTab::Runtime => return,
+        };
+
+        match super::super::http::reset_stats(&self.agent, self.metrics_port, scope) {
+            Ok(()) => self.refresh_data(),
+            Err(e) => {
+                self.set_error(format!("{}", e));
+            }
+        }
+    }
+
     pub(crate) fn refresh_data(&mut self) {
         match self.selected_tab {
+            SelectedTab::Timing if self.show_call_tree => {
+                match super::super::http::fetch_functions_timing_tree(
+                    &self.agent,
+                    self.metrics_port,
+                ) {
+                    Ok(tree) => {
+                        self.update_call_tree(tree);
+                    }
+                    Err(e) => {
+                        self.set_error(format!("{}", e));
+                    }
+                }
+            }
             SelectedTab::Timing => {
-                match super::super::http::fetch_functions_timing(&self.agent, self.metrics_port) {
+                match super::super::http::fetch_functions_timing(
+                    &self.agent,
+                    self.metrics_port,
+                    self.functions_window,
+                    self.functions_tag.clone(),
+                ) {
                     Ok(metrics) => {
                         self.update_timing_metrics(metrics);
                     }
@@ -425,10 +506,16 @@ impl App {
                 self.fetch_function_logs_if_open(self.metrics_port);
             }
             SelectedTab::Memory => {
-                match super::super::http::fetch_functions_alloc(&self.agent, self.metrics_port) {
+                match super::super::http::fetch_functions_alloc(
+                    &self.agent,
+                    self.metrics_port,
+                    self.functions_window,
+                    self.functions_tag.clone(),
+                ) {
                     Ok(Some(metrics)) => {
                         self.memory_available = true;
                         self.update_memory_metrics(metrics);
+                        self.refresh_memory_retained();
                     }
                     Ok(None) => {
                         self.memory_available = false;
@@ -473,6 +560,22 @@ impl App {
                     }
                 }
             }
+            SelectedTab::Runtime => {
+                match super::super::http::fetch_runtime(&self.agent, self.metrics_port) {
+                    Ok(Some(runtime)) => {
+                        self.update_runtime(runtime);
+                    }
+                    Ok(None) => {
+                        self.set_error(
+                            "Runtime metrics not available - enable tokio feature and run inside a Tokio runtime"
+                                .to_string(),
+                        );
+                    }
+                    Err(e) => {
+                        self.set_error(format!("{}", e));
+                    }
+                }
+            }
             SelectedTab::Futures => {
                 match super::super::http::fetch_futures(&self.agent, self.metrics_port) {
                     Ok(futures) => {
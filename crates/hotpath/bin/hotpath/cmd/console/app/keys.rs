@@ -33,8 +33,40 @@ impl App {
                 self.switch_to_tab(SelectedTab::Threads);
                 self.refresh_data();
             }
+            KeyCode::Char('7') => {
+                self.switch_to_tab(SelectedTab::Runtime);
+                self.refresh_data();
+            }
+            KeyCode::Char('t') | KeyCode::Char('T') if self.selected_tab == SelectedTab::Timing => {
+                self.toggle_call_tree();
+                self.refresh_data();
+            }
+            KeyCode::Char('w') | KeyCode::Char('W')
+                if self.selected_tab.is_functions_tab()
+                    && !(self.selected_tab == SelectedTab::Timing && self.show_call_tree) =>
+            {
+                self.cycle_functions_window();
+                self.refresh_data();
+            }
+            KeyCode::Char('g') | KeyCode::Char('G')
+                if self.selected_tab.is_functions_tab()
+                    && !(self.selected_tab == SelectedTab::Timing && self.show_call_tree) =>
+            {
+                self.cycle_functions_tag();
+                self.refresh_data();
+            }
+            KeyCode::Char('r') | KeyCode::Char('R')
+                if !matches!(
+                    self.selected_tab,
+                    SelectedTab::Threads | SelectedTab::Runtime
+                ) =>
+            {
+                self.reset_stats();
+            }
             KeyCode::Char('o') | KeyCode::Char('O') => {
-                if self.selected_tab == SelectedTab::Channels {
+                if self.selected_tab == SelectedTab::Timing && self.show_call_tree {
+                    // No logs panel in call tree view - do nothing
+                } else if self.selected_tab == SelectedTab::Channels {
                     match self.channels_focus {
                         ChannelsFocus::Inspect => self.close_inspect_and_refocus_channels(),
                         ChannelsFocus::Logs => self.hide_logs(),
@@ -52,8 +84,11 @@ impl App {
                         FuturesFocus::Calls => self.hide_future_calls(),
                         FuturesFocus::Futures => self.toggle_future_calls(),
                     }
-                } else if self.selected_tab == SelectedTab::Threads {
-                    // No logs panel for threads tab - do nothing
+                } else if matches!(
+                    self.selected_tab,
+                    SelectedTab::Threads | SelectedTab::Runtime
+                ) {
+                    // No logs panel for threads and runtime tabs - do nothing
                 } else if self.selected_tab.is_functions_tab() {
                     match self.functions_focus {
                         FunctionsFocus::Inspect => {
@@ -138,6 +173,10 @@ impl App {
                     }
                 } else if self.selected_tab == SelectedTab::Threads {
                     self.select_next_thread();
+                } else if self.selected_tab == SelectedTab::Runtime {
+                    self.select_next_runtime_worker();
+                } else if self.selected_tab == SelectedTab::Timing && self.show_call_tree {
+                    self.select_next_call_tree_node();
                 } else if self.selected_tab.is_functions_tab() {
                     match self.functions_focus {
                         FunctionsFocus::Functions => {
@@ -172,6 +211,10 @@ impl App {
                     }
                 } else if self.selected_tab == SelectedTab::Threads {
                     self.select_previous_thread();
+                } else if self.selected_tab == SelectedTab::Runtime {
+                    self.select_previous_runtime_worker();
+                } else if self.selected_tab == SelectedTab::Timing && self.show_call_tree {
+                    self.select_previous_call_tree_node();
                 } else if self.selected_tab.is_functions_tab() {
                     match self.functions_focus {
                         FunctionsFocus::Functions => {
@@ -514,6 +514,33 @@ impl App {
         self.threads_table_state.select(Some(i));
     }
 
+    // Runtime state management methods
+    pub(crate) fn select_previous_runtime_worker(&mut self) {
+        let count = self.runtime.workers.len();
+        if count == 0 {
+            return;
+        }
+
+        let i = match self.runtime_table_state.selected() {
+            Some(i) => i.saturating_sub(1),
+            None => 0,
+        };
+        self.runtime_table_state.select(Some(i));
+    }
+
+    pub(crate) fn select_next_runtime_worker(&mut self) {
+        let count = self.runtime.workers.len();
+        if count == 0 {
+            return;
+        }
+
+        let i = match self.runtime_table_state.selected() {
+            Some(i) => (i + 1).min(count - 1),
+            None => 0,
+        };
+        self.runtime_table_state.select(Some(i));
+    }
+
     // Futures state management methods
     pub(crate) fn select_previous_future(&mut self) {
         let count = self.futures.futures.len();
@@ -671,4 +698,71 @@ impl App {
         self.futures_focus = FuturesFocus::Futures;
         self.future_calls_table_state.select(None);
     }
+
+    pub(crate) fn cycle_functions_window(&mut self) {
+        let windows = super::super::constants::FUNCTIONS_WINDOWS;
+        let current = windows
+            .iter()
+            .position(|w| *w == self.functions_window)
+            .unwrap_or(0);
+        self.functions_window = windows[(current + 1) % windows.len()];
+    }
+
+    pub(crate) fn cycle_functions_tag(&mut self) {
+        let tags = &self.active_functions().tags;
+        let next = match &self.functions_tag {
+            None => tags.first(),
+            Some(current) => tags
+                .iter()
+                .position(|tag| tag == current)
+                .and_then(|i| tags.get(i + 1)),
+        };
+        self.functions_tag = next.cloned();
+    }
+
+    pub(crate) fn toggle_call_tree(&mut self) {
+        self.show_call_tree = !self.show_call_tree;
+        if self.show_call_tree {
+            // The tree replaces the functions table, close the logs panel
+            self.show_function_logs = false;
+            self.pinned_function = None;
+            self.inspected_function_log = None;
+            self.function_logs_table_state.select(None);
+            self.functions_focus = FunctionsFocus::Functions;
+        }
+    }
+
+    pub(crate) fn select_previous_call_tree_node(&mut self) {
+        let count = self.call_tree_len();
+        if count == 0 {
+            return;
+        }
+
+        let i = match self.call_tree_table_state.selected() {
+            Some(i) => i.saturating_sub(1),
+            None => 0,
+        };
+        self.call_tree_table_state.select(Some(i));
+    }
+
+    pub(crate) fn select_next_call_tree_node(&mut self) {
+        let count = self.call_tree_len();
+        if count == 0 {
+            return;
+        }
+
+        let i = match self.call_tree_table_state.selected() {
+            Some(i) => (i + 1).min(count - 1),
+            None => 0,
+        };
+        self.call_tree_table_state.select(Some(i));
+    }
+
+    /// Number of rows in the flattened call tree
+    pub(crate) fn call_tree_len(&self) -> usize {
+        fn count(nodes: &[hotpath::CallTreeNode]) -> usize {
+            nodes.iter().map(|n| 1 + count(&n.children)).sum()
+        }
+        count(&self.call_tree.roots)
+    }
 }
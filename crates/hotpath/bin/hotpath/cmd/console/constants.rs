@@ -8,6 +8,14 @@ const HTTP_TIMEOUT_MS: u64 = 2000;
 /// Polling interval for keyboard events in the main loop (milliseconds)
 const EVENT_POLL_INTERVAL_MS: u64 = 100;
 
+/// Rolling windows cycled through on the functions tabs, `None` shows since-start stats
+pub(crate) const FUNCTIONS_WINDOWS: [Option<Duration>; 4] = [
+    None,
+    Some(Duration::from_secs(10)),
+    Some(Duration::from_secs(60)),
+    Some(Duration::from_secs(300)),
+];
+
 /// HTTP timeout as Duration
 pub(crate) fn http_timeout() -> Duration {
     Duration::from_millis(HTTP_TIMEOUT_MS)
@@ -1,13 +1,20 @@
 use eyre::Result;
 use hotpath::json::{
-    ChannelLogs, ChannelsJson, FunctionLogsJson, FunctionsJson, FutureCalls, FuturesJson, Route,
-    StreamLogs, StreamsJson, ThreadsJson,
+    ChannelLogs, ChannelsJson, FunctionLogsJson, FunctionsJson, FunctionsRetainedJson,
+    FunctionsTreeJson, FutureCalls, FuturesJson, ResetScope, Route, RuntimeJson, StreamLogs,
+    StreamsJson, ThreadsJson,
 };
+use std::time::Duration;
 
 /// Fetches timing metrics from the hotpath HTTP server
 #[hotpath::measure(log = true)]
-pub(crate) fn fetch_functions_timing(agent: &ureq::Agent, port: u16) -> Result<FunctionsJson> {
-    let url = Route::FunctionsTiming.to_url(port);
+pub(crate) fn fetch_functions_timing(
+    agent: &ureq::Agent,
+    port: u16,
+    window: Option<Duration>,
+    tag: Option<String>,
+) -> Result<FunctionsJson> {
+    let url = Route::FunctionsTimingQuery { window, tag }.to_url(port);
     let metrics: FunctionsJson = agent
         .get(&url)
         .call()
@@ -18,14 +25,33 @@ pub(crate) fn fetch_functions_timing(agent: &ureq::Agent, port: u16) -> Result<F
     Ok(metrics)
 }
 
+/// Fetches the caller→callee tree of measured functions from the hotpath HTTP server
+#[hotpath::measure(log = true)]
+pub(crate) fn fetch_functions_timing_tree(
+    agent: &ureq::Agent,
+    port: u16,
+) -> Result<FunctionsTreeJson> {
+    let url = Route::FunctionsTimingTree.to_url(port);
+    let tree: FunctionsTreeJson = agent
+        .get(&url)
+        .call()
+        .map_err(|e| eyre::eyre!("HTTP request failed: {}", e))?
+        .body_mut()
+        .read_json()
+        .map_err(|e| eyre::eyre!("JSON deserialization failed: {}", e))?;
+    Ok(tree)
+}
+
 /// Fetches allocation metrics from the hotpath HTTP server
 /// Returns None if hotpath-alloc feature is not enabled (404 response)
 #[hotpath::measure(log = true)]
 pub(crate) fn fetch_functions_alloc(
     agent: &ureq::Agent,
     port: u16,
+    window: Option<Duration>,
+    tag: Option<String>,
 ) -> Result<Option<FunctionsJson>> {
-    let url = Route::FunctionsAlloc.to_url(port);
+    let url = Route::FunctionsAllocQuery { window, tag }.to_url(port);
     let response = agent.get(&url).call();
 
     match response {
@@ -44,6 +70,29 @@ pub(crate) fn fetch_functions_alloc(
     }
 }
 
+/// Fetches retained and live heap memory per function from the hotpath HTTP server
+/// Returns None if hotpath-alloc-retained feature is not enabled (404 response)
+#[hotpath::measure(log = true)]
+pub(crate) fn fetch_functions_alloc_retained(
+    agent: &ureq::Agent,
+    port: u16,
+) -> Result<Option<FunctionsRetainedJson>> {
+    let url = Route::FunctionsAllocRetained.to_url(port);
+    let response = agent.get(&url).call();
+
+    match response {
+        Ok(mut resp) => {
+            let retained: FunctionsRetainedJson = resp
+                .body_mut()
+                .read_json()
+                .map_err(|e| eyre::eyre!("JSON deserialization failed: {}", e))?;
+            Ok(Some(retained))
+        }
+        Err(ureq::Error::StatusCode(404)) => Ok(None),
+        Err(e) => Err(eyre::eyre!("HTTP request failed: {}", e)),
+    }
+}
+
 /// Fetches channels from the hotpath HTTP server
 #[hotpath::measure(log = true)]
 pub(crate) fn fetch_channels(agent: &ureq::Agent, port: u16) -> Result<ChannelsJson> {
@@ -182,6 +231,26 @@ pub(crate) fn fetch_threads(agent: &ureq::Agent, port: u16) -> Result<ThreadsJso
     Ok(threads)
 }
 
+/// Fetches Tokio runtime metrics from the hotpath HTTP server
+/// Returns None if no runtime is monitored (404 response)
+#[hotpath::measure(log = true)]
+pub(crate) fn fetch_runtime(agent: &ureq::Agent, port: u16) -> Result<Option<RuntimeJson>> {
+    let url = Route::Runtime.to_url(port);
+    let response = agent.get(&url).call();
+
+    match response {
+        Ok(mut resp) => {
+            let runtime: RuntimeJson = resp
+                .body_mut()
+                .read_json()
+                .map_err(|e| eyre::eyre!("JSON deserialization failed: {}", e))?;
+            Ok(Some(runtime))
+        }
+        Err(ureq::Error::StatusCode(404)) => Ok(None),
+        Err(e) => Err(eyre::eyre!("HTTP request failed: {}", e)),
+    }
+}
+
 /// Fetches futures from the hotpath HTTP server
 #[hotpath::measure(log = true)]
 pub(crate) fn fetch_futures(agent: &ureq::Agent, port: u16) -> Result<FuturesJson> {
@@ -213,3 +282,14 @@ pub(crate) fn fetch_future_calls(
         .map_err(|e| eyre::eyre!("JSON deserialization failed: {}", e))?;
     Ok(calls)
 }
+
+/// Clears stats on the profiled process with a `POST /reset` request
+#[hotpath::measure(log = true)]
+pub(crate) fn reset_stats(agent: &ureq::Agent, port: u16, scope: ResetScope) -> Result<()> {
+    let url = Route::Reset { scope }.to_url(port);
+    agent
+        .post(&url)
+        .send_empty()
+        .map_err(|e| eyre::eyre!("HTTP request failed: {}", e))?;
+    Ok(())
+}
@@ -24,6 +24,15 @@ const CLOSE_LABEL: &str = " | Close ";
 const CLOSE_KEYS: &str = "<i/o/h> ";
 const TOGGLE_CALLS_LABEL: &str = " | Toggle Calls ";
 const TOGGLE_CALLS_KEY: &str = "<o> ";
+const CALL_TREE_LABEL: &str = " | Call Tree ";
+const TABLE_VIEW_LABEL: &str = " | Table View ";
+const CALL_TREE_KEY: &str = "<t> ";
+const WINDOW_LABEL: &str = " | Window ";
+const WINDOW_KEY: &str = "<w> ";
+const TAG_LABEL: &str = " | Tag ";
+const TAG_KEY: &str = "<g> ";
+const RESET_LABEL: &str = " | Reset ";
+const RESET_KEY: &str = "<r> ";
 
 /// Renders the bottom controls bar showing context-aware keybindings
 #[hotpath::measure]
@@ -36,9 +45,40 @@ pub(crate) fn render_help_bar(
     streams_focus: StreamsFocus,
     functions_focus: FunctionsFocus,
     futures_focus: FuturesFocus,
+    show_call_tree: bool,
 ) {
-    let controls_line = if selected_tab == SelectedTab::Threads {
-        // Threads tab - simple controls, no logs
+    let controls_line = if selected_tab == SelectedTab::Timing && show_call_tree {
+        Line::from(vec![
+            NAV_KEYS_FULL.blue().bold(),
+            TABLE_VIEW_LABEL.into(),
+            CALL_TREE_KEY.blue().bold(),
+            RESET_LABEL.into(),
+            RESET_KEY.blue().bold(),
+            PAUSE_LABEL.into(),
+            PAUSE_KEY.blue().bold(),
+            QUIT_LABEL.into(),
+            QUIT_KEY.blue().bold(),
+        ])
+    } else if selected_tab == SelectedTab::Timing && functions_focus == FunctionsFocus::Functions {
+        Line::from(vec![
+            NAV_KEYS_FULL.blue().bold(),
+            TOGGLE_LOGS_LABEL.into(),
+            TOGGLE_LOGS_KEY.blue().bold(),
+            CALL_TREE_LABEL.into(),
+            CALL_TREE_KEY.blue().bold(),
+            WINDOW_LABEL.into(),
+            WINDOW_KEY.blue().bold(),
+            TAG_LABEL.into(),
+            TAG_KEY.blue().bold(),
+            RESET_LABEL.into(),
+            RESET_KEY.blue().bold(),
+            PAUSE_LABEL.into(),
+            PAUSE_KEY.blue().bold(),
+            QUIT_LABEL.into(),
+            QUIT_KEY.blue().bold(),
+        ])
+    } else if matches!(selected_tab, SelectedTab::Threads | SelectedTab::Runtime) {
+        // Threads and runtime tabs - simple controls, no logs
         Line::from(vec![
             NAV_KEYS_FULL.blue().bold(),
             PAUSE_LABEL.into(),
@@ -53,6 +93,8 @@ pub(crate) fn render_help_bar(
                 NAV_KEYS_FULL.blue().bold(),
                 TOGGLE_CALLS_LABEL.into(),
                 TOGGLE_CALLS_KEY.blue().bold(),
+                RESET_LABEL.into(),
+                RESET_KEY.blue().bold(),
                 PAUSE_LABEL.into(),
                 PAUSE_KEY.blue().bold(),
                 QUIT_LABEL.into(),
@@ -87,6 +129,8 @@ pub(crate) fn render_help_bar(
                 NAV_KEYS_FULL.blue().bold(),
                 TOGGLE_LOGS_LABEL.into(),
                 TOGGLE_LOGS_KEY.blue().bold(),
+                RESET_LABEL.into(),
+                RESET_KEY.blue().bold(),
                 PAUSE_LABEL.into(),
                 PAUSE_KEY.blue().bold(),
                 QUIT_LABEL.into(),
@@ -121,6 +165,8 @@ pub(crate) fn render_help_bar(
                 NAV_KEYS_FULL.blue().bold(),
                 TOGGLE_LOGS_LABEL.into(),
                 TOGGLE_LOGS_KEY.blue().bold(),
+                RESET_LABEL.into(),
+                RESET_KEY.blue().bold(),
                 PAUSE_LABEL.into(),
                 PAUSE_KEY.blue().bold(),
                 QUIT_LABEL.into(),
@@ -155,6 +201,12 @@ pub(crate) fn render_help_bar(
                 NAV_KEYS_FULL.blue().bold(),
                 TOGGLE_LOGS_LABEL.into(),
                 TOGGLE_LOGS_KEY.blue().bold(),
+                WINDOW_LABEL.into(),
+                WINDOW_KEY.blue().bold(),
+                TAG_LABEL.into(),
+                TAG_KEY.blue().bold(),
+                RESET_LABEL.into(),
+                RESET_KEY.blue().bold(),
                 PAUSE_LABEL.into(),
                 PAUSE_KEY.blue().bold(),
                 QUIT_LABEL.into(),
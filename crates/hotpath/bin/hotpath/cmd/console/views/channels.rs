@@ -1,11 +1,13 @@
+pub(crate) mod backpressure;
 pub(crate) mod inspect;
 pub(crate) mod logs;
+pub(crate) mod subscribers;
 
 use super::common_styles;
 use crate::cmd::console::app::ChannelsFocus;
 use crate::cmd::console::widgets::formatters::{queue_status, truncate_left};
-use hotpath::format_bytes;
 use hotpath::json::{ChannelState, ChannelType, SerializableChannelStats};
+use hotpath::{format_bytes, format_duration};
 use ratatui::{
     layout::{Constraint, Rect},
     style::{Color, Style},
@@ -38,6 +40,9 @@ pub(crate) fn render_channels_panel(
         Cell::from("Receive"),
         Cell::from("Queue"),
         Cell::from("Mem"),
+        Cell::from("P50"),
+        Cell::from("P95"),
+        Cell::from("P99"),
     ])
     .style(common_styles::HEADER_STYLE)
     .height(1);
@@ -59,10 +64,12 @@ pub(crate) fn render_channels_panel(
             };
 
             let mem_cell = match &stat.channel_type {
-                ChannelType::Unbounded => Cell::from("N/A"),
+                ChannelType::Unbounded | ChannelType::Watch => Cell::from("N/A"),
                 _ => Cell::from(format_bytes(stat.queued_bytes)),
             };
             let queue_cell = queue_status(stat.queued, &stat.channel_type, 8);
+            let latency_cell =
+                |ns: Option<u64>| Cell::from(ns.map_or_else(|| "-".to_string(), format_duration));
 
             Row::new(vec![
                 Cell::from(truncate_left(&stat.label, channel_width)),
@@ -72,18 +79,24 @@ pub(crate) fn render_channels_panel(
                 Cell::from(stat.received_count.to_string()),
                 queue_cell,
                 mem_cell,
+                latency_cell(stat.latency.map(|latency| latency.p50_ns)),
+                latency_cell(stat.latency.map(|latency| latency.p95_ns)),
+                latency_cell(stat.latency.map(|latency| latency.p99_ns)),
             ])
         })
         .collect();
 
     let widths = [
-        Constraint::Percentage(30), // Channel
-        Constraint::Percentage(14), // Type
-        Constraint::Percentage(10), // State
-        Constraint::Percentage(9),  // Sent
-        Constraint::Percentage(11), // Received
-        Constraint::Percentage(16), // Queue
-        Constraint::Percentage(10), // Mem
+        Constraint::Percentage(22), // Channel
+        Constraint::Percentage(11), // Type
+        Constraint::Percentage(8),  // State
+        Constraint::Percentage(7),  // Sent
+        Constraint::Percentage(8),  // Received
+        Constraint::Percentage(13), // Queue
+        Constraint::Percentage(7),  // Mem
+        Constraint::Percentage(8),  // P50
+        Constraint::Percentage(8),  // P95
+        Constraint::Percentage(8),  // P99
     ];
 
     let table_block = if show_logs {
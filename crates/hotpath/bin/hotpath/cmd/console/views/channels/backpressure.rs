@@ -0,0 +1,58 @@
+use super::super::common_styles;
+use hotpath::format_duration;
+use hotpath::json::ChannelBackpressure;
+use ratatui::{
+    layout::{Constraint, Rect},
+    style::{Color, Style},
+    symbols::border,
+    widgets::{Block, Cell, Row, Table},
+    Frame,
+};
+
+/// Renders the time senders of the selected channel spent blocked on a full queue
+pub(crate) fn render_backpressure_panel(
+    backpressure: &ChannelBackpressure,
+    area: Rect,
+    frame: &mut Frame,
+) {
+    let block = Block::bordered()
+        .title(" Backpressure ")
+        .border_set(border::PLAIN)
+        .border_style(common_styles::UNFOCUSED_BORDER_STYLE);
+
+    let header = Row::new(vec!["Blocked", "Total", "P50", "P95", "P99", "Try Fails"])
+        .style(common_styles::HEADER_STYLE)
+        .height(1);
+
+    let try_fails_style = if backpressure.try_send_failures > 0 {
+        Style::default().fg(Color::Red)
+    } else {
+        Style::default()
+    };
+
+    let row = Row::new(vec![
+        Cell::from(backpressure.blocked_count.to_string()),
+        Cell::from(format_duration(backpressure.blocked_total_ns))
+            .style(Style::default().fg(Color::Yellow)),
+        Cell::from(format_duration(backpressure.p50_ns)),
+        Cell::from(format_duration(backpressure.p95_ns)),
+        Cell::from(format_duration(backpressure.p99_ns)),
+        Cell::from(backpressure.try_send_failures.to_string()).style(try_fails_style),
+    ]);
+
+    let widths = [
+        Constraint::Length(8),  // Blocked
+        Constraint::Length(10), // Total
+        Constraint::Length(10), // P50
+        Constraint::Length(10), // P95
+        Constraint::Length(10), // P99
+        Constraint::Min(9),     // Try Fails
+    ];
+
+    let table = Table::new(vec![row], widths)
+        .header(header)
+        .block(block)
+        .column_spacing(1);
+
+    frame.render_widget(table, area);
+}
@@ -0,0 +1,64 @@
+use super::super::common_styles;
+use hotpath::json::{ChannelState, SerializableSubscriberStats};
+use ratatui::{
+    layout::{Constraint, Rect},
+    style::{Color, Style},
+    symbols::border,
+    widgets::{Block, Cell, Row, Table},
+    Frame,
+};
+
+/// Renders the receivers of the selected broadcast or watch channel
+pub(crate) fn render_subscribers_panel(
+    subscribers: &[SerializableSubscriberStats],
+    area: Rect,
+    frame: &mut Frame,
+) {
+    let block = Block::bordered()
+        .title(format!(" Subscribers ({}) ", subscribers.len()))
+        .border_set(border::PLAIN)
+        .border_style(common_styles::UNFOCUSED_BORDER_STYLE);
+
+    let header = Row::new(vec!["Subscriber", "State", "Receive", "Queue", "Lagged"])
+        .style(common_styles::HEADER_STYLE)
+        .height(1);
+
+    let rows: Vec<Row> = subscribers
+        .iter()
+        .map(|sub| {
+            let state_style = match sub.state {
+                ChannelState::Full => Style::default().fg(Color::Red),
+                ChannelState::Closed => Style::default().fg(Color::Yellow),
+                _ => Style::default().fg(Color::Green),
+            };
+            let lagged_style = if sub.lagged_count > 0 {
+                Style::default().fg(Color::Red)
+            } else {
+                Style::default()
+            };
+
+            Row::new(vec![
+                Cell::from(sub.label.clone()),
+                Cell::from(sub.state.to_string()).style(state_style),
+                Cell::from(sub.received_count.to_string()),
+                Cell::from(sub.queued.to_string()),
+                Cell::from(sub.lagged_count.to_string()).style(lagged_style),
+            ])
+        })
+        .collect();
+
+    let widths = [
+        Constraint::Min(12),    // Subscriber
+        Constraint::Length(8),  // State
+        Constraint::Length(10), // Receive
+        Constraint::Length(8),  // Queue
+        Constraint::Length(8),  // Lagged
+    ];
+
+    let table = Table::new(rows, widths)
+        .header(header)
+        .block(block)
+        .column_spacing(1);
+
+    frame.render_widget(table, area);
+}
@@ -3,6 +3,7 @@ pub(crate) mod logs;
 
 use super::super::app::{App, FunctionsFocus};
 use super::common_styles;
+use crate::cmd::console::widgets::formatters::{format_tag, format_window};
 use ratatui::{
     layout::{Alignment, Constraint, Rect},
     style::{Color, Modifier, Style},
@@ -15,8 +16,11 @@ use ratatui::{
 #[hotpath::measure]
 pub(crate) fn render_functions_table(frame: &mut Frame, app: &mut App, area: Rect) {
     let title = format!(
-        " {} - {} ",
-        app.memory_functions.caller_name, app.memory_functions.description
+        " {} - {}{}{} ",
+        app.memory_functions.caller_name,
+        app.memory_functions.description,
+        format_window(app.functions_window),
+        format_tag(app.functions_tag.as_deref())
     );
 
     // Check if memory profiling is available
@@ -56,22 +60,13 @@ pub(crate) fn render_functions_table(frame: &mut Frame, app: &mut App, area: Rec
         return;
     }
 
-    let header_cells = vec![
-        "Function".to_string(),
-        "Calls".to_string(),
-        "Avg".to_string(),
-    ]
-    .into_iter()
-    .chain(
-        app.memory_functions
-            .percentiles
-            .iter()
-            .map(|p| format!("P{}", p))
-            .collect::<Vec<_>>(),
-    )
-    .chain(vec!["Total".to_string(), "% Total".to_string()])
-    .map(|h| Cell::from(h).style(common_styles::HEADER_STYLE_CYAN))
-    .collect::<Vec<_>>();
+    let headers = app.memory_functions.headers();
+    let header_cells = headers
+        .iter()
+        .cloned()
+        .chain(app.memory_retained.as_ref().map(|_| "Retained".to_string()))
+        .map(|h| Cell::from(h).style(common_styles::HEADER_STYLE_CYAN))
+        .collect::<Vec<_>>();
 
     let header = Row::new(header_cells).height(1);
 
@@ -83,11 +78,20 @@ pub(crate) fn render_functions_table(frame: &mut Frame, app: &mut App, area: Rec
         .map(|s| s + 1)
         .unwrap_or(0);
 
+    let retained = app.memory_retained.as_ref();
     let rows = entries.iter().map(|(function_name, metrics)| {
         let short_name = hotpath::shorten_function_name(function_name);
 
         let cells = std::iter::once(Cell::from(short_name))
             .chain(metrics.iter().map(|m| Cell::from(format!("{}", m))))
+            .chain(retained.map(|retained| {
+                Cell::from(
+                    retained
+                        .get(function_name)
+                        .map(|bytes| hotpath::format_bytes(*bytes))
+                        .unwrap_or_else(|| "-".to_string()),
+                )
+            }))
             .collect::<Vec<_>>();
 
         Row::new(cells)
@@ -96,26 +100,18 @@ pub(crate) fn render_functions_table(frame: &mut Frame, app: &mut App, area: Rec
     let show_logs = app.show_function_logs;
     let focus = app.functions_focus;
 
-    let num_percentiles = app.memory_functions.percentiles.len();
-
-    let function_pct: u16 = 35;
+    let function_pct: u16 = 30;
     let remaining_pct: u16 = 100 - function_pct;
-    let num_other_cols = (4 + num_percentiles) as u16; // Calls, Avg, P95s, Total, % Total
+    let num_retained_cols = app.memory_retained.is_some() as usize;
+    // Calls, Avg, P95s, Total, Reallocs, Grown, % Total, Retained
+    let num_other_cols = (headers.len() - 1 + num_retained_cols) as u16;
     let col_pct: u16 = remaining_pct / num_other_cols;
 
     let table = Table::new(
         rows,
         vec![Constraint::Percentage(function_pct)] // Function
             .into_iter()
-            .chain(vec![
-                Constraint::Percentage(col_pct), // Calls
-                Constraint::Percentage(col_pct), // Avg
-            ])
-            .chain((0..num_percentiles).map(|_| Constraint::Percentage(col_pct))) // P95, etc
-            .chain(vec![
-                Constraint::Percentage(col_pct), // Total
-                Constraint::Percentage(col_pct), // % Total
-            ])
+            .chain((0..num_other_cols).map(|_| Constraint::Percentage(col_pct)))
             .collect::<Vec<_>>(),
     )
     .header(header)
@@ -1,15 +1,59 @@
 use super::super::super::app::InspectedFunctionLog;
+use hotpath::json::SizeClassJson;
 use ratatui::{
-    layout::Rect,
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Style},
     symbols::border,
-    text::Line,
+    text::{Line, Span},
     widgets::{Block, Clear, Paragraph, Wrap},
     Frame,
 };
 
+const SIZE_CLASS_BAR_WIDTH: u64 = 30;
+
+fn size_class_label(class: &SizeClassJson) -> String {
+    match class.max_bytes {
+        Some(max_bytes) => format!(
+            "{} - {}",
+            hotpath::format_bytes(class.min_bytes),
+            hotpath::format_bytes(max_bytes)
+        ),
+        None => format!(">= {}", hotpath::format_bytes(class.min_bytes)),
+    }
+}
+
+/// One line per size class with the allocation count and a bar relative to the largest class
+fn size_class_lines(size_classes: &[SizeClassJson]) -> Vec<Line<'static>> {
+    let max_count = size_classes
+        .iter()
+        .map(|c| c.count)
+        .max()
+        .unwrap_or(0)
+        .max(1);
+
+    std::iter::once(Line::from(Span::styled(
+        " Allocations by size (all calls):",
+        Style::default().fg(Color::DarkGray),
+    )))
+    .chain(size_classes.iter().map(|class| {
+        let bar_len = (class.count * SIZE_CLASS_BAR_WIDTH).div_ceil(max_count) as usize;
+        Line::from(vec![
+            Span::raw(format!(
+                " {:>17} {:>10} ",
+                size_class_label(class),
+                class.count
+            )),
+            Span::styled("█".repeat(bar_len), Style::default().fg(Color::Yellow)),
+        ])
+    }))
+    .collect()
+}
+
 /// Renders a centered popup displaying the full result value for a function log entry (memory mode)
+/// and the allocation size classes of the function
 pub(crate) fn render_inspect_popup(
     entry: &InspectedFunctionLog,
+    size_classes: &[SizeClassJson],
     area: Rect,
     frame: &mut Frame,
     total_elapsed: u64,
@@ -57,6 +101,19 @@ pub(crate) fn render_inspect_popup(
 
     frame.render_widget(block, popup_area);
 
+    let inner_area = if size_classes.is_empty() {
+        inner_area
+    } else {
+        let histogram = size_class_lines(size_classes);
+        let chunks = Layout::vertical([
+            Constraint::Length(histogram.len() as u16 + 1),
+            Constraint::Min(0),
+        ])
+        .split(inner_area);
+        frame.render_widget(Paragraph::new(histogram), chunks[0]);
+        chunks[1]
+    };
+
     let text_lines: Vec<Line> = result_text
         .lines()
         .flat_map(|line| {
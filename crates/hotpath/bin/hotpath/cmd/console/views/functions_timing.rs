@@ -1,8 +1,10 @@
 pub(crate) mod inspect;
 pub(crate) mod logs;
+pub(crate) mod tree;
 
 use super::super::app::{App, FunctionsFocus};
 use super::common_styles;
+use crate::cmd::console::widgets::formatters::{format_tag, format_window};
 use ratatui::{
     layout::{Constraint, Rect},
     style::Style,
@@ -15,26 +17,20 @@ use ratatui::{
 #[hotpath::measure]
 pub(crate) fn render_functions_table(frame: &mut Frame, app: &mut App, area: Rect) {
     let title = format!(
-        " {} - {} ",
-        app.timing_functions.caller_name, app.timing_functions.description
+        " {} - {}{}{} ",
+        app.timing_functions.caller_name,
+        app.timing_functions.description,
+        format_window(app.functions_window),
+        format_tag(app.functions_tag.as_deref())
     );
 
-    let header_cells = vec![
-        "Function".to_string(),
-        "Calls".to_string(),
-        "Avg".to_string(),
-    ]
-    .into_iter()
-    .chain(
-        app.timing_functions
-            .percentiles
-            .iter()
-            .map(|p| format!("P{}", p))
-            .collect::<Vec<_>>(),
-    )
-    .chain(vec!["Total".to_string(), "% Total".to_string()])
-    .map(|h| Cell::from(h).style(common_styles::HEADER_STYLE_CYAN))
-    .collect::<Vec<_>>();
+    let headers = app.timing_functions.headers();
+    let num_other_cols = (headers.len() - 1) as u16; // Calls, Avg, P95s, Total, Self, % Total
+
+    let header_cells = headers
+        .into_iter()
+        .map(|h| Cell::from(h).style(common_styles::HEADER_STYLE_CYAN))
+        .collect::<Vec<_>>();
 
     let header = Row::new(header_cells).height(1);
 
@@ -59,26 +55,14 @@ pub(crate) fn render_functions_table(frame: &mut Frame, app: &mut App, area: Rec
     let show_logs = app.show_function_logs;
     let focus = app.functions_focus;
 
-    let num_percentiles = app.timing_functions.percentiles.len();
-
     let function_pct: u16 = 35;
     let remaining_pct: u16 = 100 - function_pct;
-    let num_other_cols = (4 + num_percentiles) as u16; // Calls, Avg, P95s, Total, % Total
     let col_pct: u16 = remaining_pct / num_other_cols;
 
     let table = Table::new(
         rows,
-        vec![Constraint::Percentage(function_pct)] // Function
-            .into_iter()
-            .chain(vec![
-                Constraint::Percentage(col_pct), // Calls
-                Constraint::Percentage(col_pct), // Avg
-            ])
-            .chain((0..num_percentiles).map(|_| Constraint::Percentage(col_pct))) // P95, etc
-            .chain(vec![
-                Constraint::Percentage(col_pct), // Total
-                Constraint::Percentage(col_pct), // % Total
-            ])
+        std::iter::once(Constraint::Percentage(function_pct)) // Function
+            .chain((0..num_other_cols).map(|_| Constraint::Percentage(col_pct)))
             .collect::<Vec<_>>(),
     )
     .header(header)
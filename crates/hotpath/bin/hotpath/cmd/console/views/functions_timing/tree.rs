@@ -0,0 +1,102 @@
+use super::super::super::app::App;
+use super::super::common_styles;
+use hotpath::CallTreeNode;
+use ratatui::{
+    layout::{Constraint, Rect},
+    symbols::border,
+    text::{Line, Span},
+    widgets::{Block, Cell, Paragraph, Row, Table},
+    Frame,
+};
+
+/// Flattens the call tree into rows of (indentation prefix, node) in display order
+fn flatten_call_tree<'a>(
+    nodes: &'a [CallTreeNode],
+    prefix: &str,
+    rows: &mut Vec<(String, &'a CallTreeNode)>,
+) {
+    for (i, node) in nodes.iter().enumerate() {
+        let last = i == nodes.len() - 1;
+        let branch = if last { "└─ " } else { "├─ " };
+        rows.push((format!("{}{}", prefix, branch), node));
+
+        let child_prefix = format!("{}{}", prefix, if last { "   " } else { "│  " });
+        flatten_call_tree(&node.children, &child_prefix, rows);
+    }
+}
+
+#[hotpath::measure]
+pub(crate) fn render_call_tree(frame: &mut Frame, app: &mut App, area: Rect) {
+    let title = format!(" {} - Call tree ", app.call_tree.caller_name);
+
+    if app.call_tree.roots.is_empty() {
+        let empty_text = vec![
+            Line::from(""),
+            Line::from("No call tree data yet").centered(),
+        ];
+        let block = Block::bordered()
+            .title(Span::styled(title, common_styles::TITLE_STYLE_YELLOW))
+            .border_set(border::THICK);
+        frame.render_widget(Paragraph::new(empty_text).block(block), area);
+        return;
+    }
+
+    let mut rows = Vec::new();
+    flatten_call_tree(&app.call_tree.roots, "", &mut rows);
+
+    let total_rows = rows.len();
+    let position = app
+        .call_tree_table_state
+        .selected()
+        .map(|s| s + 1)
+        .unwrap_or(0);
+
+    let header = Row::new(
+        ["Function", "Calls", "Total", "Self", "Self %"]
+            .into_iter()
+            .map(|h| Cell::from(h).style(common_styles::HEADER_STYLE_CYAN)),
+    )
+    .height(1);
+
+    let table_rows = rows.iter().map(|(prefix, node)| {
+        let self_pct = if node.total_ns > 0 {
+            node.self_ns as f64 / node.total_ns as f64 * 100.0
+        } else {
+            0.0
+        };
+
+        Row::new(vec![
+            Cell::from(format!(
+                "{}{}",
+                prefix,
+                hotpath::shorten_function_name(&node.name)
+            )),
+            Cell::from(node.calls.to_string()),
+            Cell::from(hotpath::format_duration(node.total_ns)),
+            Cell::from(hotpath::format_duration(node.self_ns)),
+            Cell::from(format!("{:.2}%", self_pct)),
+        ])
+    });
+
+    let table = Table::new(
+        table_rows,
+        [
+            Constraint::Percentage(52),
+            Constraint::Percentage(12),
+            Constraint::Percentage(12),
+            Constraint::Percentage(12),
+            Constraint::Percentage(12),
+        ],
+    )
+    .header(header)
+    .block(
+        Block::bordered()
+            .title(format!(" [{}/{}] ", position, total_rows))
+            .title(Span::styled(title, common_styles::TITLE_STYLE_YELLOW))
+            .border_set(border::THICK),
+    )
+    .row_highlight_style(common_styles::SELECTED_ROW_STYLE)
+    .highlight_symbol(">> ");
+
+    frame.render_stateful_widget(table, area, &mut app.call_tree_table_state);
+}
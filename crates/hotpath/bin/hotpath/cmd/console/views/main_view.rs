@@ -1,14 +1,19 @@
 use super::super::app::{
     App, ChannelsFocus, FunctionsFocus, FuturesFocus, SelectedTab, StreamsFocus,
 };
-use super::channels::{inspect, logs as channel_logs};
+use super::channels::{
+    backpressure as channel_backpressure, inspect, logs as channel_logs,
+    subscribers as channel_subscribers,
+};
 use super::functions_memory::{inspect as memory_inspect, logs as memory_logs};
-use super::functions_timing::{inspect as timing_inspect, logs as timing_logs};
+use super::functions_timing::{inspect as timing_inspect, logs as timing_logs, tree as call_tree};
 use super::futures::{calls as future_calls, inspect as future_inspect};
 use super::streams::{inspect as stream_inspect, logs as stream_logs};
 use super::{
-    bottom_bar, channels, functions_memory, functions_timing, futures, streams, threads, top_bar,
+    bottom_bar, channels, functions_memory, functions_timing, futures, runtime, streams, threads,
+    top_bar,
 };
+use hotpath::json::SerializableChannelStats;
 use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style, Stylize},
@@ -31,11 +36,13 @@ pub(crate) fn render_ui(frame: &mut Frame, app: &mut App) {
         .split(frame.area());
 
     let has_data = match app.selected_tab {
+        SelectedTab::Timing if app.show_call_tree => !app.call_tree.roots.is_empty(),
         SelectedTab::Timing => !app.timing_functions.data.0.is_empty(),
         SelectedTab::Memory => !app.memory_functions.data.0.is_empty(),
         SelectedTab::Channels => !app.channels.channels.is_empty(),
         SelectedTab::Streams => !app.streams.streams.is_empty(),
         SelectedTab::Threads => !app.threads.threads.is_empty(),
+        SelectedTab::Runtime => !app.runtime.workers.is_empty(),
         SelectedTab::Futures => !app.futures.futures.is_empty(),
     };
 
@@ -53,7 +60,9 @@ pub(crate) fn render_ui(frame: &mut Frame, app: &mut App) {
     // Render content based on selected tab
     match app.selected_tab {
         SelectedTab::Timing => {
-            if app.show_function_logs {
+            if app.show_call_tree {
+                call_tree::render_call_tree(frame, app, main_chunks[2]);
+            } else if app.show_function_logs {
                 let content_chunks = Layout::default()
                     .direction(Direction::Horizontal)
                     .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
@@ -108,6 +117,9 @@ pub(crate) fn render_ui(frame: &mut Frame, app: &mut App) {
                     if let Some(ref inspected_log) = app.inspected_function_log {
                         memory_inspect::render_inspect_popup(
                             inspected_log,
+                            app.current_function_logs
+                                .as_ref()
+                                .map_or(&[], |logs| logs.size_classes.as_slice()),
                             main_chunks[2],
                             frame,
                             app.memory_functions.total_elapsed,
@@ -127,6 +139,9 @@ pub(crate) fn render_ui(frame: &mut Frame, app: &mut App) {
         SelectedTab::Threads => {
             render_threads_view(frame, app, main_chunks[2]);
         }
+        SelectedTab::Runtime => {
+            render_runtime_view(frame, app, main_chunks[2]);
+        }
         SelectedTab::Futures => {
             render_futures_view(frame, app, main_chunks[2]);
         }
@@ -140,6 +155,7 @@ pub(crate) fn render_ui(frame: &mut Frame, app: &mut App) {
         app.streams_focus,
         app.functions_focus,
         app.futures_focus,
+        app.show_call_tree,
     );
 }
 
@@ -212,10 +228,39 @@ fn render_channels_view(frame: &mut Frame, app: &mut App, area: Rect) {
 
     // Render logs panel if visible
     if let Some(logs_area) = logs_area {
-        let channel_label = app
+        let selected_stat = app
             .channels_table_state
             .selected()
-            .and_then(|i| stats.get(i))
+            .and_then(|i| stats.get(i));
+
+        // Broadcast and watch channels list their receivers above the logs
+        let logs_area = match selected_stat {
+            Some(stat) if !stat.subscribers.is_empty() => {
+                let subscribers_height =
+                    (stat.subscribers.len() as u16 + 3).min(logs_area.height / 2);
+                let chunks = Layout::default()
+                    .direction(Direction::Vertical)
+                    .constraints([Constraint::Length(subscribers_height), Constraint::Min(0)])
+                    .split(logs_area);
+                channel_subscribers::render_subscribers_panel(&stat.subscribers, chunks[0], frame);
+                chunks[1]
+            }
+            // Bounded channels whose senders waited for space show the wait times
+            Some(SerializableChannelStats {
+                backpressure: Some(backpressure),
+                ..
+            }) => {
+                let chunks = Layout::default()
+                    .direction(Direction::Vertical)
+                    .constraints([Constraint::Length(4), Constraint::Min(0)])
+                    .split(logs_area);
+                channel_backpressure::render_backpressure_panel(backpressure, chunks[0], frame);
+                chunks[1]
+            }
+            _ => logs_area,
+        };
+
+        let channel_label = selected_stat
             .map(|stat| {
                 if stat.label.is_empty() {
                     stat.id.to_string()
@@ -438,6 +483,48 @@ fn render_threads_view(frame: &mut Frame, app: &mut App, area: Rect) {
     );
 }
 
+#[hotpath::measure]
+fn render_runtime_view(frame: &mut Frame, app: &mut App, area: Rect) {
+    if let Some(ref error_msg) = app.error_message {
+        if app.runtime.workers.is_empty() {
+            let error_text = vec![
+                Line::from(""),
+                Line::from("Error").red().bold().centered(),
+                Line::from(""),
+                Line::from(error_msg.as_str()).red().centered(),
+                Line::from(""),
+                Line::from(format!(
+                    "Make sure the metrics server is running on http://localhost:{}",
+                    app.metrics_port
+                ))
+                .yellow()
+                .centered(),
+            ];
+
+            let block = Block::bordered().border_set(border::THICK);
+            frame.render_widget(Paragraph::new(error_text).block(block), area);
+            return;
+        }
+    }
+
+    if app.runtime.workers.is_empty() {
+        let empty_text = vec![
+            Line::from(""),
+            Line::from("No runtime statistics found")
+                .yellow()
+                .centered(),
+            Line::from(""),
+            Line::from("Make sure the profiled program runs inside a Tokio runtime").centered(),
+        ];
+
+        let block = Block::bordered().border_set(border::THICK);
+        frame.render_widget(Paragraph::new(empty_text).block(block), area);
+        return;
+    }
+
+    runtime::render_runtime_panel(&app.runtime, area, frame, &mut app.runtime_table_state);
+}
+
 #[hotpath::measure]
 fn render_futures_view(frame: &mut Frame, app: &mut App, area: Rect) {
     let stats = &app.futures.futures;
@@ -572,6 +659,7 @@ fn render_tabs(frame: &mut Frame, area: ratatui::layout::Rect, selected_tab: Sel
         create_tab_line(SelectedTab::Channels),
         create_tab_line(SelectedTab::Streams),
         create_tab_line(SelectedTab::Threads),
+        create_tab_line(SelectedTab::Runtime),
     ];
 
     let selected_index = (selected_tab.number() - 1) as usize;
@@ -0,0 +1,118 @@
+use super::common_styles;
+use hotpath::json::RuntimeJson;
+use ratatui::{
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Style},
+    symbols::border,
+    text::{Line, Span},
+    widgets::{Block, Cell, HighlightSpacing, Paragraph, Row, Table, TableState},
+    Frame,
+};
+
+fn format_optional(value: Option<usize>) -> String {
+    value
+        .map(|v| v.to_string())
+        .unwrap_or_else(|| "-".to_string())
+}
+
+/// Renders Tokio runtime totals and the per-worker metrics table
+#[hotpath::measure]
+pub(crate) fn render_runtime_panel(
+    runtime: &RuntimeJson,
+    area: Rect,
+    frame: &mut Frame,
+    table_state: &mut TableState,
+) {
+    let chunks = Layout::vertical([Constraint::Length(1), Constraint::Min(0)]).split(area);
+    let info_area = chunks[0];
+    let table_area = chunks[1];
+
+    let value = |v: String| Span::styled(v, Style::default().fg(Color::Yellow));
+
+    let mut spans = vec![
+        Span::raw(" Flavor: "),
+        value(runtime.flavor.clone()),
+        Span::raw("  Alive Tasks: "),
+        value(runtime.num_alive_tasks.to_string()),
+        Span::raw("  Global Queue: "),
+        value(runtime.global_queue_depth.to_string()),
+    ];
+
+    if runtime.num_blocking_threads.is_some() {
+        spans.push(Span::raw("  Blocking Threads: "));
+        spans.push(value(format!(
+            "{} ({} idle)",
+            format_optional(runtime.num_blocking_threads),
+            format_optional(runtime.num_idle_blocking_threads)
+        )));
+        spans.push(Span::raw("  Blocking Queue: "));
+        spans.push(value(format_optional(runtime.blocking_queue_depth)));
+    } else {
+        spans.push(Span::raw("  "));
+        spans.push(Span::styled(
+            "Build with --cfg tokio_unstable to track queues and the blocking pool",
+            Style::default().fg(Color::DarkGray),
+        ));
+    }
+
+    frame.render_widget(Paragraph::new(Line::from(spans)), info_area);
+
+    let header = Row::new(vec![
+        Cell::from("Worker"),
+        Cell::from("Busy %"),
+        Cell::from("Busy"),
+        Cell::from("Parks"),
+        Cell::from("Park/Unpark"),
+        Cell::from("Local Queue"),
+    ])
+    .style(common_styles::HEADER_STYLE)
+    .height(1);
+
+    let rows: Vec<Row> = runtime
+        .workers
+        .iter()
+        .map(|worker| {
+            let busy_percent_str = match worker.busy_percent {
+                Some(pct) => format!("{:.1}%", pct),
+                None => "-".to_string(),
+            };
+
+            Row::new(vec![
+                Cell::from(worker.index.to_string()),
+                Cell::from(busy_percent_str),
+                Cell::from(format!("{:.2}s", worker.busy_total)),
+                Cell::from(worker.park_count.to_string()),
+                Cell::from(worker.park_unpark_count.to_string()),
+                Cell::from(format_optional(worker.local_queue_depth)),
+            ])
+        })
+        .collect();
+
+    let widths = [
+        Constraint::Percentage(12), // Worker
+        Constraint::Percentage(14), // Busy %
+        Constraint::Percentage(16), // Busy
+        Constraint::Percentage(18), // Parks
+        Constraint::Percentage(20), // Park/Unpark
+        Constraint::Percentage(20), // Local Queue
+    ];
+
+    let selected_index = table_state.selected().unwrap_or(0);
+    let table_block = Block::bordered()
+        .title(format!(
+            " [{}/{}] ",
+            (selected_index + 1).min(runtime.workers.len()),
+            runtime.num_workers
+        ))
+        .border_set(border::THICK);
+
+    let table = Table::new(rows, widths)
+        .header(header)
+        .block(table_block)
+        .column_spacing(1)
+        .row_highlight_style(common_styles::SELECTED_ROW_STYLE)
+        .highlight_symbol(">> ")
+        .highlight_spacing(HighlightSpacing::Always);
+
+    frame.render_stateful_widget(table, table_area, table_state);
+}
@@ -4,6 +4,7 @@ use ratatui::{
     style::{Color, Style},
     widgets::Cell,
 };
+use std::time::Duration;
 
 pub(crate) fn truncate_left(s: &str, max_len: usize) -> String {
     if s.len() <= max_len {
@@ -30,9 +31,9 @@ pub(crate) fn queue_status(
     _width: usize,
 ) -> Cell<'static> {
     let capacity = match channel_type {
-        ChannelType::Bounded(cap) => Some(*cap),
+        ChannelType::Bounded(cap) | ChannelType::Broadcast(cap) => Some(*cap),
         ChannelType::Oneshot => Some(1),
-        ChannelType::Unbounded => None,
+        ChannelType::Unbounded | ChannelType::Watch => None,
     };
 
     match capacity {
@@ -85,6 +86,25 @@ pub(crate) fn format_timestamp(timestamp_ns: u64) -> String {
     format!("{:02}:{:02}.{:03}", minutes, seconds, millis)
 }
 
+/// Formats the rolling window of function stats for a table title, e.g. " [last 1m]"
+pub(crate) fn format_window(window: Option<Duration>) -> String {
+    match window {
+        Some(window) if window.as_secs() >= 60 && window.as_secs() % 60 == 0 => {
+            format!(" [last {}m]", window.as_secs() / 60)
+        }
+        Some(window) => format!(" [last {}s]", window.as_secs()),
+        None => String::new(),
+    }
+}
+
+/// Formats the tag function stats are limited to for a table title, e.g. " [tag: checkout]"
+pub(crate) fn format_tag(tag: Option<&str>) -> String {
+    match tag {
+        Some(tag) => format!(" [tag: {}]", tag),
+        None => String::new(),
+    }
+}
+
 /// Formats a time difference in nanoseconds as "now", "1s ago", "1m ago", "1h ago", etc.
 pub(crate) fn format_time_ago(nanos_ago: u64) -> String {
     const NANOS_PER_SEC: u64 = 1_000_000_000;
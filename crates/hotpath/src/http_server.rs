@@ -1,9 +1,12 @@
+use crate::functions::window::MAX_WINDOW;
 use crate::functions::{
     get_function_logs_alloc, get_function_logs_timing, get_functions_alloc_json,
-    get_functions_timing_json,
+    get_functions_alloc_retained_json, get_functions_timing_folded, get_functions_timing_json,
+    get_functions_timing_tree_json,
 };
-use crate::json::Route;
+use crate::json::{Route, RouteError};
 use std::sync::LazyLock;
+use std::time::Duration;
 
 pub(crate) static HTTP_SERVER_PORT: LazyLock<u16> = LazyLock::new(|| {
     std::env::var("HOTPATH_HTTP_PORT")
@@ -28,7 +31,7 @@ use serde::Serialize;
 use std::fmt::Display;
 use std::sync::OnceLock;
 use std::thread;
-use tiny_http::{Header, Request, Response, Server};
+use tiny_http::{Header, Method, Request, Response, Server};
 
 static HTTP_SERVER_STARTED: OnceLock<()> = OnceLock::new();
 
@@ -44,6 +47,8 @@ pub(crate) fn start_metrics_server_once(port: u16) {
 fn start_metrics_server(port: u16) {
     #[cfg(feature = "threads")]
     crate::threads::init_threads_monitoring();
+    #[cfg(feature = "tokio")]
+    crate::runtime::init_runtime_monitoring();
 
     thread::Builder::new()
             .name("hp-server".into())
@@ -71,17 +76,39 @@ fn start_metrics_server(port: u16) {
 fn handle_request(request: Request) {
     let path = request.url();
 
-    match path.parse::<Route>() {
+    match Route::from_path(path) {
         Ok(Route::FunctionsTiming) => {
-            let metrics = get_functions_timing_json();
+            let metrics = get_functions_timing_json(None, None);
             respond_json(request, &metrics);
         }
-        Ok(Route::FunctionsAlloc) => match get_functions_alloc_json() {
-            Some(metrics) => respond_json(request, &metrics),
+        Ok(Route::FunctionsTimingQuery { window, tag }) => {
+            if let Err(msg) = check_window(window) {
+                return respond_error(request, 400, &msg);
+            }
+            let metrics = get_functions_timing_json(window, tag);
+            respond_json(request, &metrics);
+        }
+        Ok(Route::FunctionsTimingTree) => {
+            let tree = get_functions_timing_tree_json();
+            respond_json(request, &tree);
+        }
+        Ok(Route::FunctionsTimingFolded) => {
+            let folded = get_functions_timing_folded();
+            respond_text(request, folded);
+        }
+        Ok(Route::FunctionsAlloc) => respond_functions_alloc(request, None, None),
+        Ok(Route::FunctionsAllocQuery { window, tag }) => {
+            if let Err(msg) = check_window(window) {
+                return respond_error(request, 400, &msg);
+            }
+            respond_functions_alloc(request, window, tag);
+        }
+        Ok(Route::FunctionsAllocRetained) => match get_functions_alloc_retained_json() {
+            Some(retained) => respond_json(request, &retained),
             None => respond_error(
                 request,
                 404,
-                "Memory profiling not available - enable hotpath-alloc feature",
+                "Retained memory tracking not available - enable hotpath-alloc-retained feature",
             ),
         },
         Ok(Route::Channels) => {
@@ -141,7 +168,58 @@ fn handle_request(request: Request) {
                 "Thread monitoring not available - enable threads feature",
             );
         }
-        Err(_) => respond_error(request, 404, "Not found"),
+        #[cfg(feature = "tokio")]
+        Ok(Route::Runtime) => match crate::runtime::get_runtime_json() {
+            Some(runtime) => respond_json(request, &runtime),
+            None => respond_error(
+                request,
+                404,
+                "Runtime metrics not available - no Tokio runtime is monitored",
+            ),
+        },
+        #[cfg(not(feature = "tokio"))]
+        Ok(Route::Runtime) => {
+            respond_error(
+                request,
+                404,
+                "Runtime metrics not available - enable tokio feature",
+            );
+        }
+        Ok(Route::Metrics) => {
+            let metrics = crate::openmetrics::render();
+            respond_with_content_type(request, metrics, crate::openmetrics::CONTENT_TYPE);
+        }
+        Ok(Route::Reset { scope }) => {
+            if *request.method() != Method::Post {
+                return respond_error(request, 405, "Method not allowed - use POST");
+            }
+            crate::reset::reset_scope(scope);
+            let _ = request.respond(Response::empty(204));
+        }
+        Err(RouteError::BadQuery(msg)) => respond_error(request, 400, &msg),
+        Err(RouteError::NotFound) => respond_error(request, 404, "Not found"),
+    }
+}
+
+fn check_window(window: Option<Duration>) -> Result<(), String> {
+    match window {
+        Some(window) if window.is_zero() => Err("window must be greater than 0".to_string()),
+        Some(window) if window > MAX_WINDOW => Err(format!(
+            "window must be at most {}",
+            crate::json::format_window(MAX_WINDOW)
+        )),
+        _ => Ok(()),
+    }
+}
+
+fn respond_functions_alloc(request: Request, window: Option<Duration>, tag: Option<String>) {
+    match get_functions_alloc_json(window, tag) {
+        Some(metrics) => respond_json(request, &metrics),
+        None => respond_error(
+            request,
+            404,
+            "Memory profiling not available - enable hotpath-alloc feature",
+        ),
     }
 }
 
@@ -159,6 +237,18 @@ fn respond_json<T: Serialize>(request: Request, value: &T) {
     }
 }
 
+fn respond_text(request: Request, body: String) {
+    respond_with_content_type(request, body, "text/plain");
+}
+
+fn respond_with_content_type(request: Request, body: String, content_type: &str) {
+    let mut response = Response::from_string(body);
+    response.add_header(
+        Header::from_bytes(b"Content-Type".as_slice(), content_type.as_bytes()).unwrap(),
+    );
+    let _ = request.respond(response);
+}
+
 fn respond_error(request: Request, code: u16, msg: &str) {
     let _ = request.respond(Response::from_string(msg).with_status_code(code));
 }
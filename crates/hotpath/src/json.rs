@@ -7,8 +7,11 @@ use regex::Regex;
 use serde::{Deserialize, Serialize};
 use std::str::FromStr;
 use std::sync::LazyLock;
+use std::time::Duration;
 
-pub use crate::output::{FunctionLogsJson, FunctionsJson};
+pub use crate::output::{
+    CallTreeNode, FunctionLogsJson, FunctionsJson, FunctionsTreeJson, SizeClassJson,
+};
 
 /// State of a channel or stream.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
@@ -44,6 +47,8 @@ pub enum ChannelType {
     Bounded(usize),
     Unbounded,
     Oneshot,
+    Broadcast(usize),
+    Watch,
 }
 
 impl std::fmt::Display for ChannelType {
@@ -52,6 +57,8 @@ impl std::fmt::Display for ChannelType {
             ChannelType::Bounded(size) => write!(f, "bounded[{}]", size),
             ChannelType::Unbounded => write!(f, "unbounded"),
             ChannelType::Oneshot => write!(f, "oneshot"),
+            ChannelType::Broadcast(size) => write!(f, "broadcast[{}]", size),
+            ChannelType::Watch => write!(f, "watch"),
         }
     }
 }
@@ -75,12 +82,21 @@ impl<'de> Deserialize<'de> for ChannelType {
         match s.as_str() {
             "unbounded" => Ok(ChannelType::Unbounded),
             "oneshot" => Ok(ChannelType::Oneshot),
+            "watch" => Ok(ChannelType::Watch),
             _ => {
                 if let Some(inner) = s.strip_prefix("bounded[").and_then(|x| x.strip_suffix(']')) {
                     let size = inner
                         .parse()
                         .map_err(|_| serde::de::Error::custom("invalid bounded size"))?;
                     Ok(ChannelType::Bounded(size))
+                } else if let Some(inner) = s
+                    .strip_prefix("broadcast[")
+                    .and_then(|x| x.strip_suffix(']'))
+                {
+                    let size = inner
+                        .parse()
+                        .map_err(|_| serde::de::Error::custom("invalid broadcast size"))?;
+                    Ok(ChannelType::Broadcast(size))
                 } else {
                     Err(serde::de::Error::custom("invalid channel type"))
                 }
@@ -134,6 +150,49 @@ pub struct SerializableChannelStats {
     pub type_size: usize,
     pub queued_bytes: u64,
     pub iter: u32,
+    /// Receivers of a broadcast or watch channel, empty for other channel types
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub subscribers: Vec<SerializableSubscriberStats>,
+    /// Time received messages spent in the queue, `None` until the first one is received
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub latency: Option<ChannelLatency>,
+    /// Sends that waited for space in a full channel, `None` until one does
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub backpressure: Option<ChannelBackpressure>,
+}
+
+/// Percentiles of the time messages spent in a channel queue, in nanoseconds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChannelLatency {
+    pub p50_ns: u64,
+    pub p95_ns: u64,
+    pub p99_ns: u64,
+}
+
+/// Time producers spent blocked on a full bounded channel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChannelBackpressure {
+    /// Sends that found the channel full and waited for space
+    pub blocked_count: u64,
+    pub blocked_total_ns: u64,
+    /// Percentiles of the wait time of a single blocked send
+    pub p50_ns: u64,
+    pub p95_ns: u64,
+    pub p99_ns: u64,
+    /// `try_send` calls rejected because the channel was full, only counted with `proxy = false`
+    pub try_send_failures: u64,
+}
+
+/// Serializable statistics of a single broadcast or watch channel receiver.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SerializableSubscriberStats {
+    pub index: u32,
+    pub label: String,
+    pub state: ChannelState,
+    pub received_count: u64,
+    pub queued: u64,
+    /// Messages this receiver never saw, overwritten broadcast values or coalesced watch updates
+    pub lagged_count: u64,
 }
 
 /// Serializable log response containing sent and received logs for channels.
@@ -323,13 +382,115 @@ pub struct ThreadsJson {
     pub rss_bytes: Option<u64>,
 }
 
+/// Metrics of a single Tokio runtime worker.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RuntimeWorkerMetrics {
+    /// Worker index within the runtime
+    pub index: usize,
+    /// Total time the worker has been busy polling tasks (seconds)
+    pub busy_total: f64,
+    /// Share of the last sample interval the worker was busy
+    /// None if this is the first sample
+    pub busy_percent: Option<f64>,
+    /// Number of times the worker parked
+    pub park_count: u64,
+    /// Number of times the worker parked or unparked
+    pub park_unpark_count: u64,
+    /// Tasks in the worker's local run queue (only with `--cfg tokio_unstable`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub local_queue_depth: Option<usize>,
+}
+
+/// JSON response structure for /runtime endpoint.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RuntimeJson {
+    /// Current elapsed time since monitoring started in nanoseconds
+    pub current_elapsed_ns: u64,
+    /// Sample interval in milliseconds
+    pub sample_interval_ms: u64,
+    /// Runtime flavor (CurrentThread or MultiThread)
+    pub flavor: String,
+    /// Number of worker threads
+    pub num_workers: usize,
+    /// Number of tasks that are spawned and not yet completed
+    pub num_alive_tasks: usize,
+    /// Tasks in the global injection queue
+    pub global_queue_depth: usize,
+    /// Threads in the blocking pool (only with `--cfg tokio_unstable`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub num_blocking_threads: Option<usize>,
+    /// Idle threads in the blocking pool (only with `--cfg tokio_unstable`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub num_idle_blocking_threads: Option<usize>,
+    /// Tasks waiting for a blocking pool thread (only with `--cfg tokio_unstable`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub blocking_queue_depth: Option<usize>,
+    /// Per-worker metrics
+    pub workers: Vec<RuntimeWorkerMetrics>,
+}
+
+/// Bytes still live when calls of a measured function returned.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FunctionRetainedJson {
+    /// Function name
+    pub name: String,
+    /// Number of calls
+    pub calls: u64,
+    /// Average bytes allocated and not freed within a call
+    pub avg_retained_bytes: u64,
+    /// Sum of bytes allocated and not freed within the calls
+    pub total_retained_bytes: u64,
+}
+
+/// Heap memory currently held by allocations of a measured function.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LiveAllocationsJson {
+    /// Name of the function that made the allocations
+    pub name: String,
+    /// Bytes allocated by the function and not freed yet
+    pub live_bytes: u64,
+    /// Number of allocations made by the function and not freed yet
+    pub live_count: u64,
+}
+
+/// JSON response structure for /functions_alloc/retained endpoint.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FunctionsRetainedJson {
+    /// Time since profiling started or stats were reset in nanoseconds
+    pub total_elapsed: u64,
+    /// Name of the profiled program's main function
+    pub caller_name: String,
+    /// Retained bytes per measured function, sorted by total retained bytes
+    pub functions: Vec<FunctionRetainedJson>,
+    /// Live heap memory by allocating function, sorted by live bytes
+    pub live: Vec<LiveAllocationsJson>,
+}
+
 /// HTTP routes for the hotpath metrics server.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Route {
     /// GET /functions_timing - Returns timing metrics for all functions
     FunctionsTiming,
+    /// GET /functions_timing?window=60s&tag=checkout - Returns timing metrics limited
+    /// to a rolling window and/or to the calls with a tag
+    FunctionsTimingQuery {
+        window: Option<Duration>,
+        tag: Option<String>,
+    },
+    /// GET /functions_timing/tree - Returns the caller→callee tree of measured functions
+    FunctionsTimingTree,
+    /// GET /functions_timing/folded - Returns self time per stack in collapsed stack format
+    FunctionsTimingFolded,
     /// GET /functions_alloc - Returns allocation metrics for all functions
     FunctionsAlloc,
+    /// GET /functions_alloc?window=60s&tag=checkout - Returns allocation metrics limited
+    /// to a rolling window and/or to the calls with a tag
+    FunctionsAllocQuery {
+        window: Option<Duration>,
+        tag: Option<String>,
+    },
+    /// GET /functions_alloc/retained - Returns retained and live heap memory per function
+    FunctionsAllocRetained,
     /// GET /channels - Returns all channel statistics
     Channels,
     /// GET /streams - Returns all stream statistics
@@ -338,6 +499,10 @@ pub enum Route {
     Futures,
     /// GET /threads - Returns thread metrics
     Threads,
+    /// GET /runtime - Returns Tokio runtime metrics
+    Runtime,
+    /// GET /metrics - Returns all metrics in OpenMetrics text format
+    Metrics,
     /// GET /functions_timing/{base64_name}/logs - Returns timing logs for a function
     FunctionTimingLogs { function_name: String },
     /// GET /functions_alloc/{base64_name}/logs - Returns allocation logs for a function
@@ -348,6 +513,18 @@ pub enum Route {
     StreamLogs { stream_id: u64 },
     /// GET /futures/{id}/calls - Returns calls for a specific future
     FutureCalls { future_id: u64 },
+    /// POST /reset - Clears all stats, or a single subsystem with e.g. `/reset/channels`
+    Reset { scope: ResetScope },
+}
+
+/// Stats cleared by a [`Route::Reset`] request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResetScope {
+    All,
+    Functions,
+    Channels,
+    Streams,
+    Futures,
 }
 
 impl Route {
@@ -356,11 +533,28 @@ impl Route {
         use base64::Engine;
         match self {
             Route::FunctionsTiming => "/functions_timing".to_string(),
+            Route::FunctionsTimingQuery { window, tag } => {
+                format!(
+                    "/functions_timing{}",
+                    functions_query(*window, tag.as_deref())
+                )
+            }
+            Route::FunctionsTimingTree => "/functions_timing/tree".to_string(),
+            Route::FunctionsTimingFolded => "/functions_timing/folded".to_string(),
             Route::FunctionsAlloc => "/functions_alloc".to_string(),
+            Route::FunctionsAllocQuery { window, tag } => {
+                format!(
+                    "/functions_alloc{}",
+                    functions_query(*window, tag.as_deref())
+                )
+            }
+            Route::FunctionsAllocRetained => "/functions_alloc/retained".to_string(),
             Route::Channels => "/channels".to_string(),
             Route::Streams => "/streams".to_string(),
             Route::Futures => "/futures".to_string(),
             Route::Threads => "/threads".to_string(),
+            Route::Runtime => "/runtime".to_string(),
+            Route::Metrics => "/metrics".to_string(),
             Route::FunctionTimingLogs { function_name } => {
                 let encoded =
                     base64::engine::general_purpose::STANDARD.encode(function_name.as_bytes());
@@ -374,6 +568,13 @@ impl Route {
             Route::ChannelLogs { channel_id } => format!("/channels/{}/logs", channel_id),
             Route::StreamLogs { stream_id } => format!("/streams/{}/logs", stream_id),
             Route::FutureCalls { future_id } => format!("/futures/{}/calls", future_id),
+            Route::Reset { scope } => match scope {
+                ResetScope::All => "/reset".to_string(),
+                ResetScope::Functions => "/reset/functions".to_string(),
+                ResetScope::Channels => "/reset/channels".to_string(),
+                ResetScope::Streams => "/reset/streams".to_string(),
+                ResetScope::Futures => "/reset/futures".to_string(),
+            },
         }
     }
 
@@ -394,6 +595,122 @@ static RE_FUNCTION_LOGS_TIMING: LazyLock<Regex> =
 static RE_FUNCTION_LOGS_ALLOC: LazyLock<Regex> =
     LazyLock::new(|| Regex::new(r"^/functions_alloc/([^/]+)/logs$").unwrap());
 
+fn functions_query(window: Option<Duration>, tag: Option<&str>) -> String {
+    let mut params = Vec::new();
+    if let Some(window) = window {
+        params.push(format!("window={}", format_window(window)));
+    }
+    if let Some(tag) = tag {
+        params.push(format!("tag={}", percent_encode(tag)));
+    }
+    if params.is_empty() {
+        String::new()
+    } else {
+        format!("?{}", params.join("&"))
+    }
+}
+
+/// Percent-encodes everything but unreserved characters of a query value.
+fn percent_encode(s: &str) -> String {
+    let mut encoded = String::with_capacity(s.len());
+    for byte in s.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'_' | b'.' | b'~') {
+            encoded.push(byte as char);
+        } else {
+            encoded.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    encoded
+}
+
+fn percent_decode(s: &str) -> Option<String> {
+    let bytes = s.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'%' => {
+                let hex = s.get(i + 1..i + 3)?;
+                decoded.push(u8::from_str_radix(hex, 16).ok()?);
+                i += 3;
+            }
+            b'+' => {
+                decoded.push(b' ');
+                i += 1;
+            }
+            byte => {
+                decoded.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8(decoded).ok()
+}
+
+/// Formats a rolling window the way it is accepted by the `window` query parameter, e.g. `60s`.
+pub fn format_window(window: Duration) -> String {
+    if window.subsec_millis() > 0 {
+        format!("{}ms", window.as_millis())
+    } else {
+        format!("{}s", window.as_secs())
+    }
+}
+
+/// Parses a rolling window like `500ms`, `10s`, `1m` or `1h`.
+pub fn parse_window(s: &str) -> Option<Duration> {
+    let split = s.find(|c: char| !c.is_ascii_digit())?;
+    let (value, unit) = s.split_at(split);
+    let value: u64 = value.parse().ok()?;
+    match unit {
+        "ms" => Some(Duration::from_millis(value)),
+        "s" => Some(Duration::from_secs(value)),
+        "m" => value.checked_mul(60).map(Duration::from_secs),
+        "h" => value.checked_mul(3600).map(Duration::from_secs),
+        _ => None,
+    }
+}
+
+/// Value of the `window` query parameter, `Err` if it is present but malformed.
+fn query_window(query: Option<&str>) -> Result<Option<Duration>, RouteError> {
+    let Some(query) = query else {
+        return Ok(None);
+    };
+    for pair in query.split('&') {
+        if let Some(value) = pair.strip_prefix("window=") {
+            return parse_window(value).map(Some).ok_or_else(|| {
+                RouteError::BadQuery(format!(
+                    "invalid window '{value}', expected a duration like 500ms, 10s, 1m or 1h"
+                ))
+            });
+        }
+    }
+    Ok(None)
+}
+
+/// Value of the `tag` query parameter, `Err` if it is present but malformed.
+fn query_tag(query: Option<&str>) -> Result<Option<String>, RouteError> {
+    let Some(query) = query else {
+        return Ok(None);
+    };
+    for pair in query.split('&') {
+        if let Some(value) = pair.strip_prefix("tag=") {
+            return percent_decode(value)
+                .map(Some)
+                .ok_or_else(|| RouteError::BadQuery(format!("invalid tag '{value}'")));
+        }
+    }
+    Ok(None)
+}
+
+/// Why a URL couldn't be parsed into a [`Route`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RouteError {
+    /// The path doesn't match any known route
+    NotFound,
+    /// The route is known but a query parameter is malformed
+    BadQuery(String),
+}
+
 fn base64_decode(encoded: &str) -> Result<String, String> {
     use base64::Engine;
     let bytes = base64::engine::general_purpose::STANDARD
@@ -408,43 +725,94 @@ impl FromStr for Route {
     /// Parses a URL path into a Route using regex patterns.
     /// Returns Err(()) if the path doesn't match any known route.
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let path = s.split('?').next().unwrap_or(s);
+        Route::from_path(s).map_err(|_| ())
+    }
+}
+
+impl Route {
+    /// Parses a URL path into a Route like [`FromStr`], but tells an unknown
+    /// path apart from a malformed query parameter.
+    pub fn from_path(s: &str) -> Result<Self, RouteError> {
+        let (path, query) = match s.split_once('?') {
+            Some((path, query)) => (path, Some(query)),
+            None => (s, None),
+        };
 
         match path {
-            "/functions_timing" => return Ok(Route::FunctionsTiming),
-            "/functions_alloc" => return Ok(Route::FunctionsAlloc),
+            "/functions_timing" => {
+                return Ok(match (query_window(query)?, query_tag(query)?) {
+                    (None, None) => Route::FunctionsTiming,
+                    (window, tag) => Route::FunctionsTimingQuery { window, tag },
+                })
+            }
+            "/functions_timing/tree" => return Ok(Route::FunctionsTimingTree),
+            "/functions_timing/folded" => return Ok(Route::FunctionsTimingFolded),
+            "/functions_alloc" => {
+                return Ok(match (query_window(query)?, query_tag(query)?) {
+                    (None, None) => Route::FunctionsAlloc,
+                    (window, tag) => Route::FunctionsAllocQuery { window, tag },
+                })
+            }
+            "/functions_alloc/retained" => return Ok(Route::FunctionsAllocRetained),
             "/channels" => return Ok(Route::Channels),
             "/streams" => return Ok(Route::Streams),
             "/futures" => return Ok(Route::Futures),
             "/threads" => return Ok(Route::Threads),
+            "/runtime" => return Ok(Route::Runtime),
+            "/metrics" => return Ok(Route::Metrics),
+            "/reset" => {
+                return Ok(Route::Reset {
+                    scope: ResetScope::All,
+                })
+            }
+            "/reset/functions" => {
+                return Ok(Route::Reset {
+                    scope: ResetScope::Functions,
+                })
+            }
+            "/reset/channels" => {
+                return Ok(Route::Reset {
+                    scope: ResetScope::Channels,
+                })
+            }
+            "/reset/streams" => {
+                return Ok(Route::Reset {
+                    scope: ResetScope::Streams,
+                })
+            }
+            "/reset/futures" => {
+                return Ok(Route::Reset {
+                    scope: ResetScope::Futures,
+                })
+            }
             _ => {}
         }
 
         if let Some(caps) = RE_FUNCTION_LOGS_TIMING.captures(path) {
-            let function_name = base64_decode(&caps[1]).map_err(|_| ())?;
+            let function_name = base64_decode(&caps[1]).map_err(|_| RouteError::NotFound)?;
             return Ok(Route::FunctionTimingLogs { function_name });
         }
 
         if let Some(caps) = RE_FUNCTION_LOGS_ALLOC.captures(path) {
-            let function_name = base64_decode(&caps[1]).map_err(|_| ())?;
+            let function_name = base64_decode(&caps[1]).map_err(|_| RouteError::NotFound)?;
             return Ok(Route::FunctionAllocLogs { function_name });
         }
 
         if let Some(caps) = RE_CHANNEL_LOGS.captures(path) {
-            let channel_id = caps[1].parse().map_err(|_| ())?;
+            let channel_id = caps[1].parse().map_err(|_| RouteError::NotFound)?;
             return Ok(Route::ChannelLogs { channel_id });
         }
 
         if let Some(caps) = RE_STREAM_LOGS.captures(path) {
-            let stream_id = caps[1].parse().map_err(|_| ())?;
+            let stream_id = caps[1].parse().map_err(|_| RouteError::NotFound)?;
             return Ok(Route::StreamLogs { stream_id });
         }
 
         if let Some(caps) = RE_FUTURE_CALLS.captures(path) {
-            let future_id = caps[1].parse().map_err(|_| ())?;
+            let future_id = caps[1].parse().map_err(|_| RouteError::NotFound)?;
             return Ok(Route::FutureCalls { future_id });
         }
 
-        Err(())
+        Err(RouteError::NotFound)
     }
 }
@@ -13,12 +13,29 @@
 /// * `Table` - Human-readable table format (default)
 /// * `Json` - JSON format
 /// * `JsonPretty` - Pretty-printed JSON format
+/// * `Folded` - Collapsed stacks of measured functions with self time in nanoseconds,
+///   ready for `inferno` or speedscope. Only supported by the functions report,
+///   channels, streams and futures reports fall back to `Table`.
 #[derive(Clone, Copy, Debug, Default)]
 pub enum Format {
     #[default]
     Table,
     Json,
     JsonPretty,
+    Folded,
+}
+
+/// Column used to rank functions in timing reports.
+///
+/// # Variants
+///
+/// * `Total` - Inclusive duration, including nested measured functions (default)
+/// * `SelfTime` - Exclusive duration, excluding nested measured functions
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SortBy {
+    #[default]
+    Total,
+    SelfTime,
 }
 
 #[cfg(all(feature = "hotpath", not(feature = "hotpath-off")))]
@@ -31,6 +48,8 @@ mod lib_on;
 pub use lib_on::channels;
 #[cfg(all(feature = "hotpath", not(feature = "hotpath-off")))]
 pub use lib_on::futures;
+#[cfg(all(feature = "hotpath", not(feature = "hotpath-off"), feature = "tokio"))]
+pub use lib_on::runtime;
 #[cfg(all(feature = "hotpath", not(feature = "hotpath-off")))]
 pub use lib_on::streams;
 #[cfg(all(feature = "hotpath", not(feature = "hotpath-off"), feature = "threads"))]
@@ -41,8 +60,8 @@ pub(crate) mod output;
 #[cfg(any(feature = "hotpath", feature = "ci", feature = "tui"))]
 pub use output::{
     ceil_char_boundary, floor_char_boundary, format_bytes, format_duration, shorten_function_name,
-    truncate_result, FunctionLogsJson, FunctionsDataJson, FunctionsJson, MetricType,
-    MetricsProvider, ProfilingMode, Reporter, MAX_RESULT_LEN,
+    truncate_result, CallTreeNode, FunctionLogsJson, FunctionsDataJson, FunctionsJson,
+    FunctionsTreeJson, MetricType, MetricsProvider, ProfilingMode, Reporter, MAX_RESULT_LEN,
 };
 
 #[cfg(all(feature = "hotpath", not(feature = "hotpath-off")))]
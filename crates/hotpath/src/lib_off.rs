@@ -2,13 +2,53 @@ pub use hotpath_macros::{main, measure, measure_all, skip};
 
 #[macro_export]
 macro_rules! measure_block {
+    ($label:expr, tag = $tag:expr, $expr:expr) => {{
+        let _ = || {
+            let _ = &($tag);
+        };
+        $expr
+    }};
+    ($label:expr, $expr:expr) => {{
+        $expr
+    }};
+}
+
+#[macro_export]
+macro_rules! measure_block_dyn {
+    ($label:expr, tag = $tag:expr, $expr:expr) => {{
+        let _ = || {
+            let _ = (&($label), &($tag));
+        };
+        $expr
+    }};
     ($label:expr, $expr:expr) => {{
+        let _ = || {
+            let _ = &($label);
+        };
         $expr
     }};
 }
 
 #[macro_export]
 macro_rules! channel {
+    (@opts $expr:expr; proxy = true $(, $($rest:tt)*)?) => {
+        $crate::channel!(@opts $expr; $($($rest)*)?)
+    };
+    (@opts $expr:expr; proxy = false $(, $($rest:tt)*)?) => {
+        $crate::channel!(@opts $expr; $($($rest)*)?)
+    };
+    (@opts $expr:expr; label = $label:expr $(, $($rest:tt)*)?) => {
+        $crate::channel!(@opts $expr; $($($rest)*)?)
+    };
+    (@opts $expr:expr; capacity = $capacity:expr $(, $($rest:tt)*)?) => {
+        $crate::channel!(@opts $expr; $($($rest)*)?)
+    };
+    (@opts $expr:expr; log = true $(, $($rest:tt)*)?) => {
+        $crate::channel!(@opts $expr; $($($rest)*)?)
+    };
+    (@opts $expr:expr;) => {
+        $expr
+    };
     ($expr:expr) => {
         $expr
     };
@@ -57,6 +97,9 @@ macro_rules! channel {
     ($expr:expr, log = true, capacity = $capacity:expr, label = $label:expr) => {
         $expr
     };
+    ($expr:expr, $($opts:tt)+) => {
+        $crate::channel!(@opts $expr; $($opts)+)
+    };
 }
 
 #[macro_export]
@@ -88,12 +131,23 @@ macro_rules! future {
     };
 }
 
+#[macro_export]
+macro_rules! global_allocator {
+    ($alloc:path) => {
+        $crate::global_allocator!($alloc, $alloc);
+    };
+    ($ty:ty, $init:expr) => {
+        #[global_allocator]
+        static HOTPATH_GLOBAL_ALLOCATOR: $ty = $init;
+    };
+}
+
 pub use crate::Format;
 
 pub struct MeasurementGuard {}
 
 impl MeasurementGuard {
-    pub fn new(_name: &'static str, _wrapper: bool, _unsupported_async: bool) -> Self {
+    pub fn new(_name: &'static str, _wrapper: bool, _untracked_allocs: bool) -> Self {
         Self {}
     }
 
@@ -101,13 +155,17 @@ impl MeasurementGuard {
         Self {}
     }
 
+    pub fn with_tag(self, _tag: &str) -> Self {
+        self
+    }
+
     pub fn build_with_timeout(self, _duration: std::time::Duration) {}
 }
 
 pub struct MeasurementGuardWithLog {}
 
 impl MeasurementGuardWithLog {
-    pub fn new(_name: &'static str, _wrapper: bool, _unsupported_async: bool) -> Self {
+    pub fn new(_name: &'static str, _wrapper: bool, _untracked_allocs: bool) -> Self {
         Self {}
     }
 
@@ -115,9 +173,28 @@ impl MeasurementGuardWithLog {
         Self {}
     }
 
+    pub fn with_tag(self, _tag: &str) -> Self {
+        self
+    }
+
     pub fn finish_with_result<T: std::fmt::Debug>(self, _result: &T) {}
 }
 
+pub struct Span {}
+
+impl Span {
+    pub fn with_tag(self, _tag: &str) -> Self {
+        self
+    }
+
+    pub fn finish(self) {}
+}
+
+#[inline]
+pub fn span_start(_name: &'static str) -> Span {
+    Span {}
+}
+
 #[inline]
 pub fn measure_with_log<T: std::fmt::Debug, F: FnOnce() -> T>(
     _name: &'static str,
@@ -178,6 +255,18 @@ impl FunctionsGuardBuilder {
         self
     }
 
+    pub fn sort_by(self, _sort_by: crate::SortBy) -> Self {
+        self
+    }
+
+    pub fn report_interval(self, _interval: std::time::Duration) -> Self {
+        self
+    }
+
+    pub fn report_delta(self, _delta: bool) -> Self {
+        self
+    }
+
     pub fn build(self) -> HotPath {
         HotPath
     }
@@ -189,8 +278,45 @@ impl FunctionsGuardBuilder {
     pub fn reporter(self, _reporter: Box<dyn Reporter>) -> Self {
         self
     }
+
+    #[cfg(feature = "otlp")]
+    pub fn otlp(self, _reporter: OtlpReporter) -> Self {
+        self
+    }
 }
 
+#[cfg(feature = "otlp")]
+pub struct OtlpReporter {}
+
+#[cfg(feature = "otlp")]
+impl OtlpReporter {
+    pub fn new(_endpoint: impl Into<String>) -> Self {
+        Self {}
+    }
+
+    pub fn service_name(self, _service_name: impl Into<String>) -> Self {
+        self
+    }
+
+    pub fn header(self, _key: impl Into<String>, _value: impl Into<String>) -> Self {
+        self
+    }
+
+    pub fn interval(self, _interval: std::time::Duration) -> Self {
+        self
+    }
+}
+
+pub fn reset() {}
+
+pub fn reset_functions() {}
+
+pub fn reset_channels() {}
+
+pub fn reset_streams() {}
+
+pub fn reset_futures() {}
+
 #[deprecated(since = "0.9.0", note = "Renamed to FunctionsGuardBuilder")]
 pub type GuardBuilder = FunctionsGuardBuilder;
 
@@ -240,6 +366,19 @@ pub mod channels {
     impl Drop for ChannelsGuard {
         fn drop(&mut self) {}
     }
+
+    /// Without profiling, `channel!(expr, proxy = false)` returns the channel unchanged,
+    /// so the wrapper types are aliases of the original handles.
+    pub mod direct {
+        pub mod std {
+            pub use std::sync::mpsc::{IntoIter, Iter, Receiver, Sender, SyncSender, TryIter};
+        }
+
+        #[cfg(feature = "crossbeam")]
+        pub mod crossbeam {
+            pub use crossbeam_channel::{IntoIter, Iter, Receiver, Sender, TryIter};
+        }
+    }
 }
 
 pub mod streams {
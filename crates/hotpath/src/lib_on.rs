@@ -4,30 +4,90 @@ pub use hotpath_macros::{future_fn, main, measure, measure_all, skip};
 
 pub mod channels;
 pub mod futures;
+#[cfg(feature = "tokio")]
+pub mod runtime;
 pub mod streams;
 #[cfg(feature = "threads")]
 pub mod threads;
 
 pub mod functions;
 
+pub(crate) mod openmetrics;
+#[cfg(feature = "otlp")]
+pub mod otlp;
+pub(crate) mod reset;
+pub(crate) mod trace;
+
+#[doc(hidden)]
+pub use channels::direct::{InstrumentChannelDirect, InstrumentChannelDirectLog};
 pub use channels::{InstrumentChannel, InstrumentChannelLog};
 pub use futures::{InstrumentFuture, InstrumentFutureLog};
 pub use streams::{InstrumentStream, InstrumentStreamLog};
 
 pub use functions::guard::{FunctionsGuard, FunctionsGuardBuilder};
+pub use functions::span::{span_start, Span};
 pub use functions::{
     measure_with_log, measure_with_log_async, FunctionStats, MeasurementGuard,
     MeasurementGuardWithLog,
 };
+#[cfg(feature = "otlp")]
+pub use otlp::OtlpReporter;
+pub use reset::{reset, reset_channels, reset_functions, reset_futures, reset_streams};
 
 cfg_if::cfg_if! {
     if #[cfg(feature = "hotpath-alloc")] {
         #[doc(hidden)]
         pub use tokio::runtime::{Handle, RuntimeFlavor};
+        pub use functions::alloc::allocator::CountingAllocator;
+    }
+}
+
+// Memory allocations profiling using a custom global allocator
+#[cfg(all(feature = "hotpath-alloc", not(feature = "hotpath-alloc-custom")))]
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator::new(std::alloc::System);
 
-        // Memory allocations profiling using a custom global allocator
-        #[global_allocator]
-        static GLOBAL: functions::alloc::allocator::CountingAllocator = functions::alloc::allocator::CountingAllocator {};
+cfg_if::cfg_if! {
+    if #[cfg(feature = "hotpath-alloc")] {
+        /// Declares the global allocator of the program, wrapped in a [`CountingAllocator`]
+        /// so allocations are profiled with the allocator the program ships with.
+        ///
+        /// Requires the `hotpath-alloc-custom` feature, which disables the built-in
+        /// global allocator of `hotpath-alloc`. Without allocation profiling the
+        /// allocator is declared as is.
+        ///
+        /// # Examples
+        ///
+        /// ```rust,ignore
+        /// hotpath::global_allocator!(mimalloc::MiMalloc);
+        ///
+        /// // Allocators that are not unit structs need a type and an initializer
+        /// hotpath::global_allocator!(MyAllocator, MyAllocator::new());
+        /// ```
+        #[macro_export]
+        macro_rules! global_allocator {
+            ($alloc:path) => {
+                $crate::global_allocator!($alloc, $alloc);
+            };
+            ($ty:ty, $init:expr) => {
+                #[global_allocator]
+                static HOTPATH_GLOBAL_ALLOCATOR: $crate::CountingAllocator<$ty> =
+                    $crate::CountingAllocator::new($init);
+            };
+        }
+    } else {
+        /// Declares the global allocator of the program, it is only wrapped in a
+        /// counting allocator with the `hotpath-alloc` feature.
+        #[macro_export]
+        macro_rules! global_allocator {
+            ($alloc:path) => {
+                $crate::global_allocator!($alloc, $alloc);
+            };
+            ($ty:ty, $init:expr) => {
+                #[global_allocator]
+                static HOTPATH_GLOBAL_ALLOCATOR: $ty = $init;
+            };
+        }
     }
 }
 
@@ -40,6 +100,7 @@ cfg_if::cfg_if! {
 /// # Arguments
 ///
 /// * `$label` - A static string label to identify this code block in the profiling report
+/// * `tag = $tag` - Optional tag of this run of the block (`AsRef<str>`), stats are also broken down per tag
 /// * `$expr` - The expression or code block to measure
 ///
 /// # Behavior
@@ -58,6 +119,11 @@ cfg_if::cfg_if! {
 ///     // Your code here
 ///     std::thread::sleep(Duration::from_millis(10));
 /// });
+///
+/// let tenant = "acme";
+/// hotpath::measure_block!("query", tag = tenant, {
+///     std::thread::sleep(Duration::from_millis(1));
+/// });
 /// # }
 /// ```
 ///
@@ -65,8 +131,15 @@ cfg_if::cfg_if! {
 ///
 /// * [`measure`](hotpath_macros::measure) - Attribute macro for instrumenting functions
 /// * [`main`](hotpath_macros::main) - Attribute macro that initializes profiling
+/// * [`measure_block_dyn!`] - Same for labels only known at runtime
 #[macro_export]
 macro_rules! measure_block {
+    ($label:expr, tag = $tag:expr, $expr:expr) => {{
+        let _guard = hotpath::functions::MeasurementGuard::new($label, false, false)
+            .with_tag(::core::convert::AsRef::<str>::as_ref(&($tag)));
+
+        $expr
+    }};
     ($label:expr, $expr:expr) => {{
         let _guard = hotpath::functions::MeasurementGuard::new($label, false, false);
 
@@ -74,6 +147,53 @@ macro_rules! measure_block {
     }};
 }
 
+/// Measures a code block like [`measure_block!`], with a label only known at runtime.
+///
+/// The label can be any `AsRef<str>`, e.g. a plugin name or an SQL statement id. Labels are
+/// interned into stable keys of the stats, which are kept for the lifetime of the process.
+/// To bound memory, only the first 256 distinct labels are kept, blocks with further labels
+/// are measured together as `hotpath::other`.
+///
+/// # Arguments
+///
+/// * `$label` - Label to identify this run of the code block in the profiling report
+/// * `tag = $tag` - Optional tag of this run of the block (`AsRef<str>`), stats are also broken down per tag
+/// * `$expr` - The expression or code block to measure
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # {
+/// let plugin = String::from("thumbnails");
+///
+/// hotpath::measure_block_dyn!(format!("plugin::{plugin}"), {
+///     std::thread::sleep(std::time::Duration::from_millis(1));
+/// });
+/// # }
+/// ```
+#[macro_export]
+macro_rules! measure_block_dyn {
+    ($label:expr, tag = $tag:expr, $expr:expr) => {{
+        let _guard = hotpath::functions::MeasurementGuard::new(
+            hotpath::functions::intern_label(::core::convert::AsRef::<str>::as_ref(&($label))),
+            false,
+            false,
+        )
+        .with_tag(::core::convert::AsRef::<str>::as_ref(&($tag)));
+
+        $expr
+    }};
+    ($label:expr, $expr:expr) => {{
+        let _guard = hotpath::functions::MeasurementGuard::new(
+            hotpath::functions::intern_label(::core::convert::AsRef::<str>::as_ref(&($label))),
+            false,
+            false,
+        );
+
+        $expr
+    }};
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1,6 +1,7 @@
 //! Channel instrumentation module - tracks message flow, queue sizes, and channel state.
 
-use crossbeam_channel::{unbounded, Sender as CbSender};
+use crossbeam_channel::{bounded, unbounded, Sender as CbSender};
+use hdrhistogram::Histogram;
 use std::collections::{HashMap, VecDeque};
 use std::sync::atomic::AtomicU64;
 use std::sync::{Arc, OnceLock, RwLock};
@@ -14,11 +15,16 @@ use std::time::Instant;
 pub mod guard;
 pub use guard::{ChannelsGuard, ChannelsGuardBuilder};
 
+mod tracker;
 mod wrapper;
 
-use crate::http_server::HTTP_SERVER_PORT;
+pub(crate) use tracker::{required_capacity, Tracker, TrackerHandle};
+pub use wrapper::direct;
+
+use crate::http_server::{HTTP_SERVER_PORT, RECV_TIMEOUT_MS};
 pub use crate::json::{
-    ChannelLogs, ChannelState, ChannelType, ChannelsJson, LogEntry, SerializableChannelStats,
+    ChannelBackpressure, ChannelLatency, ChannelLogs, ChannelState, ChannelType, ChannelsJson,
+    LogEntry, SerializableChannelStats, SerializableSubscriberStats,
 };
 use crate::output::truncate_result;
 
@@ -41,16 +47,88 @@ pub(crate) struct ChannelStats {
     pub(crate) received_count: u64,
     pub(crate) type_name: &'static str,
     pub(crate) type_size: usize,
+    /// Messages pass through proxy forwarders, which hold one of them outside the channel
+    pub(crate) proxied: bool,
     pub(crate) sent_logs: VecDeque<LogEntry>,
     pub(crate) received_logs: VecDeque<LogEntry>,
     pub(crate) iter: u32,
+    pub(crate) subscribers: Vec<SubscriberStats>,
+    /// Send timestamps of queued messages, matched with receives in FIFO order
+    pub(crate) pending_sends: VecDeque<u64>,
+    /// Receive timestamps processed before the send event of their message
+    pub(crate) early_receives: VecDeque<u64>,
+    /// Queue dwell time of received messages in nanoseconds, created on the first receive
+    pub(crate) latency: Option<Histogram<u64>>,
+    /// Wait time of sends that found the channel full in nanoseconds, created on the first one
+    pub(crate) blocked_sends: Option<Histogram<u64>>,
+    pub(crate) blocked_ns: u64,
+    pub(crate) try_send_failures: u64,
+}
+
+/// Statistics for a single receiver of a broadcast or watch channel.
+#[derive(Debug, Clone)]
+pub(crate) struct SubscriberStats {
+    pub(crate) id: u64,
+    pub(crate) label: Option<String>,
+    pub(crate) state: ChannelState,
+    /// Messages forwarded to the receiver queue
+    pub(crate) delivered: u64,
+    /// Delivered messages overwritten before the receiver got to them
+    pub(crate) evicted: u64,
+    pub(crate) lagged: u64,
+    pub(crate) queued: u64,
+    /// Channel message index this receiver has advanced to, messages it missed included
+    pub(crate) position: u64,
+}
+
+impl SubscriberStats {
+    fn new(id: u64, label: Option<String>, position: u64) -> Self {
+        Self {
+            id,
+            label,
+            state: ChannelState::default(),
+            delivered: 0,
+            evicted: 0,
+            lagged: 0,
+            queued: 0,
+            position,
+        }
+    }
+
+    pub(crate) fn received(&self) -> u64 {
+        self.delivered
+            .saturating_sub(self.evicted)
+            .saturating_sub(self.queued)
+    }
+
+    /// Channel messages this receiver has either consumed or missed.
+    fn seen(&self) -> u64 {
+        self.position.saturating_sub(self.queued)
+    }
 }
 
 impl ChannelStats {
     pub fn queued(&self) -> u64 {
+        if self.is_pubsub() {
+            return self
+                .subscribers
+                .iter()
+                .filter(|sub| sub.state != ChannelState::Closed)
+                .map(|sub| sub.queued)
+                .max()
+                .unwrap_or(0);
+        }
+
         self.sent_count
             .saturating_sub(self.received_count)
-            .saturating_sub(1)
+            .saturating_sub(u64::from(self.proxied))
+    }
+
+    fn is_pubsub(&self) -> bool {
+        matches!(
+            self.channel_type,
+            ChannelType::Broadcast(_) | ChannelType::Watch
+        )
     }
 
     pub fn queued_bytes(&self) -> u64 {
@@ -80,11 +158,41 @@ impl From<&ChannelStats> for SerializableChannelStats {
             type_size: channel_stats.type_size,
             queued_bytes: channel_stats.queued_bytes(),
             iter: channel_stats.iter,
+            subscribers: channel_stats
+                .subscribers
+                .iter()
+                .enumerate()
+                .map(|(index, sub)| SerializableSubscriberStats {
+                    index: index as u32,
+                    label: sub.label.clone().unwrap_or_else(|| format!("#{}", index)),
+                    state: sub.state,
+                    received_count: sub.received(),
+                    queued: sub.queued,
+                    lagged_count: channel_stats.subscriber_lagged(sub),
+                })
+                .collect(),
+            latency: channel_stats.latency(),
+            backpressure: channel_stats.backpressure(),
         }
     }
 }
 
 impl ChannelStats {
+    const LATENCY_LOW_NS: u64 = 1;
+    const LATENCY_HIGH_NS: u64 = 1_000_000_000_000; // 1000s
+    const LATENCY_SIGFIGS: u8 = 2;
+
+    /// Allocated lazily, most channels never record a sample of a given kind.
+    fn latency_histogram() -> Histogram<u64> {
+        Histogram::<u64>::new_with_bounds(
+            Self::LATENCY_LOW_NS,
+            Self::LATENCY_HIGH_NS,
+            Self::LATENCY_SIGFIGS,
+        )
+        .expect("hdrhistogram init")
+    }
+
+    #[allow(clippy::too_many_arguments)]
     fn new(
         id: u64,
         source: &'static str,
@@ -92,6 +200,7 @@ impl ChannelStats {
         channel_type: ChannelType,
         type_name: &'static str,
         type_size: usize,
+        proxied: bool,
         iter: u32,
     ) -> Self {
         Self {
@@ -104,10 +213,112 @@ impl ChannelStats {
             received_count: 0,
             type_name,
             type_size,
+            proxied,
             sent_logs: VecDeque::new(),
             received_logs: VecDeque::new(),
             iter,
+            subscribers: Vec::new(),
+            pending_sends: VecDeque::new(),
+            early_receives: VecDeque::new(),
+            latency: None,
+            blocked_sends: None,
+            blocked_ns: 0,
+            try_send_failures: 0,
+        }
+    }
+
+    /// Wrappers report a send after handing the message over, so its receive can be
+    /// processed first. Such receives wait here for their send timestamp.
+    fn record_send_time(&mut self, sent_ns: u64) {
+        match self.early_receives.pop_front() {
+            Some(received_ns) => self.record_latency(sent_ns, received_ns),
+            None if self.state == ChannelState::Closed => {}
+            None => self.pending_sends.push_back(sent_ns),
+        }
+        self.release_unmatched_times();
+    }
+
+    fn record_receive_time(&mut self, received_ns: u64) {
+        match self.pending_sends.pop_front() {
+            Some(sent_ns) => self.record_latency(sent_ns, received_ns),
+            None if self.state == ChannelState::Closed => {}
+            None => self.early_receives.push_back(received_ns),
+        }
+        self.release_unmatched_times();
+    }
+
+    /// A closed channel stops queuing timestamps. Receives can still drain the messages
+    /// left in it, so pending sends are only freed once they are all matched.
+    fn release_unmatched_times(&mut self) {
+        if self.state != ChannelState::Closed {
+            return;
+        }
+        self.early_receives = VecDeque::new();
+        if self.pending_sends.is_empty() {
+            self.pending_sends = VecDeque::new();
+        }
+    }
+
+    fn record_latency(&mut self, sent_ns: u64, received_ns: u64) {
+        self.latency
+            .get_or_insert_with(Self::latency_histogram)
+            .saturating_record(received_ns.saturating_sub(sent_ns));
+    }
+
+    fn latency(&self) -> Option<ChannelLatency> {
+        let latency = self.latency.as_ref().filter(|hist| !hist.is_empty())?;
+        Some(ChannelLatency {
+            p50_ns: latency.value_at_quantile(0.50),
+            p95_ns: latency.value_at_quantile(0.95),
+            p99_ns: latency.value_at_quantile(0.99),
+        })
+    }
+
+    fn record_blocked_send(&mut self, blocked_ns: u64) {
+        self.blocked_ns += blocked_ns;
+        self.blocked_sends
+            .get_or_insert_with(Self::latency_histogram)
+            .saturating_record(blocked_ns);
+    }
+
+    fn backpressure(&self) -> Option<ChannelBackpressure> {
+        let blocked_sends = self.blocked_sends.as_ref().filter(|hist| !hist.is_empty());
+        if blocked_sends.is_none() && self.try_send_failures == 0 {
+            return None;
         }
+        let quantile = |q| blocked_sends.map_or(0, |hist| hist.value_at_quantile(q));
+        Some(ChannelBackpressure {
+            blocked_count: blocked_sends.map_or(0, |hist| hist.len()),
+            blocked_total_ns: self.blocked_ns,
+            p50_ns: quantile(0.50),
+            p95_ns: quantile(0.95),
+            p99_ns: quantile(0.99),
+            try_send_failures: self.try_send_failures,
+        })
+    }
+
+    /// Zero counters and logs, the channel stays registered with its label and state.
+    fn reset(&mut self) {
+        self.sent_count = 0;
+        self.received_count = 0;
+        self.sent_logs.clear();
+        self.received_logs.clear();
+        // Pending send timestamps are kept, messages still in the queue get their latency
+        if let Some(latency) = &mut self.latency {
+            latency.reset();
+        }
+        if let Some(blocked_sends) = &mut self.blocked_sends {
+            blocked_sends.reset();
+        }
+        self.blocked_ns = 0;
+        self.try_send_failures = 0;
+        for sub in &mut self.subscribers {
+            sub.delivered = 0;
+            sub.evicted = 0;
+            sub.lagged = 0;
+            sub.position = 0;
+        }
+        self.update_state();
     }
 
     fn update_state(&mut self) {
@@ -117,9 +328,9 @@ impl ChannelStats {
 
         let queued = self.queued();
         let is_full = match self.channel_type {
-            ChannelType::Bounded(cap) => queued >= cap as u64,
+            ChannelType::Bounded(cap) | ChannelType::Broadcast(cap) => queued >= cap as u64,
             ChannelType::Oneshot => queued >= 1,
-            ChannelType::Unbounded => false,
+            ChannelType::Unbounded | ChannelType::Watch => false,
         };
 
         if is_full {
@@ -127,6 +338,99 @@ impl ChannelStats {
         } else {
             self.state = ChannelState::Active;
         }
+
+        if let ChannelType::Broadcast(cap) = self.channel_type {
+            for sub in &mut self.subscribers {
+                if sub.state != ChannelState::Closed {
+                    sub.state = if sub.queued >= cap as u64 {
+                        ChannelState::Full
+                    } else {
+                        ChannelState::Active
+                    };
+                }
+            }
+        }
+    }
+
+    /// Watch receivers only see the latest value, updates they were not notified of are lost.
+    pub(crate) fn subscriber_lagged(&self, sub: &SubscriberStats) -> u64 {
+        if self.channel_type == ChannelType::Watch && sub.state != ChannelState::Closed {
+            self.sent_count.saturating_sub(sub.position)
+        } else {
+            sub.lagged
+        }
+    }
+
+    fn subscriber_mut(&mut self, sub_id: u64) -> Option<&mut SubscriberStats> {
+        self.subscribers.iter_mut().find(|sub| sub.id == sub_id)
+    }
+
+    /// Move a receiver forward in the channel, the furthest receiver defines the sent count.
+    fn advance_subscriber(
+        &mut self,
+        sub_id: u64,
+        count: u64,
+        log: Option<String>,
+        timestamp: Instant,
+    ) {
+        let Some(sub) = self.subscriber_mut(sub_id) else {
+            return;
+        };
+        sub.position += count;
+        let position = sub.position;
+
+        if position > self.sent_count {
+            self.sent_count = position;
+
+            if crate::trace::is_enabled() {
+                crate::trace::record_channel_event(
+                    self.id,
+                    "send",
+                    timestamp,
+                    log.as_ref()
+                        .map(|log| serde_json::json!({ "message": truncate_result(log.clone()) })),
+                );
+            }
+
+            let limit = get_log_limit();
+            if self.sent_logs.len() >= limit {
+                self.sent_logs.pop_front();
+            }
+            self.sent_logs.push_back(LogEntry::new(
+                self.sent_count,
+                timestamp_nanos(timestamp),
+                log.map(truncate_result),
+                None,
+            ));
+        }
+    }
+
+    /// Advance the received count to the messages seen by every open receiver.
+    fn update_subscribers_received(&mut self, timestamp: Instant) {
+        let Some(seen) = self
+            .subscribers
+            .iter()
+            .filter(|sub| sub.state != ChannelState::Closed)
+            .map(SubscriberStats::seen)
+            .min()
+        else {
+            return;
+        };
+
+        let limit = get_log_limit() as u64;
+        let first = (self.received_count + 1).max(seen.saturating_sub(limit) + 1);
+        for index in first..=seen {
+            if self.received_logs.len() >= limit as usize {
+                self.received_logs.pop_front();
+            }
+            self.received_logs.push_back(LogEntry::new(
+                index,
+                timestamp_nanos(timestamp),
+                None,
+                None,
+            ));
+        }
+        self.received_count = self.received_count.max(seen);
     }
 }
 
@@ -140,6 +444,7 @@ pub(crate) enum ChannelEvent {
         channel_type: ChannelType,
         type_name: &'static str,
         type_size: usize,
+        proxied: bool,
     },
     MessageSent {
         id: u64,
@@ -157,6 +462,53 @@ pub(crate) enum ChannelEvent {
     Notified {
         id: u64,
     },
+    /// A send found the channel full at `blocked_at` and completed at `timestamp`.
+    SendBlocked {
+        id: u64,
+        blocked_at: Instant,
+        timestamp: Instant,
+    },
+    TrySendFailed {
+        id: u64,
+    },
+    #[cfg_attr(not(feature = "tokio"), allow(dead_code))]
+    SubscriberCreated {
+        id: u64,
+        sub_id: u64,
+        label: Option<String>,
+    },
+    #[cfg_attr(not(feature = "tokio"), allow(dead_code))]
+    SubscriberReceived {
+        id: u64,
+        sub_id: u64,
+        log: Option<String>,
+        timestamp: Instant,
+    },
+    /// Messages a receiver lost, `skipped` never reached its queue, `overwritten` were evicted from it.
+    #[cfg_attr(not(feature = "tokio"), allow(dead_code))]
+    SubscriberLagged {
+        id: u64,
+        sub_id: u64,
+        skipped: u64,
+        overwritten: u64,
+        timestamp: Instant,
+    },
+    #[cfg_attr(not(feature = "tokio"), allow(dead_code))]
+    SubscriberQueued {
+        id: u64,
+        sub_id: u64,
+        queued: u64,
+        timestamp: Instant,
+    },
+    #[cfg_attr(not(feature = "tokio"), allow(dead_code))]
+    SubscriberClosed {
+        id: u64,
+        sub_id: u64,
+        timestamp: Instant,
+    },
+    Reset {
+        done_tx: CbSender<()>,
+    },
 }
 
 type ChannelStatsState = (
@@ -170,6 +522,9 @@ pub(crate) static START_TIME: OnceLock<Instant> = OnceLock::new();
 
 pub(crate) static CHANNEL_ID_COUNTER: AtomicU64 = AtomicU64::new(0);
 
+#[cfg_attr(not(feature = "tokio"), allow(dead_code))]
+pub(crate) static SUBSCRIBER_ID_COUNTER: AtomicU64 = AtomicU64::new(0);
+
 const DEFAULT_LOG_LIMIT: usize = 50;
 
 pub(crate) fn get_log_limit() -> usize {
@@ -183,6 +538,7 @@ pub(crate) fn get_log_limit() -> usize {
 pub(crate) fn init_channels_state() -> &'static ChannelStatsState {
     CHANNELS_STATE.get_or_init(|| {
         START_TIME.get_or_init(Instant::now);
+        crate::trace::init_trace();
 
         let (tx, rx) = unbounded::<ChannelEvent>();
         let stats_map = Arc::new(RwLock::new(HashMap::<u64, ChannelStats>::new()));
@@ -201,10 +557,18 @@ pub(crate) fn init_channels_state() -> &'static ChannelStatsState {
                             channel_type,
                             type_name,
                             type_size,
+                            proxied,
                         } => {
                             // Count existing items with the same source location
                             let iter = stats.values().filter(|s| s.source == source).count() as u32;
 
+                            if crate::trace::is_enabled() {
+                                crate::trace::record_channel_created(
+                                    id,
+                                    &resolve_label(source, display_label.as_deref(), Some(iter)),
+                                );
+                            }
+
                             stats.insert(
                                 id,
                                 ChannelStats::new(
@@ -214,14 +578,26 @@ pub(crate) fn init_channels_state() -> &'static ChannelStatsState {
                                     channel_type,
                                     type_name,
                                     type_size,
+                                    proxied,
                                     iter,
                                 ),
                             );
                         }
                         ChannelEvent::MessageSent { id, log, timestamp } => {
+                            if crate::trace::is_enabled() {
+                                crate::trace::record_channel_event(
+                                    id,
+                                    "send",
+                                    timestamp,
+                                    log.as_ref()
+                                        .map(|log| serde_json::json!({ "message": truncate_result(log.clone()) })),
+                                );
+                            }
+
                             if let Some(channel_stats) = stats.get_mut(&id) {
                                 channel_stats.sent_count += 1;
                                 channel_stats.update_state();
+                                channel_stats.record_send_time(timestamp_nanos(timestamp));
 
                                 let limit = get_log_limit();
                                 if channel_stats.sent_logs.len() >= limit {
@@ -236,9 +612,14 @@ pub(crate) fn init_channels_state() -> &'static ChannelStatsState {
                             }
                         }
                         ChannelEvent::MessageReceived { id, timestamp } => {
+                            if crate::trace::is_enabled() {
+                                crate::trace::record_channel_event(id, "recv", timestamp, None);
+                            }
+
                             if let Some(channel_stats) = stats.get_mut(&id) {
                                 channel_stats.received_count += 1;
                                 channel_stats.update_state();
+                                channel_stats.record_receive_time(timestamp_nanos(timestamp));
 
                                 let limit = get_log_limit();
                                 if channel_stats.received_logs.len() >= limit {
@@ -255,6 +636,7 @@ pub(crate) fn init_channels_state() -> &'static ChannelStatsState {
                         ChannelEvent::Closed { id } => {
                             if let Some(channel_stats) = stats.get_mut(&id) {
                                 channel_stats.state = ChannelState::Closed;
+                                channel_stats.release_unmatched_times();
                             }
                         }
                         ChannelEvent::Notified { id } => {
@@ -262,6 +644,111 @@ pub(crate) fn init_channels_state() -> &'static ChannelStatsState {
                                 channel_stats.state = ChannelState::Notified;
                             }
                         }
+                        ChannelEvent::SendBlocked {
+                            id,
+                            blocked_at,
+                            timestamp,
+                        } => {
+                            if let Some(channel_stats) = stats.get_mut(&id) {
+                                channel_stats.record_blocked_send(
+                                    timestamp.duration_since(blocked_at).as_nanos() as u64,
+                                );
+                            }
+                        }
+                        ChannelEvent::TrySendFailed { id } => {
+                            if let Some(channel_stats) = stats.get_mut(&id) {
+                                channel_stats.try_send_failures += 1;
+                            }
+                        }
+                        ChannelEvent::SubscriberCreated { id, sub_id, label } => {
+                            if let Some(channel_stats) = stats.get_mut(&id) {
+                                let position = channel_stats.sent_count;
+                                channel_stats
+                                    .subscribers
+                                    .push(SubscriberStats::new(sub_id, label, position));
+                            }
+                        }
+                        ChannelEvent::SubscriberReceived {
+                            id,
+                            sub_id,
+                            log,
+                            timestamp,
+                        } => {
+                            if crate::trace::is_enabled() {
+                                crate::trace::record_channel_event(id, "recv", timestamp, None);
+                            }
+
+                            if let Some(channel_stats) = stats.get_mut(&id) {
+                                if let Some(sub) = channel_stats.subscriber_mut(sub_id) {
+                                    sub.delivered += 1;
+                                }
+                                channel_stats.advance_subscriber(sub_id, 1, log, timestamp);
+                                channel_stats.update_subscribers_received(timestamp);
+                                channel_stats.update_state();
+                            }
+                        }
+                        ChannelEvent::SubscriberLagged {
+                            id,
+                            sub_id,
+                            skipped,
+                            overwritten,
+                            timestamp,
+                        } => {
+                            if let Some(channel_stats) = stats.get_mut(&id) {
+                                if let Some(sub) = channel_stats.subscriber_mut(sub_id) {
+                                    sub.evicted += overwritten;
+                                    sub.lagged += skipped + overwritten;
+                                }
+                                channel_stats.advance_subscriber(sub_id, skipped, None, timestamp);
+                                channel_stats.update_subscribers_received(timestamp);
+                            }
+                        }
+                        ChannelEvent::SubscriberQueued {
+                            id,
+                            sub_id,
+                            queued,
+                            timestamp,
+                        } => {
+                            if let Some(channel_stats) = stats.get_mut(&id) {
+                                if let Some(sub) = channel_stats.subscriber_mut(sub_id) {
+                                    sub.queued = queued;
+                                }
+                                channel_stats.update_subscribers_received(timestamp);
+                                channel_stats.update_state();
+                            }
+                        }
+                        ChannelEvent::SubscriberClosed {
+                            id,
+                            sub_id,
+                            timestamp,
+                        } => {
+                            if let Some(channel_stats) = stats.get_mut(&id) {
+                                let sent_count = channel_stats.sent_count;
+                                let is_watch = channel_stats.channel_type == ChannelType::Watch;
+                                if let Some(sub) = channel_stats.subscriber_mut(sub_id) {
+                                    if is_watch {
+                                        sub.lagged = sent_count.saturating_sub(sub.position);
+                                    }
+                                    sub.state = ChannelState::Closed;
+                                    sub.queued = 0;
+                                }
+                                channel_stats.update_subscribers_received(timestamp);
+                                if channel_stats
+                                    .subscribers
+                                    .iter()
+                                    .all(|sub| sub.state == ChannelState::Closed)
+                                {
+                                    channel_stats.state = ChannelState::Closed;
+                                    channel_stats.release_unmatched_times();
+                                } else {
+                                    channel_stats.update_state();
+                                }
+                            }
+                        }
+                        ChannelEvent::Reset { done_tx } => {
+                            stats.values_mut().for_each(ChannelStats::reset);
+                            let _ = done_tx.send(());
+                        }
                     }
                 }
             })
@@ -332,7 +819,7 @@ pub trait InstrumentChannelLog {
 }
 
 cfg_if::cfg_if! {
-    if #[cfg(any(feature = "tokio", feature = "futures"))] {
+    if #[cfg(any(feature = "tokio", feature = "futures", feature = "async-channel"))] {
         use std::sync::LazyLock;
         pub static RT: LazyLock<tokio::runtime::Runtime> = LazyLock::new(|| {
             tokio::runtime::Builder::new_multi_thread()
@@ -369,6 +856,43 @@ cfg_if::cfg_if! {
 /// See the `channel!` macro documentation for full usage details.
 #[macro_export]
 macro_rules! channel {
+    // Options collected one by one, so `proxy = <bool>` can appear in any position
+    (@opts $expr:expr; $proxy:tt; $label:expr; $capacity:expr; $log:ident; proxy = $p:tt $(, $($rest:tt)*)?) => {
+        $crate::channel!(@opts $expr; $p; $label; $capacity; $log; $($($rest)*)?)
+    };
+
+    (@opts $expr:expr; $proxy:tt; $label:expr; $capacity:expr; $log:ident; label = $l:expr $(, $($rest:tt)*)?) => {
+        $crate::channel!(@opts $expr; $proxy; Some($l.to_string()); $capacity; $log; $($($rest)*)?)
+    };
+
+    (@opts $expr:expr; $proxy:tt; $label:expr; $capacity:expr; $log:ident; capacity = $c:expr $(, $($rest:tt)*)?) => {
+        $crate::channel!(@opts $expr; $proxy; $label; { const _: usize = $c; Some($c) }; $log; $($($rest)*)?)
+    };
+
+    (@opts $expr:expr; $proxy:tt; $label:expr; $capacity:expr; $log:ident; log = true $(, $($rest:tt)*)?) => {
+        $crate::channel!(@opts $expr; $proxy; $label; $capacity; log; $($($rest)*)?)
+    };
+
+    (@opts $expr:expr; true; $label:expr; $capacity:expr; plain;) => {{
+        const CHANNEL_ID: &'static str = concat!(file!(), ":", line!());
+        $crate::InstrumentChannel::instrument($expr, CHANNEL_ID, $label, $capacity)
+    }};
+
+    (@opts $expr:expr; true; $label:expr; $capacity:expr; log;) => {{
+        const CHANNEL_ID: &'static str = concat!(file!(), ":", line!());
+        $crate::InstrumentChannelLog::instrument_log($expr, CHANNEL_ID, $label, $capacity)
+    }};
+
+    (@opts $expr:expr; false; $label:expr; $capacity:expr; plain;) => {{
+        const CHANNEL_ID: &'static str = concat!(file!(), ":", line!());
+        $crate::InstrumentChannelDirect::instrument_direct($expr, CHANNEL_ID, $label, $capacity)
+    }};
+
+    (@opts $expr:expr; false; $label:expr; $capacity:expr; log;) => {{
+        const CHANNEL_ID: &'static str = concat!(file!(), ":", line!());
+        $crate::InstrumentChannelDirectLog::instrument_direct_log($expr, CHANNEL_ID, $label, $capacity)
+    }};
+
     ($expr:expr) => {{
         const CHANNEL_ID: &'static str = concat!(file!(), ":", line!());
         $crate::InstrumentChannel::instrument($expr, CHANNEL_ID, None, None)
@@ -510,6 +1034,11 @@ macro_rules! channel {
             Some($capacity),
         )
     }};
+
+    // Any other order of options, or options including `proxy = <bool>`
+    ($expr:expr, $($opts:tt)+) => {
+        $crate::channel!(@opts $expr; true; None; None; plain; $($opts)+)
+    };
 }
 
 fn get_all_channel_stats() -> HashMap<u64, ChannelStats> {
@@ -572,3 +1101,13 @@ pub fn get_channel_logs(channel_id: &str) -> Option<ChannelLogs> {
         received_logs: channel_stats.received_logs.iter().rev().cloned().collect(),
     })
 }
+
+/// Zero the stats of all instrumented channels, events sent before the reset are applied first.
+pub(crate) fn reset_channels() {
+    if let Some((tx, _)) = CHANNELS_STATE.get() {
+        let (done_tx, done_rx) = bounded::<()>(1);
+        if tx.send(ChannelEvent::Reset { done_tx }).is_ok() {
+            let _ = done_rx.recv_timeout(std::time::Duration::from_millis(RECV_TIMEOUT_MS));
+        }
+    }
+}
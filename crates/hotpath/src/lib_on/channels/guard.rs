@@ -123,6 +123,8 @@ impl Default for ChannelsGuard {
 
 impl Drop for ChannelsGuard {
     fn drop(&mut self) {
+        crate::trace::flush_trace();
+
         let elapsed = self.start_time.elapsed();
         let channels = get_sorted_channel_stats();
 
@@ -132,7 +134,7 @@ impl Drop for ChannelsGuard {
         }
 
         match self.format {
-            Format::Table => {
+            Format::Table | Format::Folded => {
                 println!(
                     "\n=== Channel Statistics (runtime: {:.2}s) ===",
                     elapsed.as_secs_f64()
@@ -150,6 +152,16 @@ impl Drop for ChannelsGuard {
                     Cell::new("Mem"),
                 ]));
 
+                let mut subscribers_table = Table::new();
+                subscribers_table.add_row(Row::new(vec![
+                    Cell::new("Channel"),
+                    Cell::new("Subscriber"),
+                    Cell::new("State"),
+                    Cell::new("Received"),
+                    Cell::new("Queued"),
+                    Cell::new("Lagged"),
+                ]));
+
                 for channel_stats in channels {
                     let label = resolve_label(
                         channel_stats.source,
@@ -165,10 +177,27 @@ impl Drop for ChannelsGuard {
                         Cell::new(&channel_stats.queued().to_string()),
                         Cell::new(&format_bytes(channel_stats.queued_bytes())),
                     ]));
+
+                    for (index, sub) in channel_stats.subscribers.iter().enumerate() {
+                        let sub_label = sub.label.clone().unwrap_or_else(|| format!("#{}", index));
+                        subscribers_table.add_row(Row::new(vec![
+                            Cell::new(&label),
+                            Cell::new(&sub_label),
+                            Cell::new(sub.state.as_str()),
+                            Cell::new(&sub.received().to_string()),
+                            Cell::new(&sub.queued.to_string()),
+                            Cell::new(&channel_stats.subscriber_lagged(sub).to_string()),
+                        ]));
+                    }
                 }
 
                 println!("\nChannels:");
                 table.printstd();
+
+                if subscribers_table.len() > 1 {
+                    println!("\nSubscribers:");
+                    subscribers_table.printstd();
+                }
             }
             Format::Json => {
                 let channels_json = crate::channels::ChannelsJson {
@@ -0,0 +1,142 @@
+//! Message counting for channels instrumented with `proxy = false`.
+//!
+//! The wrapper handles report sends and receives themselves, so no forwarder threads or
+//! proxy channels are involved and the channel keeps its exact capacity and wakeups.
+
+#[cfg(target_os = "linux")]
+use quanta::Instant;
+use std::mem;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+#[cfg(not(target_os = "linux"))]
+use std::time::Instant;
+
+use crossbeam_channel::Sender as CbSender;
+
+use crate::channels::{init_channels_state, ChannelEvent, ChannelType, CHANNEL_ID_COUNTER};
+
+pub(crate) type TrackerHandle<T> = Arc<Tracker<T>>;
+
+/// Shared by all handles of a channel, the channel closes when either side is fully dropped.
+pub(crate) struct Tracker<T> {
+    id: u64,
+    stats_tx: CbSender<ChannelEvent>,
+    senders: AtomicUsize,
+    receivers: AtomicUsize,
+    closed: AtomicBool,
+    log: Option<fn(&T) -> String>,
+}
+
+impl<T> Tracker<T> {
+    /// Register a channel, `capacity` is `None` for unbounded channels.
+    pub(crate) fn new(
+        source: &'static str,
+        label: Option<String>,
+        capacity: Option<usize>,
+        log: Option<fn(&T) -> String>,
+    ) -> TrackerHandle<T> {
+        let (stats_tx, _) = init_channels_state();
+
+        let id = CHANNEL_ID_COUNTER.fetch_add(1, Ordering::Relaxed);
+
+        let _ = stats_tx.send(ChannelEvent::Created {
+            id,
+            source,
+            display_label: label,
+            channel_type: match capacity {
+                Some(capacity) => ChannelType::Bounded(capacity),
+                None => ChannelType::Unbounded,
+            },
+            type_name: std::any::type_name::<T>(),
+            type_size: mem::size_of::<T>(),
+            proxied: false,
+        });
+
+        Arc::new(Self {
+            id,
+            stats_tx: stats_tx.clone(),
+            senders: AtomicUsize::new(1),
+            receivers: AtomicUsize::new(1),
+            closed: AtomicBool::new(false),
+            log,
+        })
+    }
+
+    /// Format the message before it's moved into the channel, if logging is enabled.
+    #[inline]
+    pub(crate) fn log(&self, msg: &T) -> Option<String> {
+        self.log.map(|log| log(msg))
+    }
+
+    #[inline]
+    pub(crate) fn sent(&self, log: Option<String>) {
+        let _ = self.stats_tx.send(ChannelEvent::MessageSent {
+            id: self.id,
+            log,
+            timestamp: Instant::now(),
+        });
+    }
+
+    #[inline]
+    pub(crate) fn received(&self) {
+        let _ = self.stats_tx.send(ChannelEvent::MessageReceived {
+            id: self.id,
+            timestamp: Instant::now(),
+        });
+    }
+
+    /// Run a send that found the channel full, timing how long it waits for space.
+    pub(crate) fn blocked_send<R>(&self, send: impl FnOnce() -> R) -> R {
+        let blocked_at = Instant::now();
+        let result = send();
+        let _ = self.stats_tx.send(ChannelEvent::SendBlocked {
+            id: self.id,
+            blocked_at,
+            timestamp: Instant::now(),
+        });
+        result
+    }
+
+    #[inline]
+    pub(crate) fn try_send_failed(&self) {
+        let _ = self
+            .stats_tx
+            .send(ChannelEvent::TrySendFailed { id: self.id });
+    }
+
+    pub(crate) fn clone_sender(&self) {
+        self.senders.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn drop_sender(&self) {
+        if self.senders.fetch_sub(1, Ordering::AcqRel) == 1 {
+            self.close();
+        }
+    }
+
+    // Only crossbeam receivers can be cloned
+    #[cfg_attr(not(feature = "crossbeam"), allow(dead_code))]
+    pub(crate) fn clone_receiver(&self) {
+        self.receivers.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn drop_receiver(&self) {
+        if self.receivers.fetch_sub(1, Ordering::AcqRel) == 1 {
+            self.close();
+        }
+    }
+
+    fn close(&self) {
+        if !self.closed.swap(true, Ordering::AcqRel) {
+            let _ = self.stats_tx.send(ChannelEvent::Closed { id: self.id });
+        }
+    }
+}
+
+/// Bounded std channels don't expose their capacity, so it has to be passed to `channel!`.
+pub(crate) fn required_capacity(capacity: Option<usize>) -> usize {
+    let Some(capacity) = capacity else {
+        panic!("Capacity is required for bounded std channels, because they don't expose their capacity in a public API");
+    };
+    capacity
+}
@@ -1,5 +1,10 @@
+#[cfg(feature = "async-channel")]
+pub(crate) mod async_channel;
 #[cfg(feature = "crossbeam")]
 pub(crate) mod crossbeam;
+pub mod direct;
+#[cfg(feature = "flume")]
+pub(crate) mod flume;
 #[cfg(feature = "futures")]
 pub(crate) mod ftc;
 pub(crate) mod std;
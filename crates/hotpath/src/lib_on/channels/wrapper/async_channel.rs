@@ -0,0 +1,178 @@
+use ::async_channel::{self, Receiver, Sender, TrySendError};
+#[cfg(target_os = "linux")]
+use quanta::Instant;
+use std::mem;
+use std::sync::atomic::Ordering;
+#[cfg(not(target_os = "linux"))]
+use std::time::Instant;
+
+use crate::channels::RT;
+use crate::channels::{init_channels_state, ChannelEvent, ChannelType, CHANNEL_ID_COUNTER};
+
+/// Internal implementation for wrapping async-channel channels with optional logging.
+fn wrap_channel_impl<T, F>(
+    inner: (Sender<T>, Receiver<T>),
+    source: &'static str,
+    label: Option<String>,
+    mut log_on_send: F,
+) -> (Sender<T>, Receiver<T>)
+where
+    T: Send + 'static,
+    F: FnMut(&T) -> Option<String> + Send + 'static,
+{
+    let (inner_tx, inner_rx) = inner;
+    let type_name = std::any::type_name::<T>();
+
+    // async-channel uses the same Sender/Receiver types for both bounded and unbounded
+    // channels, only the capacity tells them apart
+    let (channel_type, (outer_tx, to_inner_rx), (from_inner_tx, outer_rx)) =
+        match inner_tx.capacity() {
+            // Proxy channels must be minimal to avoid inflating buffering.
+            // See: https://github.com/pawurb/hotpath-rs/issues/98
+            Some(capacity) => (
+                ChannelType::Bounded(capacity),
+                async_channel::bounded::<T>(1),
+                async_channel::bounded::<T>(1),
+            ),
+            None => (
+                ChannelType::Unbounded,
+                async_channel::unbounded::<T>(),
+                async_channel::unbounded::<T>(),
+            ),
+        };
+
+    let (stats_tx, _) = init_channels_state();
+
+    let id = CHANNEL_ID_COUNTER.fetch_add(1, Ordering::Relaxed);
+
+    let _ = stats_tx.send(ChannelEvent::Created {
+        id,
+        source,
+        display_label: label,
+        channel_type,
+        type_name,
+        type_size: mem::size_of::<T>(),
+        proxied: true,
+    });
+
+    let stats_tx_send = stats_tx.clone();
+    let stats_tx_recv = stats_tx.clone();
+
+    // Create a signal channel to notify send-forwarder when outer_rx is closed
+    let (close_signal_tx, mut close_signal_rx) = tokio::sync::oneshot::channel::<()>();
+
+    // Forward outer -> inner (proxy the send path)
+    RT.spawn(async move {
+        loop {
+            tokio::select! {
+                msg = to_inner_rx.recv() => {
+                    match msg {
+                        Ok(msg) => {
+                            let log = log_on_send(&msg);
+                            // Time the send only when the channel is full, so it reflects backpressure
+                            match inner_tx.try_send(msg) {
+                                Ok(()) => {}
+                                Err(TrySendError::Full(msg)) => {
+                                    let blocked_at = Instant::now();
+                                    if inner_tx.send(msg).await.is_err() {
+                                        to_inner_rx.close();
+                                        break;
+                                    }
+                                    let _ = stats_tx_send.send(ChannelEvent::SendBlocked {
+                                        id,
+                                        blocked_at,
+                                        timestamp: Instant::now(),
+                                    });
+                                }
+                                Err(TrySendError::Closed(_)) => {
+                                    to_inner_rx.close();
+                                    break;
+                                }
+                            }
+                            let _ = stats_tx_send.send(ChannelEvent::MessageSent {
+                                id,
+                                log,
+                                timestamp: Instant::now(),
+                            });
+                        }
+                        Err(_) => break, // Outer sender dropped
+                    }
+                }
+                _ = &mut close_signal_rx => {
+                    // Outer receiver was closed/dropped, close our receiver to reject further sends
+                    to_inner_rx.close();
+                    break;
+                }
+            }
+        }
+        // Channel is closed
+        let _ = stats_tx_send.send(ChannelEvent::Closed { id });
+    });
+
+    // Forward inner -> outer (proxy the recv path)
+    RT.spawn(async move {
+        while let Ok(msg) = inner_rx.recv().await {
+            if from_inner_tx.send(msg).await.is_ok() {
+                let _ = stats_tx_recv.send(ChannelEvent::MessageReceived {
+                    id,
+                    timestamp: Instant::now(),
+                });
+            } else {
+                // Outer receiver was closed
+                let _ = close_signal_tx.send(());
+                break;
+            }
+        }
+        // Channel is closed (either inner sender dropped or outer receiver closed)
+        let _ = stats_tx_recv.send(ChannelEvent::Closed { id });
+    });
+
+    (outer_tx, outer_rx)
+}
+
+/// Wrap an async-channel channel with proxy ends. Returns (outer_tx, outer_rx).
+/// All messages pass through the two forwarders.
+pub(crate) fn wrap_channel<T: Send + 'static>(
+    inner: (Sender<T>, Receiver<T>),
+    source: &'static str,
+    label: Option<String>,
+) -> (Sender<T>, Receiver<T>) {
+    wrap_channel_impl(inner, source, label, |_| None)
+}
+
+/// Wrap an async-channel channel with logging enabled. Returns (outer_tx, outer_rx).
+pub(crate) fn wrap_channel_log<T: Send + std::fmt::Debug + 'static>(
+    inner: (Sender<T>, Receiver<T>),
+    source: &'static str,
+    label: Option<String>,
+) -> (Sender<T>, Receiver<T>) {
+    wrap_channel_impl(inner, source, label, |msg| Some(format!("{:?}", msg)))
+}
+
+use crate::channels::InstrumentChannel;
+
+impl<T: Send + 'static> InstrumentChannel for (Sender<T>, Receiver<T>) {
+    type Output = (Sender<T>, Receiver<T>);
+    fn instrument(
+        self,
+        source: &'static str,
+        label: Option<String>,
+        _capacity: Option<usize>,
+    ) -> Self::Output {
+        wrap_channel(self, source, label)
+    }
+}
+
+use crate::channels::InstrumentChannelLog;
+
+impl<T: Send + std::fmt::Debug + 'static> InstrumentChannelLog for (Sender<T>, Receiver<T>) {
+    type Output = (Sender<T>, Receiver<T>);
+    fn instrument_log(
+        self,
+        source: &'static str,
+        label: Option<String>,
+        _capacity: Option<usize>,
+    ) -> Self::Output {
+        wrap_channel_log(self, source, label)
+    }
+}
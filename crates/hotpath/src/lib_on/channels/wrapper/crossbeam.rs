@@ -1,4 +1,4 @@
-use crossbeam_channel::{self, Receiver, Sender};
+use crossbeam_channel::{self, Receiver, Sender, TrySendError};
 #[cfg(target_os = "linux")]
 use quanta::Instant;
 use std::mem;
@@ -39,6 +39,7 @@ where
         channel_type: ChannelType::Bounded(capacity),
         type_name,
         type_size: mem::size_of::<T>(),
+        proxied: true,
     });
 
     let stats_tx_send = stats_tx.clone();
@@ -59,9 +60,25 @@ where
                     match msg {
                         Ok(msg) => {
                             let log = log_on_send(&msg);
-                            if inner_tx.send(msg).is_err() {
-                                // Inner receiver dropped
-                                break;
+                            // Time the send only when the channel is full, so it reflects backpressure
+                            match inner_tx.try_send(msg) {
+                                Ok(()) => {}
+                                Err(TrySendError::Full(msg)) => {
+                                    let blocked_at = Instant::now();
+                                    if inner_tx.send(msg).is_err() {
+                                        // Inner receiver dropped
+                                        break;
+                                    }
+                                    let _ = stats_tx_send.send(ChannelEvent::SendBlocked {
+                                        id,
+                                        blocked_at,
+                                        timestamp: Instant::now(),
+                                    });
+                                }
+                                Err(TrySendError::Disconnected(_)) => {
+                                    // Inner receiver dropped
+                                    break;
+                                }
                             }
                             let _ = stats_tx_send.send(ChannelEvent::MessageSent {
                                 id,
@@ -152,6 +169,7 @@ where
         channel_type: ChannelType::Unbounded,
         type_name,
         type_size: mem::size_of::<T>(),
+        proxied: true,
     });
 
     let stats_tx_send = stats_tx.clone();
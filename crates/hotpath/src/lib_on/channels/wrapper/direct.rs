@@ -0,0 +1,44 @@
+//! Channel wrappers used by `channel!(expr, proxy = false)`.
+//!
+//! Instead of routing messages through proxy channels and forwarder threads, these types wrap
+//! the original sender and receiver and count messages on `send` and `recv`. The channel keeps
+//! its exact capacity and wakeup behavior, at the cost of a different type than the one
+//! passed to the macro. When profiling is disabled these types are aliases of the original
+//! handles, so the wrappers only expose methods the original handles have too.
+//!
+//! Supported channels:
+//! - [`std::sync::mpsc::channel`] and [`std::sync::mpsc::sync_channel`]
+//! - [`crossbeam_channel::bounded`] and [`crossbeam_channel::unbounded`] (`crossbeam` feature)
+
+#[cfg(feature = "crossbeam")]
+pub mod crossbeam;
+pub mod std;
+
+/// Trait for instrumenting channels without proxy forwarders.
+///
+/// This trait is not intended for direct use. Use the `channel!` macro with `proxy = false` instead.
+#[doc(hidden)]
+pub trait InstrumentChannelDirect {
+    type Output;
+    fn instrument_direct(
+        self,
+        source: &'static str,
+        label: Option<String>,
+        capacity: Option<usize>,
+    ) -> Self::Output;
+}
+
+/// Trait for instrumenting channels without proxy forwarders, with message logging.
+///
+/// This trait is not intended for direct use. Use the `channel!` macro with `proxy = false`
+/// and `log = true` instead.
+#[doc(hidden)]
+pub trait InstrumentChannelDirectLog {
+    type Output;
+    fn instrument_direct_log(
+        self,
+        source: &'static str,
+        label: Option<String>,
+        capacity: Option<usize>,
+    ) -> Self::Output;
+}
@@ -0,0 +1,280 @@
+//! Wrappers for [`crossbeam_channel`] channels.
+
+use crossbeam_channel::{
+    self, RecvError, RecvTimeoutError, SendError, SendTimeoutError, TryRecvError, TrySendError,
+};
+use std::fmt;
+use std::time::Duration;
+
+use super::{InstrumentChannelDirect, InstrumentChannelDirectLog};
+use crate::channels::{Tracker, TrackerHandle};
+
+/// Sending half of an instrumented crossbeam channel.
+pub struct Sender<T> {
+    inner: crossbeam_channel::Sender<T>,
+    tracker: TrackerHandle<T>,
+}
+
+impl<T> Sender<T> {
+    /// See [`crossbeam_channel::Sender::send`].
+    pub fn send(&self, msg: T) -> Result<(), SendError<T>> {
+        let log = self.tracker.log(&msg);
+        match self.inner.try_send(msg) {
+            Ok(()) => {}
+            Err(TrySendError::Full(msg)) => self.tracker.blocked_send(|| self.inner.send(msg))?,
+            Err(TrySendError::Disconnected(msg)) => return Err(SendError(msg)),
+        }
+        self.tracker.sent(log);
+        Ok(())
+    }
+
+    /// See [`crossbeam_channel::Sender::try_send`].
+    pub fn try_send(&self, msg: T) -> Result<(), TrySendError<T>> {
+        let log = self.tracker.log(&msg);
+        if let Err(err) = self.inner.try_send(msg) {
+            if err.is_full() {
+                self.tracker.try_send_failed();
+            }
+            return Err(err);
+        }
+        self.tracker.sent(log);
+        Ok(())
+    }
+
+    /// See [`crossbeam_channel::Sender::send_timeout`].
+    pub fn send_timeout(&self, msg: T, timeout: Duration) -> Result<(), SendTimeoutError<T>> {
+        let log = self.tracker.log(&msg);
+        match self.inner.try_send(msg) {
+            Ok(()) => {}
+            Err(TrySendError::Full(msg)) => self
+                .tracker
+                .blocked_send(|| self.inner.send_timeout(msg, timeout))?,
+            Err(TrySendError::Disconnected(msg)) => {
+                return Err(SendTimeoutError::Disconnected(msg))
+            }
+        }
+        self.tracker.sent(log);
+        Ok(())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.inner.is_full()
+    }
+
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    pub fn capacity(&self) -> Option<usize> {
+        self.inner.capacity()
+    }
+}
+
+impl<T> Clone for Sender<T> {
+    fn clone(&self) -> Self {
+        self.tracker.clone_sender();
+        Self {
+            inner: self.inner.clone(),
+            tracker: self.tracker.clone(),
+        }
+    }
+}
+
+impl<T> Drop for Sender<T> {
+    fn drop(&mut self) {
+        self.tracker.drop_sender();
+    }
+}
+
+impl<T> fmt::Debug for Sender<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Sender").finish_non_exhaustive()
+    }
+}
+
+/// Receiving half of an instrumented crossbeam channel.
+pub struct Receiver<T> {
+    inner: crossbeam_channel::Receiver<T>,
+    tracker: TrackerHandle<T>,
+}
+
+impl<T> Receiver<T> {
+    /// See [`crossbeam_channel::Receiver::recv`].
+    pub fn recv(&self) -> Result<T, RecvError> {
+        let msg = self.inner.recv()?;
+        self.tracker.received();
+        Ok(msg)
+    }
+
+    /// See [`crossbeam_channel::Receiver::try_recv`].
+    pub fn try_recv(&self) -> Result<T, TryRecvError> {
+        let msg = self.inner.try_recv()?;
+        self.tracker.received();
+        Ok(msg)
+    }
+
+    /// See [`crossbeam_channel::Receiver::recv_timeout`].
+    pub fn recv_timeout(&self, timeout: Duration) -> Result<T, RecvTimeoutError> {
+        let msg = self.inner.recv_timeout(timeout)?;
+        self.tracker.received();
+        Ok(msg)
+    }
+
+    /// See [`crossbeam_channel::Receiver::iter`].
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter { rx: self }
+    }
+
+    /// See [`crossbeam_channel::Receiver::try_iter`].
+    pub fn try_iter(&self) -> TryIter<'_, T> {
+        TryIter { rx: self }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.inner.is_full()
+    }
+
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    pub fn capacity(&self) -> Option<usize> {
+        self.inner.capacity()
+    }
+}
+
+impl<T> Clone for Receiver<T> {
+    fn clone(&self) -> Self {
+        self.tracker.clone_receiver();
+        Self {
+            inner: self.inner.clone(),
+            tracker: self.tracker.clone(),
+        }
+    }
+}
+
+impl<T> Drop for Receiver<T> {
+    fn drop(&mut self) {
+        self.tracker.drop_receiver();
+    }
+}
+
+impl<T> fmt::Debug for Receiver<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Receiver").finish_non_exhaustive()
+    }
+}
+
+/// Blocking iterator over messages of a [`Receiver`].
+#[derive(Debug)]
+pub struct Iter<'a, T> {
+    rx: &'a Receiver<T>,
+}
+
+impl<T> Iterator for Iter<'_, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.rx.recv().ok()
+    }
+}
+
+/// Non-blocking iterator over messages of a [`Receiver`].
+#[derive(Debug)]
+pub struct TryIter<'a, T> {
+    rx: &'a Receiver<T>,
+}
+
+impl<T> Iterator for TryIter<'_, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.rx.try_recv().ok()
+    }
+}
+
+/// Owning blocking iterator over messages of a [`Receiver`].
+#[derive(Debug)]
+pub struct IntoIter<T> {
+    rx: Receiver<T>,
+}
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.rx.recv().ok()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a Receiver<T> {
+    type Item = T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
+
+impl<T> IntoIterator for Receiver<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> IntoIter<T> {
+        IntoIter { rx: self }
+    }
+}
+
+fn wrap_channel<T>(
+    (inner_tx, inner_rx): (crossbeam_channel::Sender<T>, crossbeam_channel::Receiver<T>),
+    source: &'static str,
+    label: Option<String>,
+    log: Option<fn(&T) -> String>,
+) -> (Sender<T>, Receiver<T>) {
+    // Crossbeam channels expose their capacity, so it doesn't have to be passed to the macro
+    let tracker = Tracker::new(source, label, inner_tx.capacity(), log);
+    (
+        Sender {
+            inner: inner_tx,
+            tracker: tracker.clone(),
+        },
+        Receiver {
+            inner: inner_rx,
+            tracker,
+        },
+    )
+}
+
+impl<T> InstrumentChannelDirect for (crossbeam_channel::Sender<T>, crossbeam_channel::Receiver<T>) {
+    type Output = (Sender<T>, Receiver<T>);
+    fn instrument_direct(
+        self,
+        source: &'static str,
+        label: Option<String>,
+        _capacity: Option<usize>,
+    ) -> Self::Output {
+        wrap_channel(self, source, label, None)
+    }
+}
+
+impl<T: fmt::Debug> InstrumentChannelDirectLog
+    for (crossbeam_channel::Sender<T>, crossbeam_channel::Receiver<T>)
+{
+    type Output = (Sender<T>, Receiver<T>);
+    fn instrument_direct_log(
+        self,
+        source: &'static str,
+        label: Option<String>,
+        _capacity: Option<usize>,
+    ) -> Self::Output {
+        wrap_channel(self, source, label, Some(|msg| format!("{:?}", msg)))
+    }
+}
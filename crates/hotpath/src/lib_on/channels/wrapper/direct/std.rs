@@ -0,0 +1,306 @@
+//! Wrappers for [`std::sync::mpsc`] channels.
+
+use std::fmt;
+use std::sync::mpsc::{self, RecvError, RecvTimeoutError, SendError, TryRecvError, TrySendError};
+use std::time::Duration;
+
+use super::{InstrumentChannelDirect, InstrumentChannelDirectLog};
+use crate::channels::{required_capacity, Tracker, TrackerHandle};
+
+/// Sending half of an instrumented [`mpsc::channel`].
+pub struct Sender<T> {
+    inner: mpsc::Sender<T>,
+    tracker: TrackerHandle<T>,
+}
+
+impl<T> Sender<T> {
+    /// See [`mpsc::Sender::send`].
+    pub fn send(&self, t: T) -> Result<(), SendError<T>> {
+        let log = self.tracker.log(&t);
+        self.inner.send(t)?;
+        self.tracker.sent(log);
+        Ok(())
+    }
+}
+
+impl<T> Clone for Sender<T> {
+    fn clone(&self) -> Self {
+        self.tracker.clone_sender();
+        Self {
+            inner: self.inner.clone(),
+            tracker: self.tracker.clone(),
+        }
+    }
+}
+
+impl<T> Drop for Sender<T> {
+    fn drop(&mut self) {
+        self.tracker.drop_sender();
+    }
+}
+
+impl<T> fmt::Debug for Sender<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Sender").finish_non_exhaustive()
+    }
+}
+
+/// Sending half of an instrumented [`mpsc::sync_channel`].
+pub struct SyncSender<T> {
+    inner: mpsc::SyncSender<T>,
+    tracker: TrackerHandle<T>,
+}
+
+impl<T> SyncSender<T> {
+    /// See [`mpsc::SyncSender::send`].
+    pub fn send(&self, t: T) -> Result<(), SendError<T>> {
+        let log = self.tracker.log(&t);
+        match self.inner.try_send(t) {
+            Ok(()) => {}
+            Err(TrySendError::Full(t)) => self.tracker.blocked_send(|| self.inner.send(t))?,
+            Err(TrySendError::Disconnected(t)) => return Err(SendError(t)),
+        }
+        self.tracker.sent(log);
+        Ok(())
+    }
+
+    /// See [`mpsc::SyncSender::try_send`].
+    pub fn try_send(&self, t: T) -> Result<(), TrySendError<T>> {
+        let log = self.tracker.log(&t);
+        if let Err(err) = self.inner.try_send(t) {
+            if matches!(err, TrySendError::Full(_)) {
+                self.tracker.try_send_failed();
+            }
+            return Err(err);
+        }
+        self.tracker.sent(log);
+        Ok(())
+    }
+}
+
+impl<T> Clone for SyncSender<T> {
+    fn clone(&self) -> Self {
+        self.tracker.clone_sender();
+        Self {
+            inner: self.inner.clone(),
+            tracker: self.tracker.clone(),
+        }
+    }
+}
+
+impl<T> Drop for SyncSender<T> {
+    fn drop(&mut self) {
+        self.tracker.drop_sender();
+    }
+}
+
+impl<T> fmt::Debug for SyncSender<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SyncSender").finish_non_exhaustive()
+    }
+}
+
+/// Receiving half of an instrumented [`mpsc::channel`] or [`mpsc::sync_channel`].
+pub struct Receiver<T> {
+    inner: mpsc::Receiver<T>,
+    tracker: TrackerHandle<T>,
+}
+
+impl<T> Receiver<T> {
+    /// See [`mpsc::Receiver::recv`].
+    pub fn recv(&self) -> Result<T, RecvError> {
+        let msg = self.inner.recv()?;
+        self.tracker.received();
+        Ok(msg)
+    }
+
+    /// See [`mpsc::Receiver::try_recv`].
+    pub fn try_recv(&self) -> Result<T, TryRecvError> {
+        let msg = self.inner.try_recv()?;
+        self.tracker.received();
+        Ok(msg)
+    }
+
+    /// See [`mpsc::Receiver::recv_timeout`].
+    pub fn recv_timeout(&self, timeout: Duration) -> Result<T, RecvTimeoutError> {
+        let msg = self.inner.recv_timeout(timeout)?;
+        self.tracker.received();
+        Ok(msg)
+    }
+
+    /// See [`mpsc::Receiver::iter`].
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter { rx: self }
+    }
+
+    /// See [`mpsc::Receiver::try_iter`].
+    pub fn try_iter(&self) -> TryIter<'_, T> {
+        TryIter { rx: self }
+    }
+}
+
+impl<T> Drop for Receiver<T> {
+    fn drop(&mut self) {
+        self.tracker.drop_receiver();
+    }
+}
+
+impl<T> fmt::Debug for Receiver<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Receiver").finish_non_exhaustive()
+    }
+}
+
+/// Blocking iterator over messages of a [`Receiver`].
+#[derive(Debug)]
+pub struct Iter<'a, T> {
+    rx: &'a Receiver<T>,
+}
+
+impl<T> Iterator for Iter<'_, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.rx.recv().ok()
+    }
+}
+
+/// Non-blocking iterator over messages of a [`Receiver`].
+#[derive(Debug)]
+pub struct TryIter<'a, T> {
+    rx: &'a Receiver<T>,
+}
+
+impl<T> Iterator for TryIter<'_, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.rx.try_recv().ok()
+    }
+}
+
+/// Owning blocking iterator over messages of a [`Receiver`].
+#[derive(Debug)]
+pub struct IntoIter<T> {
+    rx: Receiver<T>,
+}
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.rx.recv().ok()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a Receiver<T> {
+    type Item = T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
+
+impl<T> IntoIterator for Receiver<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> IntoIter<T> {
+        IntoIter { rx: self }
+    }
+}
+
+fn wrap_channel<T>(
+    (inner_tx, inner_rx): (mpsc::Sender<T>, mpsc::Receiver<T>),
+    source: &'static str,
+    label: Option<String>,
+    log: Option<fn(&T) -> String>,
+) -> (Sender<T>, Receiver<T>) {
+    let tracker = Tracker::new(source, label, None, log);
+    (
+        Sender {
+            inner: inner_tx,
+            tracker: tracker.clone(),
+        },
+        Receiver {
+            inner: inner_rx,
+            tracker,
+        },
+    )
+}
+
+fn wrap_sync_channel<T>(
+    (inner_tx, inner_rx): (mpsc::SyncSender<T>, mpsc::Receiver<T>),
+    source: &'static str,
+    label: Option<String>,
+    capacity: Option<usize>,
+    log: Option<fn(&T) -> String>,
+) -> (SyncSender<T>, Receiver<T>) {
+    let capacity = required_capacity(capacity);
+    let tracker = Tracker::new(source, label, Some(capacity), log);
+    (
+        SyncSender {
+            inner: inner_tx,
+            tracker: tracker.clone(),
+        },
+        Receiver {
+            inner: inner_rx,
+            tracker,
+        },
+    )
+}
+
+impl<T> InstrumentChannelDirect for (mpsc::Sender<T>, mpsc::Receiver<T>) {
+    type Output = (Sender<T>, Receiver<T>);
+    fn instrument_direct(
+        self,
+        source: &'static str,
+        label: Option<String>,
+        _capacity: Option<usize>,
+    ) -> Self::Output {
+        wrap_channel(self, source, label, None)
+    }
+}
+
+impl<T: fmt::Debug> InstrumentChannelDirectLog for (mpsc::Sender<T>, mpsc::Receiver<T>) {
+    type Output = (Sender<T>, Receiver<T>);
+    fn instrument_direct_log(
+        self,
+        source: &'static str,
+        label: Option<String>,
+        _capacity: Option<usize>,
+    ) -> Self::Output {
+        wrap_channel(self, source, label, Some(|msg| format!("{:?}", msg)))
+    }
+}
+
+impl<T> InstrumentChannelDirect for (mpsc::SyncSender<T>, mpsc::Receiver<T>) {
+    type Output = (SyncSender<T>, Receiver<T>);
+    fn instrument_direct(
+        self,
+        source: &'static str,
+        label: Option<String>,
+        capacity: Option<usize>,
+    ) -> Self::Output {
+        wrap_sync_channel(self, source, label, capacity, None)
+    }
+}
+
+impl<T: fmt::Debug> InstrumentChannelDirectLog for (mpsc::SyncSender<T>, mpsc::Receiver<T>) {
+    type Output = (SyncSender<T>, Receiver<T>);
+    fn instrument_direct_log(
+        self,
+        source: &'static str,
+        label: Option<String>,
+        capacity: Option<usize>,
+    ) -> Self::Output {
+        wrap_sync_channel(
+            self,
+            source,
+            label,
+            capacity,
+            Some(|msg| format!("{:?}", msg)),
+        )
+    }
+}
@@ -41,6 +41,7 @@ where
         channel_type: ChannelType::Bounded(capacity),
         type_name,
         type_size: mem::size_of::<T>(),
+        proxied: true,
     });
 
     let stats_tx_send = stats_tx.clone();
@@ -58,9 +59,22 @@ where
                     match msg {
                         Some(msg) => {
                             let log = get_msg_log(&msg);
-                            if inner_tx.send(msg).await.is_err() {
-                                to_inner_rx.close();
-                                break;
+                            // Time the send only when the channel is full, so it reflects backpressure
+                            if let Err(err) = inner_tx.try_send(msg) {
+                                if err.is_disconnected() {
+                                    to_inner_rx.close();
+                                    break;
+                                }
+                                let blocked_at = Instant::now();
+                                if inner_tx.send(err.into_inner()).await.is_err() {
+                                    to_inner_rx.close();
+                                    break;
+                                }
+                                let _ = stats_tx_send.send(ChannelEvent::SendBlocked {
+                                    id,
+                                    blocked_at,
+                                    timestamp: Instant::now(),
+                                });
                             }
                             let _ = stats_tx_send.send(ChannelEvent::MessageSent {
                                 id,
@@ -155,6 +169,7 @@ where
         channel_type: ChannelType::Unbounded,
         type_name,
         type_size: mem::size_of::<T>(),
+        proxied: true,
     });
 
     let stats_tx_send = stats_tx.clone();
@@ -264,6 +279,7 @@ where
         channel_type: ChannelType::Oneshot,
         type_name,
         type_size: mem::size_of::<T>(),
+        proxied: true,
     });
 
     let stats_tx_send = stats_tx.clone();
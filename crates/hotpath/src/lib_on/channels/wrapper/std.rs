@@ -2,7 +2,7 @@
 use quanta::Instant;
 use std::mem;
 use std::sync::atomic::Ordering;
-use std::sync::mpsc::{self, Receiver, Sender, SyncSender};
+use std::sync::mpsc::{self, Receiver, Sender, SyncSender, TrySendError};
 #[cfg(not(target_os = "linux"))]
 use std::time::Instant;
 
@@ -39,6 +39,7 @@ where
         channel_type: ChannelType::Bounded(capacity),
         type_name,
         type_size: mem::size_of::<T>(),
+        proxied: true,
     });
 
     let stats_tx_send = stats_tx.clone();
@@ -69,9 +70,25 @@ where
             match to_inner_rx.recv_timeout(std::time::Duration::from_millis(10)) {
                 Ok(msg) => {
                     let log = log_on_send(&msg);
-                    if inner_tx.send(msg).is_err() {
-                        // Inner receiver dropped
-                        break;
+                    // Time the send only when the channel is full, so it reflects backpressure
+                    match inner_tx.try_send(msg) {
+                        Ok(()) => {}
+                        Err(TrySendError::Full(msg)) => {
+                            let blocked_at = Instant::now();
+                            if inner_tx.send(msg).is_err() {
+                                // Inner receiver dropped
+                                break;
+                            }
+                            let _ = stats_tx_send.send(ChannelEvent::SendBlocked {
+                                id,
+                                blocked_at,
+                                timestamp: Instant::now(),
+                            });
+                        }
+                        Err(TrySendError::Disconnected(_)) => {
+                            // Inner receiver dropped
+                            break;
+                        }
                     }
                     let _ = stats_tx_send.send(ChannelEvent::MessageSent {
                         id,
@@ -164,6 +181,7 @@ where
         channel_type: ChannelType::Unbounded,
         type_name,
         type_size: mem::size_of::<T>(),
+        proxied: true,
     });
 
     let stats_tx_send = stats_tx.clone();
@@ -1,15 +1,23 @@
 #[cfg(target_os = "linux")]
 use quanta::Instant;
+use std::any::Any;
+use std::marker::PhantomData;
 use std::mem;
 use std::sync::atomic::Ordering;
+use std::sync::{Arc, Mutex, Weak};
+use std::time::Duration;
 #[cfg(not(target_os = "linux"))]
 use std::time::Instant;
-use tokio::sync::mpsc;
+use tokio::sync::broadcast::{self, error::RecvError};
+use tokio::sync::mpsc::{self, error::TrySendError};
 use tokio::sync::mpsc::{Receiver, Sender, UnboundedReceiver, UnboundedSender};
 use tokio::sync::oneshot;
+use tokio::sync::watch;
 
 use crate::channels::RT;
-use crate::channels::{init_channels_state, ChannelEvent, ChannelType, CHANNEL_ID_COUNTER};
+use crate::channels::{
+    init_channels_state, ChannelEvent, ChannelType, CHANNEL_ID_COUNTER, SUBSCRIBER_ID_COUNTER,
+};
 
 /// Internal implementation for wrapping bounded Tokio channels with optional logging.
 fn wrap_channel_impl<T, F>(
@@ -42,6 +50,7 @@ where
         channel_type: ChannelType::Bounded(capacity),
         type_name,
         type_size: mem::size_of::<T>(),
+        proxied: true,
     });
 
     let stats_tx_send = stats_tx.clone();
@@ -58,9 +67,25 @@ where
                     match msg {
                         Some(msg) => {
                             let log = log_on_send(&msg);
-                            if inner_tx.send(msg).await.is_err() {
-                                to_inner_rx.close();
-                                break;
+                            // Time the send only when the channel is full, so it reflects backpressure
+                            match inner_tx.try_send(msg) {
+                                Ok(()) => {}
+                                Err(TrySendError::Full(msg)) => {
+                                    let blocked_at = Instant::now();
+                                    if inner_tx.send(msg).await.is_err() {
+                                        to_inner_rx.close();
+                                        break;
+                                    }
+                                    let _ = stats_tx_send.send(ChannelEvent::SendBlocked {
+                                        id,
+                                        blocked_at,
+                                        timestamp: Instant::now(),
+                                    });
+                                }
+                                Err(TrySendError::Closed(_)) => {
+                                    to_inner_rx.close();
+                                    break;
+                                }
                             }
                             let _ = stats_tx_send.send(ChannelEvent::MessageSent {
                                 id,
@@ -163,6 +188,7 @@ where
         channel_type: ChannelType::Unbounded,
         type_name,
         type_size: mem::size_of::<T>(),
+        proxied: true,
     });
 
     let stats_tx_send = stats_tx.clone();
@@ -284,6 +310,7 @@ where
         channel_type: ChannelType::Oneshot,
         type_name,
         type_size: mem::size_of::<T>(),
+        proxied: true,
     });
 
     let stats_tx_send = stats_tx.clone();
@@ -380,6 +407,374 @@ pub(crate) fn wrap_oneshot_log<T: Send + std::fmt::Debug + 'static>(
     wrap_oneshot_impl(inner, source, label, |msg| Some(format!("{:?}", msg)))
 }
 
+/// How often a broadcast receiver queue is sampled while it holds unread messages.
+const QUEUE_SAMPLE_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Instrumented broadcast and watch channels, used to attach receivers created later with
+/// `subscribe()` to the channel they belong to.
+static PUBSUB_CHANNELS: Mutex<Vec<Box<dyn PubSubChannel>>> = Mutex::new(Vec::new());
+
+trait PubSubChannel: Send {
+    fn is_closed(&self) -> bool;
+    fn as_any(&self) -> &dyn Any;
+}
+
+struct BroadcastChannel<T> {
+    id: u64,
+    capacity: usize,
+    sender: broadcast::WeakSender<T>,
+}
+
+impl<T: Send + 'static> PubSubChannel for BroadcastChannel<T> {
+    fn is_closed(&self) -> bool {
+        self.sender.strong_count() == 0
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+/// Watch receivers don't expose a weak handle, so they are matched by the address of the
+/// shared value. It can't move or be reused while `alive` is held by a forwarder, because
+/// each forwarder holds a receiver of the channel too.
+struct WatchChannel<T> {
+    id: u64,
+    value: usize,
+    alive: Weak<()>,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T: 'static> PubSubChannel for WatchChannel<T> {
+    fn is_closed(&self) -> bool {
+        self.alive.strong_count() == 0
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+fn register_pubsub_channel(channel: Box<dyn PubSubChannel>) {
+    let mut channels = PUBSUB_CHANNELS.lock().unwrap();
+    channels.retain(|channel| !channel.is_closed());
+    channels.push(channel);
+}
+
+/// Find the instrumented broadcast channel of a receiver. Returns (id, capacity).
+fn find_broadcast_channel<T: Send + 'static>(rx: &broadcast::Receiver<T>) -> Option<(u64, usize)> {
+    let mut channels = PUBSUB_CHANNELS.lock().unwrap();
+    channels.retain(|channel| !channel.is_closed());
+    channels.iter().find_map(|channel| {
+        let channel = channel.as_any().downcast_ref::<BroadcastChannel<T>>()?;
+        let sender = channel.sender.upgrade()?;
+        sender
+            .subscribe()
+            .same_channel(rx)
+            .then_some((channel.id, channel.capacity))
+    })
+}
+
+fn watch_value_addr<T>(rx: &watch::Receiver<T>) -> usize {
+    &*rx.borrow() as *const T as usize
+}
+
+/// Find the instrumented watch channel of a receiver. Returns (id, alive token).
+fn find_watch_channel<T: 'static>(rx: &watch::Receiver<T>) -> Option<(u64, Arc<()>)> {
+    let value = watch_value_addr(rx);
+    let mut channels = PUBSUB_CHANNELS.lock().unwrap();
+    channels.retain(|channel| !channel.is_closed());
+    channels.iter().find_map(|channel| {
+        let channel = channel.as_any().downcast_ref::<WatchChannel<T>>()?;
+        if channel.value != value {
+            return None;
+        }
+        Some((channel.id, channel.alive.upgrade()?))
+    })
+}
+
+fn create_pubsub_channel<T>(
+    source: &'static str,
+    label: Option<String>,
+    channel_type: ChannelType,
+) -> u64 {
+    let (stats_tx, _) = init_channels_state();
+
+    let id = CHANNEL_ID_COUNTER.fetch_add(1, Ordering::Relaxed);
+
+    let _ = stats_tx.send(ChannelEvent::Created {
+        id,
+        source,
+        display_label: label,
+        channel_type,
+        type_name: std::any::type_name::<T>(),
+        type_size: mem::size_of::<T>(),
+        proxied: true,
+    });
+
+    id
+}
+
+/// Forward a broadcast receiver into a proxy channel of the same capacity, so the returned
+/// receiver lags and sees `RecvError::Lagged` exactly where the inner one would.
+fn spawn_broadcast_subscriber<T, F>(
+    mut inner_rx: broadcast::Receiver<T>,
+    id: u64,
+    capacity: usize,
+    label: Option<String>,
+    mut log_on_send: F,
+) -> broadcast::Receiver<T>
+where
+    T: Clone + Send + 'static,
+    F: FnMut(&T) -> Option<String> + Send + 'static,
+{
+    let (proxy_tx, outer_rx) = broadcast::channel::<T>(capacity);
+    // Tokio rounds the broadcast buffer up to a power of two
+    let queue_limit = capacity.next_power_of_two();
+
+    let (stats_tx, _) = init_channels_state();
+    let stats_tx = stats_tx.clone();
+
+    let sub_id = SUBSCRIBER_ID_COUNTER.fetch_add(1, Ordering::Relaxed);
+    let _ = stats_tx.send(ChannelEvent::SubscriberCreated { id, sub_id, label });
+
+    RT.spawn(async move {
+        let mut queued = 0;
+        let mut sample = tokio::time::interval(QUEUE_SAMPLE_INTERVAL);
+        loop {
+            tokio::select! {
+                // Stop forwarding as soon as the outer receivers are gone
+                biased;
+                _ = proxy_tx.closed() => break, // Outer receivers dropped
+                msg = inner_rx.recv() => {
+                    match msg {
+                        Ok(msg) => {
+                            let log = log_on_send(&msg);
+                            // A full queue drops its oldest message the receiver didn't see yet
+                            let overwritten = u64::from(proxy_tx.len() >= queue_limit);
+                            if proxy_tx.send(msg).is_err() {
+                                break; // Outer receivers dropped
+                            }
+                            let timestamp = Instant::now();
+                            let _ = stats_tx.send(ChannelEvent::SubscriberReceived {
+                                id,
+                                sub_id,
+                                log,
+                                timestamp,
+                            });
+                            if overwritten > 0 {
+                                let _ = stats_tx.send(ChannelEvent::SubscriberLagged {
+                                    id,
+                                    sub_id,
+                                    skipped: 0,
+                                    overwritten,
+                                    timestamp,
+                                });
+                            }
+                        }
+                        Err(RecvError::Lagged(skipped)) => {
+                            let _ = stats_tx.send(ChannelEvent::SubscriberLagged {
+                                id,
+                                sub_id,
+                                skipped,
+                                overwritten: 0,
+                                timestamp: Instant::now(),
+                            });
+                        }
+                        Err(RecvError::Closed) => {
+                            // All senders dropped, the outer receivers can still drain their queue
+                            let _ = stats_tx.send(ChannelEvent::Closed { id });
+                            break;
+                        }
+                    }
+                }
+                _ = sample.tick(), if queued > 0 => {}
+            }
+
+            let len = proxy_tx.len() as u64;
+            if len != queued {
+                queued = len;
+                let _ = stats_tx.send(ChannelEvent::SubscriberQueued {
+                    id,
+                    sub_id,
+                    queued,
+                    timestamp: Instant::now(),
+                });
+            }
+        }
+        // Receivers might have drained their queue since the last sample
+        if proxy_tx.len() as u64 != queued {
+            let _ = stats_tx.send(ChannelEvent::SubscriberQueued {
+                id,
+                sub_id,
+                queued: proxy_tx.len() as u64,
+                timestamp: Instant::now(),
+            });
+        }
+        let _ = stats_tx.send(ChannelEvent::SubscriberClosed {
+            id,
+            sub_id,
+            timestamp: Instant::now(),
+        });
+    });
+
+    outer_rx
+}
+
+/// Forward watch updates into a proxy channel. Updates arriving faster than the forwarder
+/// observes them are coalesced, just like for any other watch receiver.
+fn spawn_watch_subscriber<T, F>(
+    mut inner_rx: watch::Receiver<T>,
+    id: u64,
+    alive: Arc<()>,
+    label: Option<String>,
+    mut log_on_send: F,
+) -> watch::Receiver<T>
+where
+    T: Clone + Send + Sync + 'static,
+    F: FnMut(&T) -> Option<String> + Send + 'static,
+{
+    let (proxy_tx, outer_rx) = watch::channel(inner_rx.borrow().clone());
+
+    let (stats_tx, _) = init_channels_state();
+    let stats_tx = stats_tx.clone();
+
+    let sub_id = SUBSCRIBER_ID_COUNTER.fetch_add(1, Ordering::Relaxed);
+    let _ = stats_tx.send(ChannelEvent::SubscriberCreated { id, sub_id, label });
+
+    RT.spawn(async move {
+        loop {
+            tokio::select! {
+                biased;
+                _ = proxy_tx.closed() => break, // Outer receivers dropped
+                changed = inner_rx.changed() => {
+                    if changed.is_err() {
+                        // Sender dropped
+                        let _ = stats_tx.send(ChannelEvent::Closed { id });
+                        break;
+                    }
+                    let value = inner_rx.borrow_and_update().clone();
+                    let log = log_on_send(&value);
+                    proxy_tx.send_replace(value);
+                    let _ = stats_tx.send(ChannelEvent::SubscriberReceived {
+                        id,
+                        sub_id,
+                        log,
+                        timestamp: Instant::now(),
+                    });
+                }
+            }
+        }
+        let _ = stats_tx.send(ChannelEvent::SubscriberClosed {
+            id,
+            sub_id,
+            timestamp: Instant::now(),
+        });
+        // Release the registry entry before the receiver keeping the watched value in place
+        drop(alive);
+        drop(inner_rx);
+    });
+
+    outer_rx
+}
+
+/// Internal implementation for wrapping Tokio broadcast channels with optional logging.
+fn wrap_broadcast_impl<T, F>(
+    inner: (broadcast::Sender<T>, broadcast::Receiver<T>),
+    source: &'static str,
+    label: Option<String>,
+    capacity: usize,
+    log_on_send: F,
+) -> (broadcast::Sender<T>, broadcast::Receiver<T>)
+where
+    T: Clone + Send + 'static,
+    F: FnMut(&T) -> Option<String> + Send + 'static,
+{
+    let (inner_tx, inner_rx) = inner;
+
+    let id = create_pubsub_channel::<T>(source, label, ChannelType::Broadcast(capacity));
+    register_pubsub_channel(Box::new(BroadcastChannel {
+        id,
+        capacity,
+        sender: inner_tx.downgrade(),
+    }));
+
+    let outer_rx = spawn_broadcast_subscriber(inner_rx, id, capacity, None, log_on_send);
+
+    (inner_tx, outer_rx)
+}
+
+/// Internal implementation for wrapping a receiver returned by `broadcast::Sender::subscribe`.
+/// Receivers of a channel that wasn't instrumented are tracked as a channel on their own.
+fn wrap_broadcast_subscriber_impl<T, F>(
+    inner_rx: broadcast::Receiver<T>,
+    source: &'static str,
+    label: Option<String>,
+    capacity: Option<usize>,
+    log_on_send: F,
+) -> broadcast::Receiver<T>
+where
+    T: Clone + Send + 'static,
+    F: FnMut(&T) -> Option<String> + Send + 'static,
+{
+    if let Some((id, capacity)) = find_broadcast_channel(&inner_rx) {
+        return spawn_broadcast_subscriber(inner_rx, id, capacity, label, log_on_send);
+    }
+
+    let capacity = require_broadcast_capacity(capacity);
+    let id = create_pubsub_channel::<T>(source, label, ChannelType::Broadcast(capacity));
+    spawn_broadcast_subscriber(inner_rx, id, capacity, None, log_on_send)
+}
+
+/// Internal implementation for wrapping Tokio watch channels with optional logging.
+fn wrap_watch_impl<T, F>(
+    inner: (watch::Sender<T>, watch::Receiver<T>),
+    source: &'static str,
+    label: Option<String>,
+    log_on_send: F,
+) -> (watch::Sender<T>, watch::Receiver<T>)
+where
+    T: Clone + Send + Sync + 'static,
+    F: FnMut(&T) -> Option<String> + Send + 'static,
+{
+    let (inner_tx, inner_rx) = inner;
+    let outer_rx = wrap_watch_subscriber_impl(inner_rx, source, label, log_on_send);
+    (inner_tx, outer_rx)
+}
+
+/// Internal implementation for wrapping a receiver returned by `watch::Sender::subscribe`.
+fn wrap_watch_subscriber_impl<T, F>(
+    inner_rx: watch::Receiver<T>,
+    source: &'static str,
+    label: Option<String>,
+    log_on_send: F,
+) -> watch::Receiver<T>
+where
+    T: Clone + Send + Sync + 'static,
+    F: FnMut(&T) -> Option<String> + Send + 'static,
+{
+    if let Some((id, alive)) = find_watch_channel(&inner_rx) {
+        return spawn_watch_subscriber(inner_rx, id, alive, label, log_on_send);
+    }
+
+    let id = create_pubsub_channel::<T>(source, label, ChannelType::Watch);
+    let alive = Arc::new(());
+    register_pubsub_channel(Box::new(WatchChannel::<T> {
+        id,
+        value: watch_value_addr(&inner_rx),
+        alive: Arc::downgrade(&alive),
+        _marker: PhantomData,
+    }));
+    spawn_watch_subscriber(inner_rx, id, alive, None, log_on_send)
+}
+
+fn require_broadcast_capacity(capacity: Option<usize>) -> usize {
+    let Some(capacity) = capacity else {
+        panic!("Capacity is required for broadcast channels, because tokio doesn't expose it in a public API");
+    };
+    capacity
+}
+
 use crate::channels::InstrumentChannel;
 
 impl<T: Send + 'static> InstrumentChannel for (Sender<T>, Receiver<T>) {
@@ -418,6 +813,59 @@ impl<T: Send + 'static> InstrumentChannel for (oneshot::Sender<T>, oneshot::Rece
     }
 }
 
+impl<T: Clone + Send + 'static> InstrumentChannel
+    for (broadcast::Sender<T>, broadcast::Receiver<T>)
+{
+    type Output = (broadcast::Sender<T>, broadcast::Receiver<T>);
+    fn instrument(
+        self,
+        source: &'static str,
+        label: Option<String>,
+        capacity: Option<usize>,
+    ) -> Self::Output {
+        let capacity = require_broadcast_capacity(capacity);
+        wrap_broadcast_impl(self, source, label, capacity, |_| None)
+    }
+}
+
+impl<T: Clone + Send + 'static> InstrumentChannel for broadcast::Receiver<T> {
+    type Output = broadcast::Receiver<T>;
+    fn instrument(
+        self,
+        source: &'static str,
+        label: Option<String>,
+        capacity: Option<usize>,
+    ) -> Self::Output {
+        wrap_broadcast_subscriber_impl(self, source, label, capacity, |_| None)
+    }
+}
+
+impl<T: Clone + Send + Sync + 'static> InstrumentChannel
+    for (watch::Sender<T>, watch::Receiver<T>)
+{
+    type Output = (watch::Sender<T>, watch::Receiver<T>);
+    fn instrument(
+        self,
+        source: &'static str,
+        label: Option<String>,
+        _capacity: Option<usize>,
+    ) -> Self::Output {
+        wrap_watch_impl(self, source, label, |_| None)
+    }
+}
+
+impl<T: Clone + Send + Sync + 'static> InstrumentChannel for watch::Receiver<T> {
+    type Output = watch::Receiver<T>;
+    fn instrument(
+        self,
+        source: &'static str,
+        label: Option<String>,
+        _capacity: Option<usize>,
+    ) -> Self::Output {
+        wrap_watch_subscriber_impl(self, source, label, |_| None)
+    }
+}
+
 use crate::channels::InstrumentChannelLog;
 
 impl<T: Send + std::fmt::Debug + 'static> InstrumentChannelLog for (Sender<T>, Receiver<T>) {
@@ -459,3 +907,62 @@ impl<T: Send + std::fmt::Debug + 'static> InstrumentChannelLog
         wrap_oneshot_log(self, source, label)
     }
 }
+
+impl<T: Clone + Send + std::fmt::Debug + 'static> InstrumentChannelLog
+    for (broadcast::Sender<T>, broadcast::Receiver<T>)
+{
+    type Output = (broadcast::Sender<T>, broadcast::Receiver<T>);
+    fn instrument_log(
+        self,
+        source: &'static str,
+        label: Option<String>,
+        capacity: Option<usize>,
+    ) -> Self::Output {
+        let capacity = require_broadcast_capacity(capacity);
+        wrap_broadcast_impl(self, source, label, capacity, |msg| {
+            Some(format!("{:?}", msg))
+        })
+    }
+}
+
+impl<T: Clone + Send + std::fmt::Debug + 'static> InstrumentChannelLog for broadcast::Receiver<T> {
+    type Output = broadcast::Receiver<T>;
+    fn instrument_log(
+        self,
+        source: &'static str,
+        label: Option<String>,
+        capacity: Option<usize>,
+    ) -> Self::Output {
+        wrap_broadcast_subscriber_impl(self, source, label, capacity, |msg| {
+            Some(format!("{:?}", msg))
+        })
+    }
+}
+
+impl<T: Clone + Send + Sync + std::fmt::Debug + 'static> InstrumentChannelLog
+    for (watch::Sender<T>, watch::Receiver<T>)
+{
+    type Output = (watch::Sender<T>, watch::Receiver<T>);
+    fn instrument_log(
+        self,
+        source: &'static str,
+        label: Option<String>,
+        _capacity: Option<usize>,
+    ) -> Self::Output {
+        wrap_watch_impl(self, source, label, |msg| Some(format!("{:?}", msg)))
+    }
+}
+
+impl<T: Clone + Send + Sync + std::fmt::Debug + 'static> InstrumentChannelLog
+    for watch::Receiver<T>
+{
+    type Output = watch::Receiver<T>;
+    fn instrument_log(
+        self,
+        source: &'static str,
+        label: Option<String>,
+        _capacity: Option<usize>,
+    ) -> Self::Output {
+        wrap_watch_subscriber_impl(self, source, label, |msg| Some(format!("{:?}", msg)))
+    }
+}
@@ -1,17 +1,21 @@
 //! Function profiling module - measures execution time and memory allocations per function.
 
-use std::{collections::HashMap, sync::OnceLock, sync::RwLock, time::Duration};
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::{collections::HashMap, sync::OnceLock, sync::RwLock, time::Duration, time::Instant};
 
 use arc_swap::ArcSwapOption;
 use crossbeam_channel::{bounded, Sender};
+use pin_project_lite::pin_project;
 
-use crate::{http_server::RECV_TIMEOUT_MS, FunctionLogsJson, FunctionsJson};
+use crate::json::FunctionsRetainedJson;
+use crate::{http_server::RECV_TIMEOUT_MS, FunctionLogsJson, FunctionsJson, FunctionsTreeJson};
 
 cfg_if::cfg_if! {
     if #[cfg(feature = "hotpath-alloc")] {
         pub mod alloc;
         use alloc::state::FunctionsState;
-        use tokio::runtime::{Handle, RuntimeFlavor};
         pub use alloc::guard::{MeasurementGuard, MeasurementGuardWithLog};
         pub use alloc::state::FunctionStats;
     } else {
@@ -26,56 +30,13 @@ pub(crate) use crate::output::truncate_result;
 
 impl MeasurementGuard {
     pub fn build(measurement_name: &'static str, wrapper: bool, _is_async: bool) -> Self {
-        #[allow(clippy::needless_bool)]
-        let unsupported_async = if wrapper {
-            // Top wrapper functions are not inside a runtime
-            false
-        } else {
-            cfg_if::cfg_if! {
-                if #[cfg(feature = "hotpath-alloc")] {
-                    // For allocation profiling: mark async as unsupported unless
-                    // running on Tokio CurrentThread. Non-Tokio runtimes are unsupported.
-                    if _is_async {
-                        match Handle::try_current() {
-                            Ok(h) => h.runtime_flavor() != RuntimeFlavor::CurrentThread,
-                            Err(_) => true,
-                        }
-                    } else {
-                        false
-                    }
-                } else {
-                    false
-                }
-            }
-        };
-
-        MeasurementGuard::new(measurement_name, wrapper, unsupported_async)
+        MeasurementGuard::new(measurement_name, wrapper, false)
     }
 }
 
 impl MeasurementGuardWithLog {
     pub fn build(measurement_name: &'static str, wrapper: bool, _is_async: bool) -> Self {
-        #[allow(clippy::needless_bool)]
-        let unsupported_async = if wrapper {
-            false
-        } else {
-            cfg_if::cfg_if! {
-                if #[cfg(feature = "hotpath-alloc")] {
-                    if _is_async {
-                        match Handle::try_current() {
-                            Ok(h) => h.runtime_flavor() != RuntimeFlavor::CurrentThread,
-                            Err(_) => true,
-                        }
-                    } else {
-                        false
-                    }
-                } else {
-                    false
-                }
-            }
-        };
-
-        MeasurementGuardWithLog::new(measurement_name, wrapper, unsupported_async)
+        MeasurementGuardWithLog::new(measurement_name, wrapper, false)
     }
 }
 
@@ -96,28 +57,159 @@ pub fn measure_with_log<T: std::fmt::Debug, F: FnOnce() -> T>(
 
 /// Measure an async function and log its return value.
 #[doc(hidden)]
-pub async fn measure_with_log_async<T: std::fmt::Debug, F, Fut>(name: &'static str, f: F) -> T
+pub fn measure_with_log_async<T: std::fmt::Debug, F, Fut>(
+    name: &'static str,
+    f: F,
+) -> MeasuredFuture<Fut>
 where
     F: FnOnce() -> Fut,
-    Fut: std::future::Future<Output = T>,
+    Fut: Future<Output = T>,
 {
-    let guard = MeasurementGuardWithLog::build(name, false, true);
-    let result = f().await;
-    guard.finish_with_result(&result);
-    result
+    MeasuredFuture {
+        inner: f(),
+        name,
+        sampled: true,
+        tag: None,
+        guard: None,
+        skipped: None,
+        log_result: Some(|guard: MeasurementGuardWithLog, result: &T| {
+            guard.finish_with_result(result)
+        }),
+    }
+}
+
+/// Measure an async function.
+#[doc(hidden)]
+pub fn measure_async<F: Future>(name: &'static str, inner: F) -> MeasuredFuture<F> {
+    MeasuredFuture {
+        inner,
+        name,
+        sampled: true,
+        tag: None,
+        guard: None,
+        skipped: None,
+        log_result: None,
+    }
+}
+
+pin_project! {
+    /// Future wrapper used by `#[hotpath::measure]` on async functions.
+    ///
+    /// The measurement starts on the first poll and ends when the inner future completes
+    /// (or is dropped). Between polls the call frame is taken off the thread's call stack,
+    /// so functions measured inside the future are attributed to it as the caller, even
+    /// when the task moves between worker threads. With `hotpath-alloc` the allocation
+    /// frame follows the same way.
+    #[doc(hidden)]
+    pub struct MeasuredFuture<F: Future> {
+        #[pin]
+        inner: F,
+        name: &'static str,
+        // False if the call was skipped by its sampler
+        sampled: bool,
+        tag: Option<&'static str>,
+        guard: Option<MeasurementGuardWithLog>,
+        skipped: Option<sampling::SkippedCall>,
+        log_result: Option<fn(MeasurementGuardWithLog, &F::Output)>,
+    }
+}
+
+impl<F: Future> Future for MeasuredFuture<F> {
+    type Output = F::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+        if !*this.sampled {
+            match this.skipped {
+                Some(skipped) => skipped.resume(),
+                None => *this.skipped = Some(sampling::SkippedCall::enter(this.name)),
+            }
+            let result = this.inner.poll(cx);
+            match &result {
+                Poll::Ready(_) => *this.skipped = None,
+                Poll::Pending => {
+                    if let Some(skipped) = this.skipped.as_mut() {
+                        skipped.suspend();
+                    }
+                }
+            }
+            return result;
+        }
+
+        match this.guard {
+            Some(guard) => guard.resume(),
+            None => {
+                // #[hotpath::main] creates the guard before entering the runtime
+                #[cfg(feature = "tokio")]
+                crate::runtime::init_runtime_monitoring();
+                let guard = MeasurementGuardWithLog::build(this.name, false, true);
+                *this.guard = Some(match this.tag {
+                    Some(tag) => guard.with_tag(tag),
+                    None => guard,
+                });
+            }
+        }
+
+        let result = this.inner.poll(cx);
+
+        match &result {
+            Poll::Ready(output) => {
+                if let Some(guard) = this.guard.take() {
+                    match this.log_result {
+                        Some(log_result) => log_result(guard, output),
+                        None => drop(guard),
+                    }
+                }
+            }
+            Poll::Pending => {
+                if let Some(guard) = this.guard.as_mut() {
+                    guard.suspend();
+                }
+            }
+        }
+
+        result
+    }
 }
 
 pub(crate) static FUNCTIONS_STATE: OnceLock<ArcSwapOption<RwLock<FunctionsState>>> =
     OnceLock::new();
 
+pub(crate) mod callgraph;
 pub mod guard;
+pub(crate) mod intern;
+pub(crate) mod labels;
+pub use labels::intern_label;
+pub(crate) mod sampling;
+pub use sampling::{SampleGuard, Sampler, SkippedCall};
+pub(crate) mod snapshot;
+pub mod span;
+pub(crate) mod tags;
+pub(crate) mod window;
 
 /// Query request sent from TUI HTTP server to profiler worker thread
 pub(crate) enum FunctionsQuery {
-    /// Request timing metrics snapshot
-    Timing(Sender<FunctionsJson>),
-    /// Request full metrics snapshot (allocation metrics) - returns None if hotpath-alloc not enabled
-    Alloc(Sender<Option<FunctionsJson>>),
+    /// Request timing metrics snapshot, since start or over a rolling window, of all
+    /// calls or only of the calls with a tag
+    Timing {
+        window: Option<Duration>,
+        tag: Option<String>,
+        response_tx: Sender<FunctionsJson>,
+    },
+    /// Request caller→callee tree snapshot
+    TimingTree(Sender<FunctionsTreeJson>),
+    /// Request self time per stack in collapsed stack format
+    TimingFolded(Sender<String>),
+    /// Request all function metric families in OpenMetrics text format
+    OpenMetrics(Sender<String>),
+    /// Request full metrics snapshot (allocation metrics), since start or over a rolling window, of all calls or only of the calls with a tag - returns None if hotpath-alloc not enabled
+    Alloc {
+        window: Option<Duration>,
+        tag: Option<String>,
+        response_tx: Sender<Option<FunctionsJson>>,
+    },
+    /// Request retained and live heap memory per function - returns None if hotpath-alloc-retained not enabled
+    AllocRetained(Sender<Option<FunctionsRetainedJson>>),
     /// Request timing function logs for a specific function (returns None if function not found)
     LogsTiming {
         function_name: String,
@@ -128,11 +220,19 @@ pub(crate) enum FunctionsQuery {
         function_name: String,
         response_tx: Sender<Option<FunctionLogsJson>>,
     },
+    /// Clear all collected stats, measurements completed before `reset_time` are dropped
+    Reset {
+        reset_time: Instant,
+        response_tx: Sender<()>,
+    },
 }
 
 // Get instrumented functions profiling information
-pub(crate) fn get_functions_timing_json() -> FunctionsJson {
-    if let Some(metrics) = try_get_functions_timing_from_worker() {
+pub(crate) fn get_functions_timing_json(
+    window: Option<Duration>,
+    tag: Option<String>,
+) -> FunctionsJson {
+    if let Some(metrics) = try_get_functions_timing_from_worker(window, tag) {
         return metrics;
     }
 
@@ -143,7 +243,102 @@ pub(crate) fn get_functions_timing_json() -> FunctionsJson {
         description: "No timing data available yet".to_string(),
         caller_name: "hotpath".to_string(),
         percentiles: vec![95],
+        headers: FunctionsJson::default_headers(&crate::output::ProfilingMode::Timing, &[95]),
         data: crate::output::FunctionsDataJson(HashMap::new()),
+        call_tree: Vec::new(),
+        tags: Vec::new(),
+    }
+}
+
+// Get caller→callee tree of instrumented functions
+pub(crate) fn get_functions_timing_tree_json() -> FunctionsTreeJson {
+    if let Some(tree) = try_get_functions_timing_tree_from_worker() {
+        return tree;
+    }
+
+    FunctionsTreeJson {
+        total_elapsed: 0,
+        caller_name: "hotpath".to_string(),
+        roots: Vec::new(),
+    }
+}
+
+// Get self time of measured stacks in collapsed stack format
+pub(crate) fn get_functions_timing_folded() -> String {
+    try_get_functions_timing_folded_from_worker().unwrap_or_default()
+}
+
+fn try_get_functions_timing_folded_from_worker() -> Option<String> {
+    let arc_swap = FUNCTIONS_STATE.get()?;
+    let state_option = arc_swap.load();
+    let state_arc = (*state_option).as_ref()?.clone();
+
+    let state_guard = state_arc.read().ok()?;
+
+    let (response_tx, response_rx) = bounded::<String>(1);
+
+    if let Some(query_tx) = &state_guard.query_tx {
+        query_tx
+            .send(FunctionsQuery::TimingFolded(response_tx))
+            .ok()?;
+        drop(state_guard);
+
+        response_rx
+            .recv_timeout(Duration::from_millis(RECV_TIMEOUT_MS))
+            .ok()
+    } else {
+        None
+    }
+}
+
+// Get function metric families in OpenMetrics text format
+pub(crate) fn get_functions_openmetrics() -> String {
+    try_get_functions_openmetrics_from_worker().unwrap_or_default()
+}
+
+fn try_get_functions_openmetrics_from_worker() -> Option<String> {
+    let arc_swap = FUNCTIONS_STATE.get()?;
+    let state_option = arc_swap.load();
+    let state_arc = (*state_option).as_ref()?.clone();
+
+    let state_guard = state_arc.read().ok()?;
+
+    let (response_tx, response_rx) = bounded::<String>(1);
+
+    if let Some(query_tx) = &state_guard.query_tx {
+        query_tx
+            .send(FunctionsQuery::OpenMetrics(response_tx))
+            .ok()?;
+        drop(state_guard);
+
+        response_rx
+            .recv_timeout(Duration::from_millis(RECV_TIMEOUT_MS))
+            .ok()
+    } else {
+        None
+    }
+}
+
+fn try_get_functions_timing_tree_from_worker() -> Option<FunctionsTreeJson> {
+    let arc_swap = FUNCTIONS_STATE.get()?;
+    let state_option = arc_swap.load();
+    let state_arc = (*state_option).as_ref()?.clone();
+
+    let state_guard = state_arc.read().ok()?;
+
+    let (response_tx, response_rx) = bounded::<FunctionsTreeJson>(1);
+
+    if let Some(query_tx) = &state_guard.query_tx {
+        query_tx
+            .send(FunctionsQuery::TimingTree(response_tx))
+            .ok()?;
+        drop(state_guard);
+
+        response_rx
+            .recv_timeout(Duration::from_millis(RECV_TIMEOUT_MS))
+            .ok()
+    } else {
+        None
     }
 }
 
@@ -175,7 +370,10 @@ pub(crate) fn get_function_logs_timing(function_name: &str) -> Option<FunctionLo
     }
 }
 
-fn try_get_functions_timing_from_worker() -> Option<FunctionsJson> {
+fn try_get_functions_timing_from_worker(
+    window: Option<Duration>,
+    tag: Option<String>,
+) -> Option<FunctionsJson> {
     let arc_swap = FUNCTIONS_STATE.get()?;
     let state_option = arc_swap.load();
     let state_arc = (*state_option).as_ref()?.clone();
@@ -185,7 +383,13 @@ fn try_get_functions_timing_from_worker() -> Option<FunctionsJson> {
     let (response_tx, response_rx) = bounded::<FunctionsJson>(1);
 
     if let Some(query_tx) = &state_guard.query_tx {
-        query_tx.send(FunctionsQuery::Timing(response_tx)).ok()?;
+        query_tx
+            .send(FunctionsQuery::Timing {
+                window,
+                tag,
+                response_tx,
+            })
+            .ok()?;
         drop(state_guard);
 
         response_rx
@@ -198,7 +402,10 @@ fn try_get_functions_timing_from_worker() -> Option<FunctionsJson> {
 
 // Get a JSON representation of all functions and their allocations
 // Will return None unless hotpath-alloc is enabled
-pub(crate) fn get_functions_alloc_json() -> Option<FunctionsJson> {
+pub(crate) fn get_functions_alloc_json(
+    window: Option<Duration>,
+    tag: Option<String>,
+) -> Option<FunctionsJson> {
     let arc_swap = FUNCTIONS_STATE.get()?;
     let state_option = arc_swap.load();
     let state_arc = (*state_option).as_ref()?.clone();
@@ -208,7 +415,13 @@ pub(crate) fn get_functions_alloc_json() -> Option<FunctionsJson> {
     let (response_tx, response_rx) = bounded::<Option<FunctionsJson>>(1);
 
     if let Some(query_tx) = &state_guard.query_tx {
-        query_tx.send(FunctionsQuery::Alloc(response_tx)).ok()?;
+        query_tx
+            .send(FunctionsQuery::Alloc {
+                window,
+                tag,
+                response_tx,
+            })
+            .ok()?;
         drop(state_guard);
 
         // Flatten the Option<Option<FunctionsJson>> to Option<FunctionsJson>
@@ -221,6 +434,32 @@ pub(crate) fn get_functions_alloc_json() -> Option<FunctionsJson> {
     }
 }
 
+// Get retained and live heap memory of measured functions
+// Will return None unless hotpath-alloc-retained is enabled
+pub(crate) fn get_functions_alloc_retained_json() -> Option<FunctionsRetainedJson> {
+    let arc_swap = FUNCTIONS_STATE.get()?;
+    let state_option = arc_swap.load();
+    let state_arc = (*state_option).as_ref()?.clone();
+
+    let state_guard = state_arc.read().ok()?;
+
+    let (response_tx, response_rx) = bounded::<Option<FunctionsRetainedJson>>(1);
+
+    if let Some(query_tx) = &state_guard.query_tx {
+        query_tx
+            .send(FunctionsQuery::AllocRetained(response_tx))
+            .ok()?;
+        drop(state_guard);
+
+        response_rx
+            .recv_timeout(Duration::from_millis(RECV_TIMEOUT_MS))
+            .ok()
+            .flatten()
+    } else {
+        None
+    }
+}
+
 // Get instrumented function calls information
 // Will return None unless hotpath-alloc is enabled
 pub(crate) fn get_function_logs_alloc(function_name: &str) -> Option<FunctionLogsJson> {
@@ -249,3 +488,38 @@ pub(crate) fn get_function_logs_alloc(function_name: &str) -> Option<FunctionLog
         None
     }
 }
+
+// Clear the stats of measured functions, the report starts over from the reset
+pub(crate) fn reset_functions() {
+    let Some(arc_swap) = FUNCTIONS_STATE.get() else {
+        return;
+    };
+    let state_option = arc_swap.load();
+    let Some(state_arc) = (*state_option).as_ref() else {
+        return;
+    };
+
+    let Ok(mut state_guard) = state_arc.write() else {
+        return;
+    };
+
+    let reset_time = Instant::now();
+    state_guard.start_time = reset_time;
+
+    let (response_tx, response_rx) = bounded::<()>(1);
+
+    if let Some(query_tx) = &state_guard.query_tx {
+        if query_tx
+            .send(FunctionsQuery::Reset {
+                reset_time,
+                response_tx,
+            })
+            .is_err()
+        {
+            return;
+        }
+        drop(state_guard);
+
+        let _ = response_rx.recv_timeout(Duration::from_millis(RECV_TIMEOUT_MS));
+    }
+}
@@ -2,5 +2,7 @@ pub mod allocator;
 pub mod core;
 pub mod guard;
 pub mod report;
+#[cfg(feature = "hotpath-alloc-retained")]
+pub(crate) mod retained;
 pub mod shared;
 pub mod state;
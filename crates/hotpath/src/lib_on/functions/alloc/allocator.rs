@@ -7,24 +7,77 @@
 // Modifications:
 // - Adjusted to work with hotpath module system
 // - Split into feature-specific dispatching allocator
+// - Count reallocations separately instead of as alloc + dealloc
+// - Generic over the inner allocator
 
 use std::alloc::{GlobalAlloc, Layout, System};
 
-/// Shared global allocator that dispatches to enabled allocation tracking features
-pub struct CountingAllocator;
+/// Global allocator that records allocations for `hotpath-alloc` and delegates
+/// them to an inner allocator, [`System`] by default.
+///
+/// It is installed automatically with the `hotpath-alloc` feature. With the
+/// `hotpath-alloc-custom` feature it is not, and [`global_allocator!`](crate::global_allocator)
+/// wraps the allocator the program ships with instead.
+pub struct CountingAllocator<A = System> {
+    inner: A,
+}
+
+impl<A> CountingAllocator<A> {
+    pub const fn new(inner: A) -> Self {
+        Self { inner }
+    }
+}
 
-unsafe impl GlobalAlloc for CountingAllocator {
+unsafe impl<A: GlobalAlloc> GlobalAlloc for CountingAllocator<A> {
     unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
-        super::core::track_alloc(layout.size());
+        let _live_slot = super::core::track_alloc(layout.size());
 
-        unsafe { System.alloc(layout) }
+        cfg_if::cfg_if! {
+            if #[cfg(feature = "hotpath-alloc-retained")] {
+                unsafe { super::retained::alloc(&self.inner, layout, _live_slot) }
+            } else {
+                unsafe { self.inner.alloc(layout) }
+            }
+        }
     }
 
     unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
         super::core::track_dealloc(layout.size());
 
-        unsafe {
-            System.dealloc(ptr, layout);
+        cfg_if::cfg_if! {
+            if #[cfg(feature = "hotpath-alloc-retained")] {
+                unsafe { super::retained::dealloc(&self.inner, ptr, layout) }
+            } else {
+                unsafe { self.inner.dealloc(ptr, layout) }
+            }
+        }
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let _live_slot = super::core::track_alloc(layout.size());
+
+        cfg_if::cfg_if! {
+            if #[cfg(feature = "hotpath-alloc-retained")] {
+                unsafe { super::retained::alloc_zeroed(&self.inner, layout, _live_slot) }
+            } else {
+                unsafe { self.inner.alloc_zeroed(layout) }
+            }
+        }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        cfg_if::cfg_if! {
+            if #[cfg(feature = "hotpath-alloc-retained")] {
+                let new_ptr = unsafe { super::retained::realloc(&self.inner, ptr, layout, new_size) };
+            } else {
+                let new_ptr = unsafe { self.inner.realloc(ptr, layout, new_size) };
+            }
+        }
+
+        // A failed realloc leaves the original block untouched
+        if !new_ptr.is_null() {
+            super::core::track_realloc(layout.size(), new_size);
         }
+        new_ptr
     }
 }
@@ -3,9 +3,36 @@ use std::sync::atomic::{AtomicU64, Ordering};
 
 use crate::tid::current_tid;
 
-pub const MAX_DEPTH: usize = 64;
+/// Capacity of the per-thread allocation stack, including the frame of unmeasured code.
+///
+/// The stack is a fixed-size thread-local, so the allocator never has to allocate for
+/// it. Programs with deeper recursion can raise it at build time with the
+/// `HOTPATH_ALLOC_MAX_DEPTH` environment variable. Each frame takes 240 bytes of every
+/// thread's storage, mostly its per-size-class counts, so the stack is 15 KB per thread
+/// at the default depth and 960 KB at the maximum of 4096.
+pub const MAX_DEPTH: usize = match option_env!("HOTPATH_ALLOC_MAX_DEPTH") {
+    Some(depth) => match usize::from_str_radix(depth, 10) {
+        Ok(depth) if depth >= 2 && depth <= 4096 => depth,
+        _ => panic!("HOTPATH_ALLOC_MAX_DEPTH must be a number between 2 and 4096"),
+    },
+    None => 64,
+};
+
+/// Number of power-of-two allocation size classes, the last one also counts larger blocks
+pub const SIZE_CLASSES: usize = 24;
+
+/// Allocation counts per size class, class `i` counts blocks of `2^i..2^(i+1)` bytes
+pub type SizeClassCounts = [u64; SIZE_CLASSES];
 
-/// Maximum number of threads we can track (fixed size to avoid allocations in allocator)
+/// Returns the power-of-two size class of an allocation of `size` bytes.
+#[inline]
+pub fn size_class(size: usize) -> usize {
+    let class = (usize::BITS - size.leading_zeros()).saturating_sub(1) as usize;
+    class.min(SIZE_CLASSES - 1)
+}
+
+/// Maximum number of live threads we can track (fixed size to avoid allocations in allocator).
+/// Slots of exited threads are reused.
 const MAX_THREADS: usize = 256;
 
 /// Per-thread allocation statistics (lock-free)
@@ -40,6 +67,37 @@ static THREAD_ALLOC_STATS: [ThreadAllocStats; MAX_THREADS] = {
 
 static THREAD_TRACKING_ENABLED: AtomicU64 = AtomicU64::new(0);
 
+/// Threads that found no free slot in the per-thread stats table
+static UNTRACKED_THREADS: AtomicU64 = AtomicU64::new(0);
+
+/// Measured calls that found the allocation stack full, their allocations went to their callers
+static OVERFLOWED_FRAMES: AtomicU64 = AtomicU64::new(0);
+
+/// The current thread has not looked for a slot yet
+const SLOT_UNCLAIMED: usize = usize::MAX;
+/// The table was full or the thread is exiting
+const SLOT_NONE: usize = usize::MAX - 1;
+
+thread_local! {
+    static THREAD_SLOT: Cell<usize> = const { Cell::new(SLOT_UNCLAIMED) };
+    static SLOT_RELEASE: SlotRelease = const { SlotRelease };
+}
+
+/// Frees the slot of the current thread when it exits
+struct SlotRelease;
+
+impl Drop for SlotRelease {
+    fn drop(&mut self) {
+        let _ = THREAD_SLOT.try_with(|slot| {
+            if let Some(stats) = THREAD_ALLOC_STATS.get(slot.replace(SLOT_NONE)) {
+                stats.alloc_bytes.store(0, Ordering::Relaxed);
+                stats.dealloc_bytes.store(0, Ordering::Relaxed);
+                stats.tid.store(0, Ordering::Release);
+            }
+        });
+    }
+}
+
 /// Initialize the thread allocation tracking system
 pub fn init_thread_alloc_tracking() {
     THREAD_TRACKING_ENABLED.store(1, Ordering::Release);
@@ -51,48 +109,116 @@ pub fn get_thread_alloc_stats(os_tid: u64) -> Option<(u64, u64)> {
         return None;
     }
 
-    for slot in &THREAD_ALLOC_STATS {
-        let slot_tid = slot.tid.load(Ordering::Acquire);
-        if slot_tid == os_tid {
-            return Some((
+    THREAD_ALLOC_STATS
+        .iter()
+        .find(|slot| slot.tid.load(Ordering::Acquire) == os_tid)
+        .map(|slot| {
+            (
                 slot.alloc_bytes.load(Ordering::Relaxed),
                 slot.dealloc_bytes.load(Ordering::Relaxed),
-            ));
-        }
-        if slot_tid == 0 {
-            break;
-        }
+            )
+        })
+}
+
+/// Number of threads without per-thread allocation stats and of measured calls
+/// whose allocations were charged to their callers.
+pub fn overflow_counts() -> (u64, u64) {
+    (
+        UNTRACKED_THREADS.load(Ordering::Relaxed),
+        OVERFLOWED_FRAMES.load(Ordering::Relaxed),
+    )
+}
+
+/// Note appended to reports when threads or frames were not tracked.
+pub(crate) fn overflow_note() -> Option<String> {
+    let (threads, frames) = overflow_counts();
+    let mut notes = Vec::new();
+    if frames > 0 {
+        notes.push(format!(
+            "{frames} calls nested deeper than {} measured functions were charged to their callers.",
+            MAX_DEPTH - 1
+        ));
     }
-    None
+    if threads > 0 {
+        notes.push(format!(
+            "{threads} threads had no per-thread stats, more than {MAX_THREADS} were alive."
+        ));
+    }
+    (!notes.is_empty()).then(|| notes.join(" "))
 }
 
 #[inline]
-fn get_or_create_slot(tid: u64) -> Option<&'static ThreadAllocStats> {
-    for slot in &THREAD_ALLOC_STATS {
-        let slot_tid = slot.tid.load(Ordering::Acquire);
-
-        if slot_tid == tid {
-            return Some(slot);
-        }
+fn current_thread_slot() -> Option<&'static ThreadAllocStats> {
+    let index = THREAD_SLOT
+        .try_with(|slot| {
+            let index = slot.get();
+            if index != SLOT_UNCLAIMED {
+                return index;
+            }
 
-        if slot_tid == 0 {
-            match slot
-                .tid
-                .compare_exchange(0, tid, Ordering::AcqRel, Ordering::Acquire)
-            {
-                Ok(_) => return Some(slot),
-                Err(current) if current == tid => return Some(slot),
-                Err(_) => continue,
+            let index = claim_slot(current_tid());
+            slot.set(index);
+            if index != SLOT_NONE {
+                // Registering the destructor may allocate, the slot is already set by then
+                let _ = SLOT_RELEASE.try_with(|_| ());
             }
+            index
+        })
+        .unwrap_or(SLOT_NONE);
+
+    THREAD_ALLOC_STATS.get(index)
+}
+
+fn claim_slot(tid: u64) -> usize {
+    for (index, slot) in THREAD_ALLOC_STATS.iter().enumerate() {
+        if slot
+            .tid
+            .compare_exchange(0, tid, Ordering::AcqRel, Ordering::Acquire)
+            .is_ok()
+        {
+            return index;
         }
     }
-    None
+    UNTRACKED_THREADS.fetch_add(1, Ordering::Relaxed);
+    SLOT_NONE
+}
+
+/// Counts a measured call that had no free allocation frame.
+#[inline]
+pub(crate) fn record_overflowed_frame() {
+    OVERFLOWED_FRAMES.fetch_add(1, Ordering::Relaxed);
 }
 
 pub struct AllocationInfo {
     pub bytes_total: Cell<u64>,
     pub count_total: Cell<u64>,
-    pub unsupported_async: Cell<bool>,
+    pub untracked_allocs: Cell<bool>,
+    /// Bytes freed while the frame was active, only tracked with `hotpath-alloc-retained`
+    pub dealloc_bytes: Cell<u64>,
+    /// Live allocations table slot of the measured function, 0 outside of measured functions
+    pub live_slot: Cell<u32>,
+    /// Allocation counts per size class
+    pub size_classes: [Cell<u64>; SIZE_CLASSES],
+    /// Reallocations, not counted as allocations
+    pub realloc_count: Cell<u64>,
+    /// Bytes added to blocks by growing reallocations
+    pub realloc_grown_bytes: Cell<u64>,
+}
+
+impl AllocationInfo {
+    /// Allocation counts per size class as a plain array.
+    #[inline]
+    pub fn size_class_counts(&self) -> SizeClassCounts {
+        std::array::from_fn(|class| self.size_classes[class].get())
+    }
+
+    /// Adds allocation counts per size class to this frame.
+    #[inline]
+    pub fn add_size_classes(&self, counts: &SizeClassCounts) {
+        for (cell, count) in self.size_classes.iter().zip(counts) {
+            cell.set(cell.get() + count);
+        }
+    }
 }
 
 impl std::ops::AddAssign for AllocationInfo {
@@ -101,8 +227,15 @@ impl std::ops::AddAssign for AllocationInfo {
             .set(self.bytes_total.get() + other.bytes_total.get());
         self.count_total
             .set(self.count_total.get() + other.count_total.get());
-        self.unsupported_async
-            .set(self.unsupported_async.get() | other.unsupported_async.get());
+        self.untracked_allocs
+            .set(self.untracked_allocs.get() | other.untracked_allocs.get());
+        self.dealloc_bytes
+            .set(self.dealloc_bytes.get() + other.dealloc_bytes.get());
+        self.add_size_classes(&other.size_class_counts());
+        self.realloc_count
+            .set(self.realloc_count.get() + other.realloc_count.get());
+        self.realloc_grown_bytes
+            .set(self.realloc_grown_bytes.get() + other.realloc_grown_bytes.get());
     }
 }
 
@@ -118,38 +251,86 @@ thread_local! {
         elements: [const { AllocationInfo {
             bytes_total: Cell::new(0),
             count_total: Cell::new(0),
-            unsupported_async: Cell::new(false)
+            untracked_allocs: Cell::new(false),
+            dealloc_bytes: Cell::new(0),
+            live_slot: Cell::new(0),
+            size_classes: [const { Cell::new(0) }; SIZE_CLASSES],
+            realloc_count: Cell::new(0),
+            realloc_grown_bytes: Cell::new(0),
         } }; MAX_DEPTH],
         tracking_enabled: Cell::new(true),
     } };
 }
 
+/// Records an allocation in the current frame and returns the live allocations table
+/// slot of the measured function that made it.
 #[inline]
-pub fn track_alloc(size: usize) {
-    ALLOCATIONS.with(|stack| {
+pub fn track_alloc(size: usize) -> u32 {
+    let live_slot = ALLOCATIONS.with(|stack| {
         if !stack.tracking_enabled.get() {
-            return;
+            return 0;
         }
         let depth = stack.depth.get() as usize;
         let info = &stack.elements[depth];
         info.bytes_total.set(info.bytes_total.get() + size as u64);
         info.count_total.set(info.count_total.get() + 1);
+        let class = &info.size_classes[size_class(size)];
+        class.set(class.get() + 1);
+        info.live_slot.get()
     });
 
     if THREAD_TRACKING_ENABLED.load(Ordering::Relaxed) != 0 {
-        let tid = current_tid();
-        if let Some(slot) = get_or_create_slot(tid) {
+        if let Some(slot) = current_thread_slot() {
             slot.alloc_bytes.fetch_add(size as u64, Ordering::Relaxed);
         }
     }
+
+    live_slot
 }
 
 #[inline]
 pub fn track_dealloc(size: usize) {
+    #[cfg(feature = "hotpath-alloc-retained")]
+    ALLOCATIONS.with(|stack| {
+        if !stack.tracking_enabled.get() {
+            return;
+        }
+        let depth = stack.depth.get() as usize;
+        let info = &stack.elements[depth];
+        info.dealloc_bytes
+            .set(info.dealloc_bytes.get() + size as u64);
+    });
+
     if THREAD_TRACKING_ENABLED.load(Ordering::Relaxed) != 0 {
-        let tid = current_tid();
-        if let Some(slot) = get_or_create_slot(tid) {
+        if let Some(slot) = current_thread_slot() {
             slot.dealloc_bytes.fetch_add(size as u64, Ordering::Relaxed);
         }
     }
 }
+
+/// Records a reallocation of a block from `old_size` to `new_size` bytes in the current frame.
+#[inline]
+pub fn track_realloc(old_size: usize, new_size: usize) {
+    let grown = new_size.saturating_sub(old_size) as u64;
+    let shrunk = old_size.saturating_sub(new_size) as u64;
+
+    ALLOCATIONS.with(|stack| {
+        if !stack.tracking_enabled.get() {
+            return;
+        }
+        let depth = stack.depth.get() as usize;
+        let info = &stack.elements[depth];
+        info.realloc_count.set(info.realloc_count.get() + 1);
+        info.realloc_grown_bytes
+            .set(info.realloc_grown_bytes.get() + grown);
+        #[cfg(feature = "hotpath-alloc-retained")]
+        info.dealloc_bytes.set(info.dealloc_bytes.get() + shrunk);
+    });
+
+    if THREAD_TRACKING_ENABLED.load(Ordering::Relaxed) != 0 {
+        if let Some(slot) = current_thread_slot() {
+            slot.alloc_bytes.fetch_add(grown, Ordering::Relaxed);
+            slot.dealloc_bytes.fetch_add(shrunk, Ordering::Relaxed);
+        }
+    }
+}
@@ -4,76 +4,163 @@ use quanta::Instant;
 #[cfg(not(target_os = "linux"))]
 use std::time::Instant;
 
+use super::super::callgraph::CallFrame;
 use super::super::truncate_result;
 
+/// Allocations made while a frame was on the stack.
+#[derive(Clone, Copy, Default)]
+struct FrameAllocs {
+    bytes: u64,
+    count: u64,
+    /// Bytes freed, only tracked with `hotpath-alloc-retained`
+    freed: u64,
+    size_classes: super::core::SizeClassCounts,
+    realloc_count: u64,
+    realloc_grown_bytes: u64,
+    untracked_allocs: bool,
+}
+
+impl FrameAllocs {
+    /// Bytes allocated and not freed again while the frame was on the stack.
+    #[inline]
+    fn retained(&self) -> u64 {
+        (self.bytes + self.realloc_grown_bytes).saturating_sub(self.freed)
+    }
+}
+
+/// Pushes a zeroed allocation frame for `name` on the current thread's stack.
+///
+/// Returns false if the stack is full, allocations then stay with the top frame.
+#[inline]
+fn push_alloc_frame(_name: &'static str) -> bool {
+    cfg_if::cfg_if! {
+        if #[cfg(feature = "hotpath-alloc-retained")] {
+            let live_slot = super::retained::slot_for(_name);
+        } else {
+            let live_slot = 0;
+        }
+    }
+
+    super::core::ALLOCATIONS.with(|stack| {
+        let depth = stack.depth.get() as usize + 1;
+        if depth >= super::core::MAX_DEPTH {
+            super::core::record_overflowed_frame();
+            return false;
+        }
+        stack.depth.set(depth as u32);
+        stack.elements[depth].bytes_total.set(0);
+        stack.elements[depth].count_total.set(0);
+        stack.elements[depth].untracked_allocs.set(false);
+        stack.elements[depth].dealloc_bytes.set(0);
+        stack.elements[depth].live_slot.set(live_slot);
+        for class in &stack.elements[depth].size_classes {
+            class.set(0);
+        }
+        stack.elements[depth].realloc_count.set(0);
+        stack.elements[depth].realloc_grown_bytes.set(0);
+        true
+    })
+}
+
+/// Pops the top allocation frame of the current thread's stack and, unless
+/// `HOTPATH_ALLOC_SELF` is set, adds its allocations to the parent frame.
+#[inline]
+fn pop_alloc_frame() -> FrameAllocs {
+    super::core::ALLOCATIONS.with(|stack| {
+        let depth = stack.depth.get() as usize;
+        let popped = FrameAllocs {
+            bytes: stack.elements[depth].bytes_total.get(),
+            count: stack.elements[depth].count_total.get(),
+            freed: stack.elements[depth].dealloc_bytes.get(),
+            size_classes: stack.elements[depth].size_class_counts(),
+            realloc_count: stack.elements[depth].realloc_count.get(),
+            realloc_grown_bytes: stack.elements[depth].realloc_grown_bytes.get(),
+            untracked_allocs: stack.elements[depth].untracked_allocs.get(),
+        };
+
+        stack.depth.set(stack.depth.get() - 1);
+
+        if !super::shared::is_alloc_self_enabled() {
+            let parent = stack.depth.get() as usize;
+            stack.elements[parent]
+                .bytes_total
+                .set(stack.elements[parent].bytes_total.get() + popped.bytes);
+            stack.elements[parent]
+                .count_total
+                .set(stack.elements[parent].count_total.get() + popped.count);
+            stack.elements[parent]
+                .dealloc_bytes
+                .set(stack.elements[parent].dealloc_bytes.get() + popped.freed);
+            stack.elements[parent].add_size_classes(&popped.size_classes);
+            stack.elements[parent]
+                .realloc_count
+                .set(stack.elements[parent].realloc_count.get() + popped.realloc_count);
+            stack.elements[parent]
+                .realloc_grown_bytes
+                .set(stack.elements[parent].realloc_grown_bytes.get() + popped.realloc_grown_bytes);
+            stack.elements[parent]
+                .untracked_allocs
+                .set(stack.elements[parent].untracked_allocs.get() | popped.untracked_allocs);
+        }
+
+        popped
+    })
+}
+
 #[must_use = "guard is dropped immediately without measuring anything"]
 pub struct MeasurementGuard {
     name: &'static str,
     wrapper: bool,
-    unsupported_async: bool,
+    untracked_allocs: bool,
+    /// Whether an allocation frame was pushed for the call
+    on_stack: bool,
     tid: u64,
+    tag: Option<&'static str>,
+    frame: CallFrame,
     start: Instant,
 }
 
 impl MeasurementGuard {
     #[inline]
-    pub fn new(name: &'static str, wrapper: bool, unsupported_async: bool) -> Self {
-        if !unsupported_async {
-            super::core::ALLOCATIONS.with(|stack| {
-                let current_depth = stack.depth.get();
-                stack.depth.set(current_depth + 1);
-                assert!((stack.depth.get() as usize) < super::core::MAX_DEPTH);
-                let depth = stack.depth.get() as usize;
-                stack.elements[depth].bytes_total.set(0);
-                stack.elements[depth].count_total.set(0);
-                stack.elements[depth].unsupported_async.set(false);
-            });
-        }
+    pub fn new(name: &'static str, wrapper: bool, untracked_allocs: bool) -> Self {
+        let frame = CallFrame::enter(name);
+        let on_stack = !untracked_allocs && push_alloc_frame(name);
 
         Self {
             name,
             wrapper,
-            unsupported_async,
+            untracked_allocs,
+            on_stack,
             tid: crate::tid::current_tid(),
+            tag: None,
+            frame,
             start: Instant::now(),
         }
     }
+
+    /// Tags the call, its stats are also kept per tag.
+    #[inline]
+    pub fn with_tag(mut self, tag: &str) -> Self {
+        self.tag = Some(super::super::tags::intern(tag));
+        self
+    }
 }
 
 impl Drop for MeasurementGuard {
     #[inline]
     fn drop(&mut self) {
         let duration = self.start.elapsed();
+        let child_ns = self.frame.exit(duration.as_nanos() as u64);
         let cross_thread = crate::tid::current_tid() != self.tid;
 
-        let (bytes_total, count_total, unsupported_async) =
-            if self.unsupported_async || cross_thread {
-                (0, 0, self.unsupported_async)
-            } else {
-                super::core::ALLOCATIONS.with(|stack| {
-                    let depth = stack.depth.get() as usize;
-                    let bytes = stack.elements[depth].bytes_total.get();
-                    let count = stack.elements[depth].count_total.get();
-                    let unsup_async = stack.elements[depth].unsupported_async.get();
-
-                    stack.depth.set(stack.depth.get() - 1);
-
-                    if !super::shared::is_alloc_self_enabled() {
-                        let parent = stack.depth.get() as usize;
-                        stack.elements[parent]
-                            .bytes_total
-                            .set(stack.elements[parent].bytes_total.get() + bytes);
-                        stack.elements[parent]
-                            .count_total
-                            .set(stack.elements[parent].count_total.get() + count);
-                        stack.elements[parent]
-                            .unsupported_async
-                            .set(stack.elements[parent].unsupported_async.get() | unsup_async);
-                    }
-
-                    (bytes, count, unsup_async)
-                })
-            };
+        let allocs = if !self.on_stack || cross_thread {
+            FrameAllocs {
+                untracked_allocs: self.untracked_allocs,
+                ..FrameAllocs::default()
+            }
+        } else {
+            pop_alloc_frame()
+        };
 
         super::core::ALLOCATIONS.with(|stack| {
             stack.tracking_enabled.set(false);
@@ -82,13 +169,20 @@ impl Drop for MeasurementGuard {
         let tid = if cross_thread { None } else { Some(self.tid) };
         super::state::send_alloc_measurement(
             self.name,
-            bytes_total,
-            count_total,
+            allocs.bytes,
+            allocs.count,
+            allocs.retained(),
+            allocs.size_classes,
+            allocs.realloc_count,
+            allocs.realloc_grown_bytes,
             duration,
-            unsupported_async,
+            child_ns,
+            self.frame.call_site(),
+            allocs.untracked_allocs,
             self.wrapper,
             cross_thread,
             tid,
+            self.tag,
         );
 
         super::core::ALLOCATIONS.with(|stack| {
@@ -97,93 +191,140 @@ impl Drop for MeasurementGuard {
     }
 }
 
+/// Guard of measured async functions and functions with `log = true`.
+///
+/// Async functions are driven by [`MeasuredFuture`](super::super::MeasuredFuture), which
+/// suspends the guard between polls. The allocation frame is popped on suspend and pushed
+/// again on resume, on whichever thread polls the future next, so allocations made
+/// by other tasks in between are not attributed to the call.
 #[must_use = "guard is dropped immediately without measuring anything"]
 pub struct MeasurementGuardWithLog {
     name: &'static str,
     wrapper: bool,
-    unsupported_async: bool,
+    untracked_allocs: bool,
     tid: u64,
+    tag: Option<&'static str>,
+    frame: CallFrame,
     start: Instant,
     finished: bool,
+    /// Whether the call's allocation frame is on the stack of the current thread
+    on_stack: bool,
+    /// False if the call ran out of allocation frames
+    tracked: bool,
+    /// Allocations of the polls completed so far
+    polled: FrameAllocs,
 }
 
 impl MeasurementGuardWithLog {
     #[inline]
-    pub fn new(name: &'static str, wrapper: bool, unsupported_async: bool) -> Self {
-        if !unsupported_async {
-            super::core::ALLOCATIONS.with(|stack| {
-                let current_depth = stack.depth.get();
-                stack.depth.set(current_depth + 1);
-                assert!((stack.depth.get() as usize) < super::core::MAX_DEPTH);
-                let depth = stack.depth.get() as usize;
-                stack.elements[depth].bytes_total.set(0);
-                stack.elements[depth].count_total.set(0);
-                stack.elements[depth].unsupported_async.set(false);
-            });
-        }
+    pub fn new(name: &'static str, wrapper: bool, untracked_allocs: bool) -> Self {
+        let frame = CallFrame::enter(name);
+        let on_stack = !untracked_allocs && push_alloc_frame(name);
 
         Self {
             name,
             wrapper,
-            unsupported_async,
+            untracked_allocs,
             tid: crate::tid::current_tid(),
+            tag: None,
+            frame,
             start: Instant::now(),
             finished: false,
+            on_stack,
+            tracked: on_stack,
+            polled: FrameAllocs::default(),
         }
     }
 
+    /// Tags the call, its stats are also kept per tag.
+    #[inline]
+    pub fn with_tag(mut self, tag: &str) -> Self {
+        self.tag = Some(super::super::tags::intern(tag));
+        self
+    }
+
+    #[inline]
+    pub(crate) fn suspend(&mut self) {
+        self.frame.suspend();
+
+        if self.on_stack {
+            self.take_alloc_frame();
+            self.on_stack = false;
+        }
+    }
+
+    #[inline]
+    pub(crate) fn resume(&mut self) {
+        self.frame.resume();
+
+        if self.tracked && !self.on_stack {
+            self.on_stack = push_alloc_frame(self.name);
+            self.tracked = self.on_stack;
+        }
+    }
+
+    #[inline]
+    fn take_alloc_frame(&mut self) {
+        let popped = pop_alloc_frame();
+        self.polled.bytes += popped.bytes;
+        self.polled.count += popped.count;
+        self.polled.freed += popped.freed;
+        for (total, count) in self.polled.size_classes.iter_mut().zip(popped.size_classes) {
+            *total += count;
+        }
+        self.polled.realloc_count += popped.realloc_count;
+        self.polled.realloc_grown_bytes += popped.realloc_grown_bytes;
+        self.polled.untracked_allocs |= popped.untracked_allocs;
+    }
+
     #[inline]
     pub fn finish_with_result<T: std::fmt::Debug>(mut self, result: &T) {
         self.finished = true;
         let result_str = truncate_result(format!("{:?}", result));
+        self.finish(Some(result_str));
+    }
 
+    #[inline]
+    fn finish(&mut self, result_log: Option<String>) {
         let duration = self.start.elapsed();
-        let cross_thread = crate::tid::current_tid() != self.tid;
-
-        let (bytes_total, count_total, unsupported_async) =
-            if self.unsupported_async || cross_thread {
-                (0, 0, self.unsupported_async)
-            } else {
-                super::core::ALLOCATIONS.with(|stack| {
-                    let depth = stack.depth.get() as usize;
-                    let bytes = stack.elements[depth].bytes_total.get();
-                    let count = stack.elements[depth].count_total.get();
-                    let unsup_async = stack.elements[depth].unsupported_async.get();
-
-                    stack.depth.set(stack.depth.get() - 1);
-
-                    if !super::shared::is_alloc_self_enabled() {
-                        let parent = stack.depth.get() as usize;
-                        stack.elements[parent]
-                            .bytes_total
-                            .set(stack.elements[parent].bytes_total.get() + bytes);
-                        stack.elements[parent]
-                            .count_total
-                            .set(stack.elements[parent].count_total.get() + count);
-                        stack.elements[parent]
-                            .unsupported_async
-                            .set(stack.elements[parent].unsupported_async.get() | unsup_async);
-                    }
-
-                    (bytes, count, unsup_async)
-                })
-            };
+        let child_ns = self.frame.exit(duration.as_nanos() as u64);
+
+        let allocs = if !self.tracked {
+            FrameAllocs {
+                untracked_allocs: self.untracked_allocs,
+                ..FrameAllocs::default()
+            }
+        } else {
+            // A future dropped between polls has no frame on the stack
+            if self.on_stack {
+                self.take_alloc_frame();
+            }
+            self.polled
+        };
 
         super::core::ALLOCATIONS.with(|stack| {
             stack.tracking_enabled.set(false);
         });
 
-        let tid = if cross_thread { None } else { Some(self.tid) };
+        // Allocations follow the future across polls, only the thread is lost
+        let tid = (crate::tid::current_tid() == self.tid).then_some(self.tid);
         super::state::send_alloc_measurement_with_log(
             self.name,
-            bytes_total,
-            count_total,
+            allocs.bytes,
+            allocs.count,
+            allocs.retained(),
+            allocs.size_classes,
+            allocs.realloc_count,
+            allocs.realloc_grown_bytes,
             duration,
-            unsupported_async,
+            child_ns,
+            self.frame.call_site(),
+            allocs.untracked_allocs,
             self.wrapper,
-            cross_thread,
+            false,
             tid,
-            Some(result_str),
+            self.tag,
+            result_log,
         );
 
         super::core::ALLOCATIONS.with(|stack| {
@@ -196,58 +337,7 @@ impl Drop for MeasurementGuardWithLog {
     #[inline]
     fn drop(&mut self) {
         if !self.finished {
-            let duration = self.start.elapsed();
-            let cross_thread = crate::tid::current_tid() != self.tid;
-
-            let (bytes_total, count_total, unsupported_async) =
-                if self.unsupported_async || cross_thread {
-                    (0, 0, self.unsupported_async)
-                } else {
-                    super::core::ALLOCATIONS.with(|stack| {
-                        let depth = stack.depth.get() as usize;
-                        let bytes = stack.elements[depth].bytes_total.get();
-                        let count = stack.elements[depth].count_total.get();
-                        let unsup_async = stack.elements[depth].unsupported_async.get();
-
-                        stack.depth.set(stack.depth.get() - 1);
-
-                        if !super::shared::is_alloc_self_enabled() {
-                            let parent = stack.depth.get() as usize;
-                            stack.elements[parent]
-                                .bytes_total
-                                .set(stack.elements[parent].bytes_total.get() + bytes);
-                            stack.elements[parent]
-                                .count_total
-                                .set(stack.elements[parent].count_total.get() + count);
-                            stack.elements[parent]
-                                .unsupported_async
-                                .set(stack.elements[parent].unsupported_async.get() | unsup_async);
-                        }
-
-                        (bytes, count, unsup_async)
-                    })
-                };
-
-            super::core::ALLOCATIONS.with(|stack| {
-                stack.tracking_enabled.set(false);
-            });
-
-            let tid = if cross_thread { None } else { Some(self.tid) };
-            super::state::send_alloc_measurement_with_log(
-                self.name,
-                bytes_total,
-                count_total,
-                duration,
-                unsupported_async,
-                self.wrapper,
-                cross_thread,
-                tid,
-                None,
-            );
-
-            super::core::ALLOCATIONS.with(|stack| {
-                stack.tracking_enabled.set(true);
-            });
+            self.finish(None);
         }
     }
 }
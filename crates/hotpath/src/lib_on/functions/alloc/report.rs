@@ -1,9 +1,10 @@
-use crate::ProfilingMode;
+use crate::{ProfilingMode, SortBy};
 use std::collections::HashMap;
 use std::time::Duration;
 
 use super::state::FunctionStats;
-use crate::output::{MetricType, MetricsProvider};
+use crate::openmetrics::{FunctionSample, MetricsWriter, DURATION_BUCKETS_NS};
+use crate::output::{sort_value, CallTreeNode, MetricType, MetricsProvider};
 
 pub struct StatsData<'a> {
     pub stats: &'a HashMap<&'static str, FunctionStats>,
@@ -11,6 +12,9 @@ pub struct StatsData<'a> {
     pub percentiles: Vec<u8>,
     pub caller_name: &'static str,
     pub limit: usize,
+    pub sort_by: SortBy,
+    /// The stats cover every call since the last reset, not a window, tag or report interval
+    pub exact_calls: bool,
 }
 
 pub struct TimingStatsData<'a> {
@@ -19,6 +23,18 @@ pub struct TimingStatsData<'a> {
     pub percentiles: Vec<u8>,
     pub caller_name: &'static str,
     pub limit: usize,
+    pub sort_by: SortBy,
+    /// The stats cover every call since the last reset, not a window, tag or report interval
+    pub exact_calls: bool,
+}
+
+impl StatsData<'_> {
+    /// Whether the stats cover every call since the last reset, so the call counts of
+    /// sampled functions can be exact instead of estimated.
+    pub(crate) fn with_exact_calls(mut self, exact_calls: bool) -> Self {
+        self.exact_calls = exact_calls;
+        self
+    }
 }
 
 impl<'a> MetricsProvider<'a> for StatsData<'a> {
@@ -35,19 +51,35 @@ impl<'a> MetricsProvider<'a> for StatsData<'a> {
             percentiles,
             caller_name,
             limit,
+            sort_by: SortBy::Total,
+            exact_calls: true,
         }
     }
 
+    fn with_sort_by(mut self, sort_by: SortBy) -> Self {
+        self.sort_by = sort_by;
+        self
+    }
+
     fn profiling_mode(&self) -> ProfilingMode {
         ProfilingMode::Alloc
     }
 
     fn description(&self) -> String {
-        if super::shared::is_alloc_self_enabled() {
-            "Exclusive allocations by each function (excluding nested calls).".to_string()
+        let description = if super::shared::is_alloc_self_enabled() {
+            "Exclusive allocations by each function (excluding nested calls)."
         } else {
-            "Cumulative allocations during each function call (including nested calls).".to_string()
-        }
+            "Cumulative allocations during each function call (including nested calls)."
+        };
+
+        let notes = [
+            super::core::overflow_note(),
+            super::super::sampling::sampling_note(),
+        ];
+        std::iter::once(description.to_string())
+            .chain(notes.into_iter().flatten())
+            .collect::<Vec<_>>()
+            .join(" ")
     }
 
     fn percentiles(&self) -> Vec<u8> {
@@ -55,7 +87,7 @@ impl<'a> MetricsProvider<'a> for StatsData<'a> {
     }
 
     fn has_unsupported_async(&self) -> bool {
-        self.stats.values().any(|s| s.has_unsupported_async)
+        self.stats.values().any(|s| s.has_untracked_allocs)
     }
 
     fn metric_data(&self) -> HashMap<String, Vec<MetricType>> {
@@ -65,9 +97,14 @@ impl<'a> MetricsProvider<'a> for StatsData<'a> {
             .filter(|(_, s)| s.has_data && !(s.wrapper && s.cross_thread))
             .collect();
 
+        let scales = super::super::sampling::scales();
+        let scale = |name: &str| scales.get(name).copied().unwrap_or_default();
+        let total_bytes =
+            |name: &str, stats: &FunctionStats| scale(name).apply(stats.total_bytes());
+
         filtered_stats.sort_by(|a, b| {
-            b.1.total_bytes()
-                .cmp(&a.1.total_bytes())
+            total_bytes(b.0, b.1)
+                .cmp(&total_bytes(a.0, a.1))
                 .then_with(|| a.0.cmp(b.0))
         });
 
@@ -84,7 +121,7 @@ impl<'a> MetricsProvider<'a> for StatsData<'a> {
             self.stats
                 .iter()
                 .filter(|(_, s)| s.has_data)
-                .map(|(_, stats)| stats.total_bytes())
+                .map(|(name, stats)| total_bytes(name, stats))
                 .sum()
         } else {
             let has_cross_thread_wrapper =
@@ -94,7 +131,7 @@ impl<'a> MetricsProvider<'a> for StatsData<'a> {
                 filtered_stats
                     .iter()
                     .filter(|(_, s)| !s.wrapper)
-                    .map(|(_, stats)| stats.total_bytes())
+                    .map(|(name, stats)| total_bytes(name, stats))
                     .sum()
             } else {
                 let wrapper_total_bytes = self
@@ -106,7 +143,7 @@ impl<'a> MetricsProvider<'a> for StatsData<'a> {
                 wrapper_total_bytes.unwrap_or_else(|| {
                     filtered_stats
                         .iter()
-                        .map(|(_, stats)| stats.total_bytes())
+                        .map(|(name, stats)| total_bytes(name, stats))
                         .sum()
                 })
             }
@@ -115,23 +152,27 @@ impl<'a> MetricsProvider<'a> for StatsData<'a> {
         filtered_stats
             .into_iter()
             .map(|(function_name, stats)| {
+                let scale = scale(function_name);
                 let percentage = if grand_total_bytes > 0 {
-                    (stats.total_bytes() as f64 / grand_total_bytes as f64) * 100.0
+                    (scale.apply(stats.total_bytes()) as f64 / grand_total_bytes as f64) * 100.0
                 } else {
                     0.0
                 };
 
-                let mut metrics = if stats.has_unsupported_async || stats.cross_thread {
-                    vec![MetricType::CallsCount(stats.count), MetricType::Unsupported]
+                let mut metrics = if stats.has_untracked_allocs || stats.cross_thread {
+                    vec![
+                        MetricType::CallsCount(scale.calls(stats.count, self.exact_calls)),
+                        MetricType::Unsupported,
+                    ]
                 } else {
                     vec![
-                        MetricType::CallsCount(stats.count),
+                        MetricType::CallsCount(scale.calls(stats.count, self.exact_calls)),
                         MetricType::Alloc(stats.avg_bytes(), stats.avg_count()),
                     ]
                 };
 
                 for &p in &self.percentiles {
-                    if stats.has_unsupported_async || stats.cross_thread {
+                    if stats.has_untracked_allocs || stats.cross_thread {
                         metrics.push(MetricType::Unsupported);
                     } else {
                         let bytes_total = stats.bytes_total_percentile(p as f64);
@@ -140,11 +181,21 @@ impl<'a> MetricsProvider<'a> for StatsData<'a> {
                     }
                 }
 
-                if stats.has_unsupported_async || stats.cross_thread {
-                    metrics.push(MetricType::Unsupported);
-                    metrics.push(MetricType::Unsupported);
+                if stats.has_untracked_allocs || stats.cross_thread {
+                    // Total, Reallocs, Grown, % Total
+                    metrics.extend(std::iter::repeat_n(MetricType::Unsupported, 4));
                 } else {
-                    metrics.push(MetricType::Alloc(stats.total_bytes(), stats.total_count()));
+                    metrics.push(MetricType::Alloc(
+                        scale.apply(stats.total_bytes()),
+                        scale.apply(stats.total_count()),
+                    ));
+                    metrics.push(MetricType::CallsCount(
+                        scale.apply(stats.total_realloc_count),
+                    ));
+                    metrics.push(MetricType::Alloc(
+                        scale.apply(stats.total_realloc_grown_bytes),
+                        scale.apply(stats.total_realloc_count),
+                    ));
                     metrics.push(MetricType::Percentage((percentage * 100.0) as u64));
                 }
 
@@ -153,6 +204,10 @@ impl<'a> MetricsProvider<'a> for StatsData<'a> {
             .collect()
     }
 
+    fn folded_stacks(&self) -> Vec<(String, u64)> {
+        super::super::callgraph::build_folded_stacks(self.stats.values().map(|s| &s.stacks))
+    }
+
     fn total_elapsed(&self) -> u64 {
         self.total_elapsed.as_nanos() as u64
     }
@@ -178,6 +233,15 @@ impl<'a> MetricsProvider<'a> for StatsData<'a> {
     }
 }
 
+impl TimingStatsData<'_> {
+    /// Whether the stats cover every call since the last reset, so the call counts of
+    /// sampled functions can be exact instead of estimated.
+    pub(crate) fn with_exact_calls(mut self, exact_calls: bool) -> Self {
+        self.exact_calls = exact_calls;
+        self
+    }
+}
+
 impl<'a> MetricsProvider<'a> for TimingStatsData<'a> {
     fn new(
         stats: &'a HashMap<&'static str, FunctionStats>,
@@ -192,15 +256,25 @@ impl<'a> MetricsProvider<'a> for TimingStatsData<'a> {
             percentiles,
             caller_name,
             limit,
+            sort_by: SortBy::Total,
+            exact_calls: true,
         }
     }
 
+    fn with_sort_by(mut self, sort_by: SortBy) -> Self {
+        self.sort_by = sort_by;
+        self
+    }
+
     fn profiling_mode(&self) -> ProfilingMode {
         ProfilingMode::Timing
     }
 
     fn description(&self) -> String {
-        "Function execution time metrics.".to_string()
+        match super::super::sampling::sampling_note() {
+            Some(note) => format!("Function execution time metrics. {note}"),
+            None => "Function execution time metrics.".to_string(),
+        }
     }
 
     fn percentiles(&self) -> Vec<u8> {
@@ -214,9 +288,17 @@ impl<'a> MetricsProvider<'a> for TimingStatsData<'a> {
     fn metric_data(&self) -> HashMap<String, Vec<MetricType>> {
         let mut filtered_stats: Vec<_> = self.stats.iter().filter(|(_, s)| s.has_data).collect();
 
+        let sort_value = |stats: &FunctionStats| match self.sort_by {
+            SortBy::Total => stats.total_duration_ns,
+            SortBy::SelfTime => stats.total_self_ns,
+        };
+        let scales = super::super::sampling::scales();
+        let scale = |name: &str| scales.get(name).copied().unwrap_or_default();
+
         filtered_stats.sort_by(|a, b| {
-            b.1.total_duration_ns
-                .cmp(&a.1.total_duration_ns)
+            scale(b.0)
+                .apply(sort_value(b.1))
+                .cmp(&scale(a.0).apply(sort_value(a.1)))
                 .then_with(|| a.0.cmp(b.0))
         });
 
@@ -240,14 +322,16 @@ impl<'a> MetricsProvider<'a> for TimingStatsData<'a> {
         filtered_stats
             .into_iter()
             .map(|(function_name, stats)| {
+                let scale = scale(function_name);
+                let total_duration_ns = scale.apply(stats.total_duration_ns);
                 let percentage = if reference_total > 0 {
-                    (stats.total_duration_ns as f64 / reference_total as f64) * 100.0
+                    (total_duration_ns as f64 / reference_total as f64) * 100.0
                 } else {
                     0.0
                 };
 
                 let mut metrics = vec![
-                    MetricType::CallsCount(stats.count),
+                    MetricType::CallsCount(scale.calls(stats.count, self.exact_calls)),
                     MetricType::DurationNs(stats.avg_duration_ns()),
                 ];
 
@@ -256,7 +340,8 @@ impl<'a> MetricsProvider<'a> for TimingStatsData<'a> {
                     metrics.push(MetricType::DurationNs(duration_ns));
                 }
 
-                metrics.push(MetricType::DurationNs(stats.total_duration_ns));
+                metrics.push(MetricType::DurationNs(total_duration_ns));
+                metrics.push(MetricType::DurationNs(scale.apply(stats.total_self_ns)));
                 metrics.push(MetricType::Percentage((percentage * 100.0) as u64));
 
                 (function_name.to_string(), metrics)
@@ -264,6 +349,18 @@ impl<'a> MetricsProvider<'a> for TimingStatsData<'a> {
             .collect()
     }
 
+    fn sort_key(&self, metrics: &[MetricType]) -> f64 {
+        sort_value(&self.headers(), metrics, self.sort_by)
+    }
+
+    fn call_tree(&self) -> Vec<CallTreeNode> {
+        super::super::callgraph::build_call_tree(self.stats.values().map(|s| &s.stacks))
+    }
+
+    fn folded_stacks(&self) -> Vec<(String, u64)> {
+        super::super::callgraph::build_folded_stacks(self.stats.values().map(|s| &s.stacks))
+    }
+
     fn total_elapsed(&self) -> u64 {
         self.total_elapsed.as_nanos() as u64
     }
@@ -284,3 +381,60 @@ impl<'a> MetricsProvider<'a> for TimingStatsData<'a> {
         (displayed_count, total_count)
     }
 }
+
+pub(crate) fn write_openmetrics(
+    stats: &HashMap<&'static str, FunctionStats>,
+    w: &mut MetricsWriter,
+) {
+    let scales = super::super::sampling::scales();
+    let mut entries: Vec<_> = stats
+        .iter()
+        .filter(|(_, s)| s.has_data)
+        .map(|(name, s)| (name, s, scales.get(name).copied().unwrap_or_default()))
+        .collect();
+    entries.sort_by(|a, b| a.0.cmp(b.0));
+
+    let functions: Vec<FunctionSample<'_>> = entries
+        .iter()
+        .map(|(name, s, scale)| FunctionSample {
+            name,
+            calls: scale.calls(s.count, true),
+            total_ns: scale.apply(s.total_duration_ns),
+            self_ns: scale.apply(s.total_self_ns),
+            duration_buckets: scale.apply_all(s.duration_buckets(&DURATION_BUCKETS_NS)),
+        })
+        .collect();
+    crate::openmetrics::write_functions(w, &functions);
+
+    if entries.is_empty() {
+        return;
+    }
+
+    w.family(
+        "hotpath_function_alloc_bytes",
+        "counter",
+        Some("bytes"),
+        "Bytes allocated by a measured function.",
+    );
+    for (name, s, scale) in &entries {
+        w.sample(
+            "hotpath_function_alloc_bytes_total",
+            &[("function", name)],
+            scale.apply(s.total_bytes()),
+        );
+    }
+
+    w.family(
+        "hotpath_function_allocations",
+        "counter",
+        None,
+        "Allocations made by a measured function.",
+    );
+    for (name, s, scale) in &entries {
+        w.sample(
+            "hotpath_function_allocations_total",
+            &[("function", name)],
+            scale.apply(s.total_count()),
+        );
+    }
+}
@@ -0,0 +1,275 @@
+//! Live heap tracking for `hotpath-alloc-retained`.
+//!
+//! Every allocation is prefixed with a small header holding the live allocations table
+//! slot of the measured function that made it, so its bytes can be subtracted from that
+//! function when the block is freed, no matter where. The table is a fixed size array
+//! so the allocator never allocates itself.
+
+use std::alloc::{GlobalAlloc, Layout};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicI64, AtomicUsize, Ordering};
+use std::time::Duration;
+
+use super::state::FunctionStats;
+use crate::json::{FunctionRetainedJson, FunctionsRetainedJson, LiveAllocationsJson};
+
+/// Maximum number of measured functions tracked in the live allocations table
+const MAX_LIVE_FUNCTIONS: usize = 4096;
+
+/// Bytes reserved in front of every allocation for the slot index
+const HEADER_SIZE: usize = 16;
+
+struct LiveSlot {
+    /// Address of the function name (0 means slot is unused)
+    name_ptr: AtomicUsize,
+    name_len: AtomicUsize,
+    live_bytes: AtomicI64,
+    live_count: AtomicI64,
+}
+
+impl LiveSlot {
+    const fn new() -> Self {
+        Self {
+            name_ptr: AtomicUsize::new(0),
+            name_len: AtomicUsize::new(0),
+            live_bytes: AtomicI64::new(0),
+            live_count: AtomicI64::new(0),
+        }
+    }
+}
+
+#[allow(clippy::declare_interior_mutable_const)]
+static LIVE_SLOTS: [LiveSlot; MAX_LIVE_FUNCTIONS] = {
+    const INIT: LiveSlot = LiveSlot::new();
+    [INIT; MAX_LIVE_FUNCTIONS]
+};
+
+/// Returns the slot of `name` in the live allocations table, starting from 1.
+/// Returns 0 if the table is full.
+pub(crate) fn slot_for(name: &'static str) -> u32 {
+    let ptr = name.as_ptr() as usize;
+    let start = ptr.wrapping_mul(0x9E37_79B9_7F4A_7C15) % MAX_LIVE_FUNCTIONS;
+
+    for offset in 0..MAX_LIVE_FUNCTIONS {
+        let index = (start + offset) % MAX_LIVE_FUNCTIONS;
+        let slot = &LIVE_SLOTS[index];
+        let slot_ptr = slot.name_ptr.load(Ordering::Acquire);
+
+        if slot_ptr == ptr {
+            return index as u32 + 1;
+        }
+
+        if slot_ptr == 0 {
+            match slot
+                .name_ptr
+                .compare_exchange(0, ptr, Ordering::AcqRel, Ordering::Acquire)
+            {
+                Ok(_) => {
+                    // Readers skip slots until the length is stored
+                    slot.name_len.store(name.len(), Ordering::Release);
+                    return index as u32 + 1;
+                }
+                Err(current) if current == ptr => return index as u32 + 1,
+                Err(_) => continue,
+            }
+        }
+    }
+    0
+}
+
+#[inline]
+fn header_offset(layout: &Layout) -> usize {
+    layout.align().max(HEADER_SIZE)
+}
+
+#[inline]
+fn with_header(layout: &Layout) -> Option<Layout> {
+    let size = layout.size().checked_add(header_offset(layout))?;
+    Layout::from_size_align(size, layout.align()).ok()
+}
+
+/// Allocates `layout` with a header holding `live_slot`.
+///
+/// # Safety
+///
+/// Same contract as [`GlobalAlloc::alloc`].
+#[inline]
+pub(crate) unsafe fn alloc<A: GlobalAlloc>(inner: &A, layout: Layout, live_slot: u32) -> *mut u8 {
+    let Some(outer) = with_header(&layout) else {
+        return std::ptr::null_mut();
+    };
+
+    let base = unsafe { inner.alloc(outer) };
+    unsafe { write_header(base, &layout, live_slot) }
+}
+
+/// Allocates zeroed `layout` with a header holding `live_slot`.
+///
+/// # Safety
+///
+/// Same contract as [`GlobalAlloc::alloc_zeroed`].
+#[inline]
+pub(crate) unsafe fn alloc_zeroed<A: GlobalAlloc>(
+    inner: &A,
+    layout: Layout,
+    live_slot: u32,
+) -> *mut u8 {
+    let Some(outer) = with_header(&layout) else {
+        return std::ptr::null_mut();
+    };
+
+    let base = unsafe { inner.alloc_zeroed(outer) };
+    unsafe { write_header(base, &layout, live_slot) }
+}
+
+/// Stores `live_slot` in the header of a new block at `base` and returns the user pointer.
+#[inline]
+unsafe fn write_header(base: *mut u8, layout: &Layout, live_slot: u32) -> *mut u8 {
+    if base.is_null() {
+        return base;
+    }
+
+    let ptr = unsafe { base.add(header_offset(layout)) };
+    unsafe { (ptr.sub(4) as *mut u32).write_unaligned(live_slot) };
+
+    if live_slot > 0 {
+        let slot = &LIVE_SLOTS[live_slot as usize - 1];
+        slot.live_bytes
+            .fetch_add(layout.size() as i64, Ordering::Relaxed);
+        slot.live_count.fetch_add(1, Ordering::Relaxed);
+    }
+
+    ptr
+}
+
+/// Resizes a block allocated by [`alloc`], it stays attributed to its allocating function.
+///
+/// # Safety
+///
+/// Same contract as [`GlobalAlloc::realloc`], `ptr` must come from [`alloc`].
+#[inline]
+pub(crate) unsafe fn realloc<A: GlobalAlloc>(
+    inner: &A,
+    ptr: *mut u8,
+    layout: Layout,
+    new_size: usize,
+) -> *mut u8 {
+    let offset = header_offset(&layout);
+    let Some(new_outer) = Layout::from_size_align(new_size, layout.align())
+        .ok()
+        .and_then(|new_layout| with_header(&new_layout))
+    else {
+        return std::ptr::null_mut();
+    };
+
+    let live_slot = unsafe { (ptr.sub(4) as *const u32).read_unaligned() };
+    // The layout was valid when the block was allocated
+    let outer =
+        unsafe { Layout::from_size_align_unchecked(layout.size() + offset, layout.align()) };
+
+    // The header is moved along with the block
+    let base = unsafe { inner.realloc(ptr.sub(offset), outer, new_outer.size()) };
+    if base.is_null() {
+        return base;
+    }
+
+    if live_slot > 0 {
+        let slot = &LIVE_SLOTS[live_slot as usize - 1];
+        slot.live_bytes
+            .fetch_add(new_size as i64 - layout.size() as i64, Ordering::Relaxed);
+    }
+
+    unsafe { base.add(offset) }
+}
+
+/// Frees a block allocated by [`alloc`] and subtracts it from its allocating function.
+///
+/// # Safety
+///
+/// Same contract as [`GlobalAlloc::dealloc`], `ptr` must come from [`alloc`].
+#[inline]
+pub(crate) unsafe fn dealloc<A: GlobalAlloc>(inner: &A, ptr: *mut u8, layout: Layout) {
+    let live_slot = unsafe { (ptr.sub(4) as *const u32).read_unaligned() };
+
+    if live_slot > 0 {
+        let slot = &LIVE_SLOTS[live_slot as usize - 1];
+        slot.live_bytes
+            .fetch_sub(layout.size() as i64, Ordering::Relaxed);
+        slot.live_count.fetch_sub(1, Ordering::Relaxed);
+    }
+
+    let offset = header_offset(&layout);
+    // The layout was valid when the block was allocated
+    let outer =
+        unsafe { Layout::from_size_align_unchecked(layout.size() + offset, layout.align()) };
+    unsafe { inner.dealloc(ptr.sub(offset), outer) };
+}
+
+/// Live bytes and allocation count of every measured function that still holds memory.
+fn live_allocations() -> Vec<(&'static str, u64, u64)> {
+    LIVE_SLOTS
+        .iter()
+        .filter_map(|slot| {
+            let ptr = slot.name_ptr.load(Ordering::Acquire);
+            if ptr == 0 {
+                return None;
+            }
+            let len = slot.name_len.load(Ordering::Acquire);
+            if len == 0 {
+                return None;
+            }
+            // SAFETY: ptr and len were taken from a &'static str in slot_for
+            let name = unsafe {
+                std::str::from_utf8_unchecked(std::slice::from_raw_parts(ptr as *const u8, len))
+            };
+
+            let live_bytes = slot.live_bytes.load(Ordering::Relaxed).max(0) as u64;
+            let live_count = slot.live_count.load(Ordering::Relaxed).max(0) as u64;
+            (live_bytes > 0).then_some((name, live_bytes, live_count))
+        })
+        .collect()
+}
+
+/// Builds the `/functions_alloc/retained` response from the worker's stats and the live table.
+pub(crate) fn retained_json(
+    stats: &HashMap<&'static str, FunctionStats>,
+    total_elapsed: Duration,
+    caller_name: &str,
+) -> FunctionsRetainedJson {
+    let mut functions: Vec<FunctionRetainedJson> = stats
+        .iter()
+        .filter(|(_, s)| s.has_data && s.count > 0)
+        .map(|(name, s)| FunctionRetainedJson {
+            name: name.to_string(),
+            calls: s.count,
+            avg_retained_bytes: s.total_retained_bytes / s.count,
+            total_retained_bytes: s.total_retained_bytes,
+        })
+        .collect();
+    functions.sort_by(|a, b| {
+        b.total_retained_bytes
+            .cmp(&a.total_retained_bytes)
+            .then_with(|| a.name.cmp(&b.name))
+    });
+
+    let mut live: Vec<LiveAllocationsJson> = live_allocations()
+        .into_iter()
+        .map(|(name, live_bytes, live_count)| LiveAllocationsJson {
+            name: name.to_string(),
+            live_bytes,
+            live_count,
+        })
+        .collect();
+    live.sort_by(|a, b| {
+        b.live_bytes
+            .cmp(&a.live_bytes)
+            .then_with(|| a.name.cmp(&b.name))
+    });
+
+    FunctionsRetainedJson {
+        total_elapsed: total_elapsed.as_nanos() as u64,
+        caller_name: caller_name.to_string(),
+        functions,
+        live,
+    }
+}
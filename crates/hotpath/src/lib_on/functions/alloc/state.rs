@@ -5,6 +5,10 @@ use std::collections::{HashMap, VecDeque};
 use std::sync::Mutex;
 use std::time::{Duration, Instant};
 
+use super::super::callgraph::{merge_stacks, CallSite, StacksMap};
+use super::core::{SizeClassCounts, SIZE_CLASSES};
+use crate::output::SizeClassJson;
+
 const BATCH_SIZE: usize = 64;
 const FLUSH_INTERVAL_MS: u64 = 50;
 
@@ -31,11 +35,18 @@ impl MeasurementBatch {
         name: &'static str,
         bytes_total: u64,
         count_total: u64,
+        retained_bytes: u64,
+        size_classes: SizeClassCounts,
+        realloc_count: u64,
+        realloc_grown_bytes: u64,
         duration: Duration,
-        unsupported_async: bool,
+        child_ns: u64,
+        call_site: CallSite,
+        untracked_allocs: bool,
         wrapper: bool,
         cross_thread: bool,
         tid: Option<u64>,
+        tag: Option<&'static str>,
         result_log: Option<String>,
     ) {
         if self.sender.is_none() {
@@ -44,6 +55,7 @@ impl MeasurementBatch {
                     if let Ok(state_guard) = state.read() {
                         self.sender = state_guard.sender.clone();
                         self.start_time = Some(state_guard.start_time);
+                        crate::trace::note_current_thread();
                     }
                 }
             }
@@ -57,12 +69,19 @@ impl MeasurementBatch {
             name,
             bytes_total,
             count_total,
+            retained_bytes,
+            size_classes,
+            realloc_count,
+            realloc_grown_bytes,
             duration,
+            self_duration_ns: (duration.as_nanos() as u64).saturating_sub(child_ns),
+            call_site,
             measurement_time: Instant::now(),
-            unsupported_async,
+            untracked_allocs,
             wrapper,
             cross_thread,
             tid,
+            tag,
             result_log,
         };
 
@@ -106,19 +125,41 @@ pub(crate) fn flush_batch() {
     });
 }
 
+#[derive(Clone)]
 pub struct Measurement {
     pub name: &'static str,
     pub bytes_total: u64,
     pub count_total: u64,
+    /// Bytes allocated and not freed again before the call returned
+    pub retained_bytes: u64,
+    /// Allocation counts per size class
+    pub size_classes: SizeClassCounts,
+    pub realloc_count: u64,
+    /// Bytes added to blocks by growing reallocations
+    pub realloc_grown_bytes: u64,
     pub duration: Duration,
+    pub self_duration_ns: u64,
+    pub call_site: CallSite,
     pub measurement_time: Instant,
-    pub unsupported_async: bool,
+    /// Spans don't track allocations, their columns are reported as N/A
+    pub untracked_allocs: bool,
     pub wrapper: bool,
     pub cross_thread: bool,
     pub tid: Option<u64>,
+    /// Tag of the call, from `#[hotpath::measure(tag = ..)]`
+    pub tag: Option<&'static str>,
     pub result_log: Option<String>,
 }
 
+impl Measurement {
+    /// When the measured call started.
+    pub(crate) fn started_at(&self) -> Instant {
+        self.measurement_time
+            .checked_sub(self.duration)
+            .unwrap_or(self.measurement_time)
+    }
+}
+
 type LogEntry = (
     Option<u64>,
     Option<u64>,
@@ -135,11 +176,22 @@ pub struct FunctionStats {
     count_total_hist: Option<Histogram<u64>>,
     duration_hist: Option<Histogram<u64>>,
     pub total_duration_ns: u64,
+    pub total_self_ns: u64,
+    /// Sum of bytes still live when the calls returned
+    pub total_retained_bytes: u64,
+    /// Allocation counts per size class of all calls
+    pub size_classes: SizeClassCounts,
+    /// Reallocations of all calls
+    pub total_realloc_count: u64,
+    /// Bytes added to blocks by growing reallocations of all calls
+    pub total_realloc_grown_bytes: u64,
     pub has_data: bool,
-    pub has_unsupported_async: bool,
+    /// Some calls were spans, which have no allocation data
+    pub has_untracked_allocs: bool,
     pub wrapper: bool,
     pub cross_thread: bool,
     pub recent_logs: VecDeque<LogEntry>,
+    pub stacks: StacksMap,
 }
 
 impl FunctionStats {
@@ -150,14 +202,23 @@ impl FunctionStats {
     const LOW_DURATION_NS: u64 = 1;
     const HIGH_DURATION_NS: u64 = 3_600_000_000_000; // 1 hour in nanoseconds
     const SIGFIGS: u8 = 3;
+    /// Rolling window slots exist per function every few seconds, so they use
+    /// smaller auto-resizing histograms.
+    const WINDOW_SIGFIGS: u8 = 2;
 
     #[allow(clippy::too_many_arguments)]
     pub fn new_alloc(
         bytes_total: u64,
         count_total: u64,
+        retained_bytes: u64,
+        size_classes: SizeClassCounts,
+        realloc_count: u64,
+        realloc_grown_bytes: u64,
         duration: Duration,
+        self_ns: u64,
+        call_site: CallSite,
         elapsed: Duration,
-        unsupported_async: bool,
+        untracked_allocs: bool,
         wrapper: bool,
         cross_thread: bool,
         recent_logs_limit: usize,
@@ -181,24 +242,36 @@ impl FunctionStats {
 
         let duration_ns = duration.as_nanos() as u64;
         let mut recent_logs = VecDeque::with_capacity(recent_logs_limit);
-        let (bytes_opt, count_opt) = if unsupported_async || cross_thread {
+        let (bytes_opt, count_opt) = if untracked_allocs || cross_thread {
             (None, None)
         } else {
             (Some(bytes_total), Some(count_total))
         };
         recent_logs.push_back((bytes_opt, count_opt, duration_ns, elapsed, tid, result_log));
 
+        let mut stacks = StacksMap::new();
+        stacks
+            .entry(call_site.stack)
+            .or_default()
+            .record(duration_ns, self_ns);
+
         let mut s = Self {
             count: 1,
             bytes_total_hist: Some(bytes_total_hist),
             count_total_hist: Some(count_total_hist),
             duration_hist: Some(duration_hist),
             total_duration_ns: duration_ns,
+            total_self_ns: self_ns,
+            total_retained_bytes: retained_bytes,
+            size_classes,
+            total_realloc_count: realloc_count,
+            total_realloc_grown_bytes: realloc_grown_bytes,
             has_data: true,
-            has_unsupported_async: unsupported_async,
+            has_untracked_allocs: untracked_allocs,
             wrapper,
             cross_thread,
             recent_logs,
+            stacks,
         };
         s.record_alloc(bytes_total, count_total);
         s.record_duration(duration_ns);
@@ -221,6 +294,13 @@ impl FunctionStats {
         }
     }
 
+    #[inline]
+    fn record_size_classes(&mut self, size_classes: &SizeClassCounts) {
+        for (total, count) in self.size_classes.iter_mut().zip(size_classes) {
+            *total += count;
+        }
+    }
+
     #[inline]
     fn record_duration(&mut self, duration_ns: u64) {
         if let Some(ref mut duration_hist) = self.duration_hist {
@@ -237,27 +317,42 @@ impl FunctionStats {
         &mut self,
         bytes_total: u64,
         count_total: u64,
+        retained_bytes: u64,
+        size_classes: SizeClassCounts,
+        realloc_count: u64,
+        realloc_grown_bytes: u64,
         duration: Duration,
+        self_ns: u64,
+        call_site: CallSite,
         elapsed: Duration,
-        unsupported_async: bool,
+        untracked_allocs: bool,
         cross_thread: bool,
         tid: Option<u64>,
         result_log: Option<String>,
     ) {
         self.count += 1;
-        self.has_unsupported_async |= unsupported_async;
+        self.has_untracked_allocs |= untracked_allocs;
         self.cross_thread |= cross_thread;
         self.record_alloc(bytes_total, count_total);
 
         let duration_ns = duration.as_nanos() as u64;
         self.total_duration_ns += duration_ns;
+        self.total_self_ns += self_ns;
+        self.total_retained_bytes += retained_bytes;
+        self.record_size_classes(&size_classes);
+        self.total_realloc_count += realloc_count;
+        self.total_realloc_grown_bytes += realloc_grown_bytes;
         self.record_duration(duration_ns);
+        self.stacks
+            .entry(call_site.stack)
+            .or_default()
+            .record(duration_ns, self_ns);
 
         if self.recent_logs.len() == self.recent_logs.capacity() && self.recent_logs.capacity() > 0
         {
             self.recent_logs.pop_front();
         }
-        let (bytes_opt, count_opt) = if unsupported_async || cross_thread {
+        let (bytes_opt, count_opt) = if untracked_allocs || cross_thread {
             (None, None)
         } else {
             (Some(bytes_total), Some(count_total))
@@ -266,6 +361,76 @@ impl FunctionStats {
             .push_back((bytes_opt, count_opt, duration_ns, elapsed, tid, result_log));
     }
 
+    /// Empty stats of a rolling window slot, without recent logs.
+    pub(crate) fn new_window(wrapper: bool) -> Self {
+        let window_hist =
+            || Some(Histogram::<u64>::new(Self::WINDOW_SIGFIGS).expect("histogram init"));
+
+        Self {
+            count: 0,
+            bytes_total_hist: window_hist(),
+            count_total_hist: window_hist(),
+            duration_hist: window_hist(),
+            total_duration_ns: 0,
+            total_self_ns: 0,
+            total_retained_bytes: 0,
+            size_classes: [0; SIZE_CLASSES],
+            total_realloc_count: 0,
+            total_realloc_grown_bytes: 0,
+            has_data: false,
+            has_untracked_allocs: false,
+            wrapper,
+            cross_thread: false,
+            recent_logs: VecDeque::new(),
+            stacks: StacksMap::new(),
+        }
+    }
+
+    pub(crate) fn record_window(&mut self, m: &Measurement) {
+        let duration_ns = m.duration.as_nanos() as u64;
+        self.count += 1;
+        self.has_data = true;
+        self.has_untracked_allocs |= m.untracked_allocs;
+        self.cross_thread |= m.cross_thread;
+        self.record_alloc(m.bytes_total, m.count_total);
+        self.total_duration_ns += duration_ns;
+        self.total_self_ns += m.self_duration_ns;
+        self.total_retained_bytes += m.retained_bytes;
+        self.record_size_classes(&m.size_classes);
+        self.total_realloc_count += m.realloc_count;
+        self.total_realloc_grown_bytes += m.realloc_grown_bytes;
+        self.record_duration(duration_ns);
+        self.stacks
+            .entry(m.call_site.stack)
+            .or_default()
+            .record(duration_ns, m.self_duration_ns);
+    }
+
+    /// Adds the calls of another rolling window slot.
+    pub(crate) fn merge(&mut self, other: &Self) {
+        self.count += other.count;
+        self.total_duration_ns += other.total_duration_ns;
+        self.total_self_ns += other.total_self_ns;
+        self.total_retained_bytes += other.total_retained_bytes;
+        self.record_size_classes(&other.size_classes);
+        self.total_realloc_count += other.total_realloc_count;
+        self.total_realloc_grown_bytes += other.total_realloc_grown_bytes;
+        self.has_data |= other.has_data;
+        self.has_untracked_allocs |= other.has_untracked_allocs;
+        self.wrapper |= other.wrapper;
+        self.cross_thread |= other.cross_thread;
+        for (hist, other_hist) in [
+            (&mut self.bytes_total_hist, &other.bytes_total_hist),
+            (&mut self.count_total_hist, &other.count_total_hist),
+            (&mut self.duration_hist, &other.duration_hist),
+        ] {
+            if let (Some(hist), Some(other_hist)) = (hist.as_mut(), other_hist.as_ref()) {
+                let _ = hist.add(other_hist);
+            }
+        }
+        merge_stacks(&mut self.stacks, &other.stacks);
+    }
+
     #[inline]
     pub fn bytes_total_percentile(&self, p: f64) -> u64 {
         if self.count == 0 || self.bytes_total_hist.is_none() {
@@ -335,6 +500,33 @@ impl FunctionStats {
         self.duration_hist.as_ref().unwrap().value_at_percentile(p)
     }
 
+    /// Cumulative number of calls that took at most each of `bounds_ns`.
+    pub fn duration_buckets(&self, bounds_ns: &[u64]) -> Vec<u64> {
+        bounds_ns
+            .iter()
+            .map(|bound| match &self.duration_hist {
+                Some(hist) => {
+                    hist.count_between(Self::LOW_DURATION_NS, (*bound).min(Self::HIGH_DURATION_NS))
+                }
+                None => 0,
+            })
+            .collect()
+    }
+
+    /// Non-empty allocation size classes of all calls.
+    pub(crate) fn size_classes_json(&self) -> Vec<SizeClassJson> {
+        self.size_classes
+            .iter()
+            .enumerate()
+            .filter(|(_, count)| **count > 0)
+            .map(|(class, count)| SizeClassJson {
+                min_bytes: if class == 0 { 0 } else { 1 << class },
+                max_bytes: (class + 1 < SIZE_CLASSES).then_some(1 << (class + 1)),
+                count: *count,
+            })
+            .collect()
+    }
+
     #[inline]
     pub fn avg_duration_ns(&self) -> u64 {
         if self.count == 0 || self.duration_hist.is_none() {
@@ -344,15 +536,38 @@ impl FunctionStats {
     }
 }
 
+/// Stats sent by the worker when it shuts down, with the time they were last reset.
+pub(crate) type FinalStats = (HashMap<&'static str, FunctionStats>, Instant);
+
 pub(crate) struct FunctionsState {
     pub sender: Option<Sender<Measurement>>,
     pub shutdown_tx: Option<Sender<()>>,
-    pub completion_rx: Option<Mutex<Receiver<HashMap<&'static str, FunctionStats>>>>,
+    pub completion_rx: Option<Mutex<Receiver<FinalStats>>>,
     pub query_tx: Option<Sender<super::super::FunctionsQuery>>,
     pub start_time: Instant,
     pub caller_name: &'static str,
     pub percentiles: Vec<u8>,
     pub limit: usize,
+    pub sort_by: crate::SortBy,
+}
+
+/// Record a measurement on the trace timeline if `HOTPATH_TRACE_FILE` is set.
+pub(crate) fn trace_measurement(m: &Measurement) {
+    if !crate::trace::is_enabled() {
+        return;
+    }
+
+    crate::trace::record_span(
+        m.name,
+        "function",
+        m.tid,
+        m.measurement_time,
+        m.duration.as_nanos() as u64,
+        Some(serde_json::json!({
+            "bytes": m.bytes_total,
+            "allocs": m.count_total,
+        })),
+    );
 }
 
 pub(crate) fn process_measurement(
@@ -362,13 +577,20 @@ pub(crate) fn process_measurement(
     start_time: Instant,
 ) {
     let elapsed = m.measurement_time.duration_since(start_time);
+
     if let Some(s) = stats.get_mut(m.name) {
         s.update_alloc(
             m.bytes_total,
             m.count_total,
+            m.retained_bytes,
+            m.size_classes,
+            m.realloc_count,
+            m.realloc_grown_bytes,
             m.duration,
+            m.self_duration_ns,
+            m.call_site,
             elapsed,
-            m.unsupported_async,
+            m.untracked_allocs,
             m.cross_thread,
             m.tid,
             m.result_log,
@@ -379,9 +601,15 @@ pub(crate) fn process_measurement(
             FunctionStats::new_alloc(
                 m.bytes_total,
                 m.count_total,
+                m.retained_bytes,
+                m.size_classes,
+                m.realloc_count,
+                m.realloc_grown_bytes,
                 m.duration,
+                m.self_duration_ns,
+                m.call_site,
                 elapsed,
-                m.unsupported_async,
+                m.untracked_allocs,
                 m.wrapper,
                 m.cross_thread,
                 recent_logs_limit,
@@ -399,21 +627,35 @@ pub fn send_alloc_measurement(
     name: &'static str,
     bytes_total: u64,
     count_total: u64,
+    retained_bytes: u64,
+    size_classes: SizeClassCounts,
+    realloc_count: u64,
+    realloc_grown_bytes: u64,
     duration: Duration,
-    unsupported_async: bool,
+    child_ns: u64,
+    call_site: CallSite,
+    untracked_allocs: bool,
     wrapper: bool,
     cross_thread: bool,
     tid: Option<u64>,
+    tag: Option<&'static str>,
 ) {
     send_alloc_measurement_with_log(
         name,
         bytes_total,
         count_total,
+        retained_bytes,
+        size_classes,
+        realloc_count,
+        realloc_grown_bytes,
         duration,
-        unsupported_async,
+        child_ns,
+        call_site,
+        untracked_allocs,
         wrapper,
         cross_thread,
         tid,
+        tag,
         None,
     );
 }
@@ -423,11 +665,18 @@ pub fn send_alloc_measurement_with_log(
     name: &'static str,
     bytes_total: u64,
     count_total: u64,
+    retained_bytes: u64,
+    size_classes: SizeClassCounts,
+    realloc_count: u64,
+    realloc_grown_bytes: u64,
     duration: Duration,
-    unsupported_async: bool,
+    child_ns: u64,
+    call_site: CallSite,
+    untracked_allocs: bool,
     wrapper: bool,
     cross_thread: bool,
     tid: Option<u64>,
+    tag: Option<&'static str>,
     result_log: Option<String>,
 ) {
     if FUNCTIONS_STATE.get().is_none() {
@@ -436,16 +685,25 @@ pub fn send_alloc_measurement_with_log(
         );
     }
 
-    MEASUREMENT_BATCH.with(|batch| {
+    // The batch is gone when a span is dropped during thread teardown, the
+    // measurement is dropped with it.
+    let _ = MEASUREMENT_BATCH.try_with(|batch| {
         batch.borrow_mut().add(
             name,
             bytes_total,
             count_total,
+            retained_bytes,
+            size_classes,
+            realloc_count,
+            realloc_grown_bytes,
             duration,
-            unsupported_async,
+            child_ns,
+            call_site,
+            untracked_allocs,
             wrapper,
             cross_thread,
             tid,
+            tag,
             result_log,
         );
     });
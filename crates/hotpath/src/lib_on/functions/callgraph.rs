@@ -0,0 +1,533 @@
+//! Caller/callee attribution for measured functions.
+//!
+//! Every measurement guard registers a frame on a per-thread stack of active measured
+//! functions. The frame below it is the caller, and completed nested calls are accumulated
+//! into the caller's frame so that self time (inclusive duration minus nested measured calls)
+//! can be reported next to the inclusive duration.
+//!
+//! Async functions are driven by [`MeasuredFuture`](super::MeasuredFuture), which suspends
+//! the frame whenever the future returns `Pending` and restores it on the next poll.
+//! That way interleaved tasks on the same worker thread never see each other as callers.
+//!
+//! Full stack paths are interned into small ids, so a measurement can carry the whole
+//! chain of measured callers (used for folded stack output) without allocating.
+
+use std::cell::{Cell, RefCell};
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+use std::sync::{LazyLock, RwLock};
+
+use crate::output::CallTreeNode;
+
+/// Taken once per thread, frame ids are this id followed by a per-thread counter,
+/// so entering a frame doesn't touch any shared state.
+static THREAD_ID_COUNTER: AtomicU64 = AtomicU64::new(1);
+const FRAME_COUNTER_BITS: u32 = 40;
+
+/// Id of the empty stack, i.e. code running outside of any measured function.
+const ROOT_STACK: u32 = 0;
+
+/// Stack ids start with the generation of the table they were interned in, so ids taken
+/// before a reset cleared the table are never resolved to the wrong path.
+const GENERATION_SHIFT: u32 = 24;
+const INDEX_MASK: u32 = (1 << GENERATION_SHIFT) - 1;
+const GENERATION_MASK: u32 = u32::MAX >> GENERATION_SHIFT;
+
+static STACK_GENERATION: AtomicU32 = AtomicU32::new(0);
+
+struct Frame {
+    id: u64,
+    name: &'static str,
+    stack: u32,
+    /// Inclusive duration of measured calls that completed inside this frame
+    child_ns: u64,
+}
+
+struct StackNode {
+    parent: u32,
+    name: &'static str,
+    /// Spans are not on the call stack, their time is also in the caller's self time
+    detached: bool,
+}
+
+/// Interned stack paths, each node points to the stack of its caller.
+struct StackTable {
+    generation: u32,
+    ids: HashMap<(u32, &'static str, bool), u32>,
+    nodes: Vec<StackNode>,
+}
+
+impl StackTable {
+    fn new(generation: u32) -> Self {
+        Self {
+            generation,
+            ids: HashMap::new(),
+            nodes: vec![StackNode {
+                parent: ROOT_STACK,
+                name: "",
+                detached: false,
+            }],
+        }
+    }
+
+    /// Node of a stack interned since the last reset.
+    fn node(&self, stack: u32) -> Option<&StackNode> {
+        if stack == ROOT_STACK || stack >> GENERATION_SHIFT != self.generation {
+            return None;
+        }
+        self.nodes.get((stack & INDEX_MASK) as usize)
+    }
+
+    fn intern(&mut self, parent: u32, name: &'static str, detached: bool) -> u32 {
+        let parent = if self.node(parent).is_some() {
+            parent
+        } else {
+            ROOT_STACK
+        };
+        if let Some(id) = self.ids.get(&(parent, name, detached)) {
+            return *id;
+        }
+
+        // Recursive calls share the stack of the outermost call, so the table
+        // doesn't grow with the recursion depth.
+        let mut current = parent;
+        let recursive = loop {
+            let Some(node) = self.node(current) else {
+                break None;
+            };
+            if !detached && !node.detached && node.name == name {
+                break Some(current);
+            }
+            current = node.parent;
+        };
+
+        let id = match recursive {
+            Some(id) => id,
+            None if self.nodes.len() > INDEX_MASK as usize => return parent,
+            None => {
+                self.nodes.push(StackNode {
+                    parent,
+                    name,
+                    detached,
+                });
+                (self.generation << GENERATION_SHIFT) | (self.nodes.len() - 1) as u32
+            }
+        };
+        self.ids.insert((parent, name, detached), id);
+        id
+    }
+
+    /// Function names of a stack, outermost caller first. Empty for spans and stacks
+    /// interned before the last reset.
+    fn path(&self, stack: u32) -> Vec<&'static str> {
+        let mut path = Vec::new();
+        let mut current = stack;
+        while let Some(node) = self.node(current) {
+            if node.detached && current == stack {
+                return Vec::new();
+            }
+            path.push(node.name);
+            current = node.parent;
+        }
+        path.reverse();
+        path
+    }
+}
+
+static STACKS: LazyLock<RwLock<StackTable>> = LazyLock::new(|| RwLock::new(StackTable::new(0)));
+
+/// Per-thread copy of the interned ids, dropped when the generation changes.
+struct StackCache {
+    generation: u32,
+    ids: HashMap<(u32, &'static str, bool), u32>,
+}
+
+thread_local! {
+    static CALL_STACK: RefCell<Vec<Frame>> = RefCell::new(Vec::with_capacity(32));
+    static STACK_IDS: RefCell<StackCache> = RefCell::new(StackCache {
+        generation: 0,
+        ids: HashMap::new(),
+    });
+    static NEXT_FRAME_ID: Cell<u64> =
+        Cell::new(THREAD_ID_COUNTER.fetch_add(1, Ordering::Relaxed) << FRAME_COUNTER_BITS);
+}
+
+/// Returns an id unique across threads, frames of suspended futures can resume on another one.
+#[inline]
+fn next_frame_id() -> u64 {
+    NEXT_FRAME_ID
+        .try_with(|next| {
+            let id = next.get();
+            next.set(id + 1);
+            id
+        })
+        .unwrap_or(0)
+}
+
+/// Whether `stack` was interned since the last reset.
+#[inline]
+fn is_current(stack: u32) -> bool {
+    stack == ROOT_STACK || stack >> GENERATION_SHIFT == STACK_GENERATION.load(Ordering::Relaxed)
+}
+
+/// Returns the id of `parent` stack extended with `name`.
+fn intern_stack(parent: u32, name: &'static str, detached: bool) -> u32 {
+    let key = (parent, name, detached);
+    STACK_IDS
+        .try_with(|cache| {
+            let generation = STACK_GENERATION.load(Ordering::Relaxed);
+            if cache.borrow().generation != generation {
+                untracked(|| {
+                    let mut cache = cache.borrow_mut();
+                    cache.generation = generation;
+                    cache.ids = HashMap::new();
+                });
+            }
+            if let Some(id) = cache.borrow().ids.get(&key) {
+                return *id;
+            }
+
+            let (id, table_generation) = untracked(|| {
+                let Ok(mut table) = STACKS.write() else {
+                    return (ROOT_STACK, generation);
+                };
+                (table.intern(parent, name, detached), table.generation)
+            });
+            if id != ROOT_STACK && table_generation == generation {
+                untracked(|| cache.borrow_mut().ids.insert(key, id));
+            }
+            id
+        })
+        .unwrap_or(ROOT_STACK)
+}
+
+/// Re-interns the stacks of frames entered before a reset cleared the table.
+fn refresh_stacks(frames: &mut [Frame]) {
+    if frames.iter().all(|f| is_current(f.stack)) {
+        return;
+    }
+
+    let mut parent = ROOT_STACK;
+    for frame in frames {
+        if !is_current(frame.stack) {
+            frame.stack = intern_stack(parent, frame.name, false);
+        }
+        parent = frame.stack;
+    }
+}
+
+/// Clears the interned stacks, frames still on a call stack are interned again on their
+/// next nested call or when they exit.
+pub(crate) fn reset_stacks() {
+    let Ok(mut table) = STACKS.write() else {
+        return;
+    };
+    let generation = (table.generation + 1) & GENERATION_MASK;
+    *table = StackTable::new(generation);
+    STACK_GENERATION.store(generation, Ordering::Relaxed);
+}
+
+/// Runs `f` without attributing its allocations to the measured functions.
+#[inline]
+fn untracked<T>(f: impl FnOnce() -> T) -> T {
+    #[cfg(feature = "hotpath-alloc")]
+    {
+        let was_enabled =
+            super::alloc::core::ALLOCATIONS.with(|stack| stack.tracking_enabled.replace(false));
+        let result = f();
+        super::alloc::core::ALLOCATIONS.with(|stack| stack.tracking_enabled.set(was_enabled));
+        result
+    }
+
+    #[cfg(not(feature = "hotpath-alloc"))]
+    f()
+}
+
+/// Where a measured call was made from.
+#[derive(Debug, Clone, Copy)]
+pub struct CallSite {
+    /// Interned stack of measured functions, ending with the called function
+    pub stack: u32,
+}
+
+#[inline]
+fn take_frame(stack: &mut Vec<Frame>, id: u64) -> Option<Frame> {
+    // The frame is almost always on top, other positions only happen when
+    // a guard outlives an await point outside of a measured future.
+    let pos = stack.iter().rposition(|f| f.id == id)?;
+    Some(stack.remove(pos))
+}
+
+/// Stack of the frame `id`, or of the root if it's not on `frames`.
+fn frame_stack(frames: &[Frame], id: Option<u64>) -> u32 {
+    id.and_then(|id| frames.iter().rev().find(|f| f.id == id))
+        .map_or(ROOT_STACK, |f| f.stack)
+}
+
+/// Position of a measured call in the per-thread call stack.
+pub(crate) struct CallFrame {
+    id: u64,
+    name: &'static str,
+    parent: Option<u64>,
+    stack: u32,
+    suspended: Option<Frame>,
+}
+
+impl CallFrame {
+    /// Pushes a new frame for `name` and records the currently active frame as its caller.
+    #[inline]
+    pub(crate) fn enter(name: &'static str) -> Self {
+        let id = next_frame_id();
+
+        let caller = CALL_STACK
+            .try_with(|stack| {
+                let mut stack = stack.borrow_mut();
+                refresh_stacks(&mut stack);
+                stack.last().map(|f| (f.id, f.stack))
+            })
+            .ok()
+            .flatten();
+
+        let parent = caller.map(|(id, _)| id);
+        let stack = intern_stack(caller.map_or(ROOT_STACK, |(_, stack)| stack), name, false);
+
+        let _ = CALL_STACK.try_with(|call_stack| {
+            call_stack.borrow_mut().push(Frame {
+                id,
+                name,
+                stack,
+                child_ns: 0,
+            })
+        });
+
+        Self {
+            id,
+            name,
+            parent,
+            stack,
+            suspended: None,
+        }
+    }
+
+    /// Stack of the measured function that was active when this frame was entered.
+    #[inline]
+    pub(crate) fn call_site(&self) -> CallSite {
+        CallSite { stack: self.stack }
+    }
+
+    /// Removes the frame from the current thread's stack while its future is not polled.
+    pub(crate) fn suspend(&mut self) {
+        if self.suspended.is_some() {
+            return;
+        }
+
+        self.suspended = CALL_STACK
+            .try_with(|stack| take_frame(&mut stack.borrow_mut(), self.id))
+            .ok()
+            .flatten();
+    }
+
+    /// Puts a suspended frame back on top of the current thread's stack.
+    pub(crate) fn resume(&mut self) {
+        if let Some(frame) = self.suspended.take() {
+            let _ = CALL_STACK.try_with(|stack| stack.borrow_mut().push(frame));
+        }
+    }
+
+    /// Pops the frame, charges `duration_ns` to the caller and returns the time
+    /// spent in nested measured calls.
+    #[inline]
+    pub(crate) fn exit(&mut self, duration_ns: u64) -> u64 {
+        let child_ns = match self.suspended.take() {
+            Some(frame) => {
+                self.stack = frame.stack;
+                frame.child_ns
+            }
+            None => CALL_STACK
+                .try_with(|stack| {
+                    let mut stack = stack.borrow_mut();
+                    let child_ns = match take_frame(&mut stack, self.id) {
+                        Some(frame) => {
+                            self.stack = frame.stack;
+                            frame.child_ns
+                        }
+                        None => 0,
+                    };
+
+                    if let Some(parent_id) = self.parent {
+                        if let Some(parent) = stack.iter_mut().rev().find(|f| f.id == parent_id) {
+                            parent.child_ns += duration_ns;
+                        }
+                    }
+
+                    child_ns
+                })
+                .unwrap_or(0),
+        };
+
+        if !is_current(self.stack) {
+            self.stack = CALL_STACK
+                .try_with(|stack| {
+                    let mut stack = stack.borrow_mut();
+                    refresh_stacks(&mut stack);
+                    frame_stack(&stack, self.parent)
+                })
+                .map_or(ROOT_STACK, |parent| intern_stack(parent, self.name, false));
+        }
+
+        child_ns
+    }
+}
+
+/// Call site of a measurement that is not kept on the call stack, with the currently
+/// active frame as its caller.
+///
+/// Its time is not subtracted from the caller's self time, so its stack is marked
+/// detached and left out of folded stacks instead of counting that time twice.
+pub(crate) fn detached_call_site(name: &'static str) -> CallSite {
+    let parent = CALL_STACK
+        .try_with(|stack| {
+            let mut stack = stack.borrow_mut();
+            refresh_stacks(&mut stack);
+            stack.last().map_or(ROOT_STACK, |f| f.stack)
+        })
+        .unwrap_or(ROOT_STACK);
+
+    CallSite {
+        stack: intern_stack(parent, name, true),
+    }
+}
+
+/// Aggregated statistics of calls made from a single call stack.
+#[derive(Debug, Clone, Default)]
+pub struct CallEdgeStats {
+    pub count: u64,
+    pub total_duration_ns: u64,
+    pub self_duration_ns: u64,
+}
+
+impl CallEdgeStats {
+    #[inline]
+    pub(crate) fn record(&mut self, duration_ns: u64, self_duration_ns: u64) {
+        self.count += 1;
+        self.total_duration_ns += duration_ns;
+        self.self_duration_ns += self_duration_ns;
+    }
+
+    #[inline]
+    pub(crate) fn merge(&mut self, other: &Self) {
+        self.count += other.count;
+        self.total_duration_ns += other.total_duration_ns;
+        self.self_duration_ns += other.self_duration_ns;
+    }
+}
+
+/// Calls keyed by interned stack id.
+pub type StacksMap = HashMap<u32, CallEdgeStats>;
+
+/// Adds the calls of `other` to `stacks`.
+pub(crate) fn merge_stacks(stacks: &mut StacksMap, other: &StacksMap) {
+    for (stack, edge) in other {
+        stacks.entry(*stack).or_default().merge(edge);
+    }
+}
+
+/// Resolves recorded stacks into Brendan Gregg's collapsed stack format, i.e.
+/// `outer;inner` paths paired with the self time spent at the end of the path.
+pub(crate) fn build_folded_stacks<'a>(
+    stacks: impl IntoIterator<Item = &'a StacksMap>,
+) -> Vec<(String, u64)> {
+    let Ok(table) = STACKS.read() else {
+        return Vec::new();
+    };
+
+    let mut folded: Vec<(String, u64)> = stacks
+        .into_iter()
+        .flatten()
+        .filter(|(_, edge)| edge.self_duration_ns > 0)
+        .map(|(stack, edge)| (table.path(*stack).join(";"), edge.self_duration_ns))
+        .filter(|(path, _)| !path.is_empty())
+        .collect();
+
+    folded.sort();
+    folded
+}
+
+/// Builds a tree of the recorded stacks rooted at functions that were called outside of
+/// any other measured function, so every node only holds the calls made in its exact
+/// context. Recursive calls are counted in the node of the outermost call.
+///
+/// Callers that have not completed a single call yet (e.g. a long running `main`)
+/// are still reported, with zero calls and no timings of their own.
+pub(crate) fn build_call_tree<'a>(
+    stacks: impl IntoIterator<Item = &'a StacksMap>,
+) -> Vec<CallTreeNode> {
+    let Ok(table) = STACKS.read() else {
+        return Vec::new();
+    };
+
+    let mut edges = StacksMap::new();
+    for (stack, edge) in stacks.into_iter().flatten() {
+        if table.node(*stack).is_some() {
+            edges.entry(*stack).or_default().merge(edge);
+        }
+    }
+
+    let mut roots = Vec::new();
+    let mut children: HashMap<u32, Vec<u32>> = HashMap::new();
+    let mut added = HashSet::new();
+    for stack in edges.keys() {
+        let mut current = *stack;
+        while let Some(node) = table.node(current) {
+            if !added.insert(current) {
+                break;
+            }
+            match node.parent {
+                ROOT_STACK => roots.push(current),
+                parent => children.entry(parent).or_default().push(current),
+            }
+            current = node.parent;
+        }
+    }
+
+    let mut nodes: Vec<CallTreeNode> = roots
+        .into_iter()
+        .map(|stack| expand_node(stack, &table, &edges, &children))
+        .collect();
+    sort_nodes(&mut nodes);
+    nodes
+}
+
+fn expand_node(
+    stack: u32,
+    table: &StackTable,
+    edges: &StacksMap,
+    children: &HashMap<u32, Vec<u32>>,
+) -> CallTreeNode {
+    let edge = edges.get(&stack).cloned().unwrap_or_default();
+    let mut node = CallTreeNode {
+        name: table.node(stack).map_or("", |n| n.name).to_string(),
+        calls: edge.count,
+        total_ns: edge.total_duration_ns,
+        self_ns: edge.self_duration_ns,
+        children: Vec::new(),
+    };
+
+    if let Some(callees) = children.get(&stack) {
+        node.children = callees
+            .iter()
+            .map(|callee| expand_node(*callee, table, edges, children))
+            .collect();
+        sort_nodes(&mut node.children);
+    }
+
+    node
+}
+
+fn sort_nodes(nodes: &mut [CallTreeNode]) {
+    nodes.sort_by(|a, b| {
+        b.total_ns
+            .cmp(&a.total_ns)
+            .then_with(|| a.name.cmp(&b.name))
+    });
+}
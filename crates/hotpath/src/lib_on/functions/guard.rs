@@ -1,33 +1,38 @@
 use arc_swap::ArcSwapOption;
-use crossbeam_channel::{bounded, select, unbounded};
+use crossbeam_channel::{bounded, never, select, tick, unbounded};
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex, RwLock};
 use std::thread;
 use std::time::Instant;
 
 use crate::http_server::HTTP_SERVER_PORT;
-use crate::output::{FunctionLogEntry, FunctionLogsJson, FunctionsJson, MetricsProvider};
-use crate::output_on::{JsonPrettyReporter, JsonReporter, TableReporter};
+use crate::output::{
+    FunctionLogEntry, FunctionLogsJson, FunctionsJson, FunctionsTreeJson, MetricsProvider,
+};
+use crate::output_on::{FoldedReporter, JsonPrettyReporter, JsonReporter, TableReporter};
 use crate::Reporter;
 
+use super::snapshot::StatsSnapshot;
+use super::tags::{tag_names, TaggedStats};
+use super::window::RollingStats;
 use super::{FunctionsQuery, FUNCTIONS_STATE};
 
 cfg_if::cfg_if! {
     if #[cfg(feature = "hotpath-alloc")] {
         use super::alloc::{
-            report::{StatsData, TimingStatsData},
-            state::{FunctionStats, FunctionsState, Measurement, process_measurement, flush_batch},
+            report::{StatsData, TimingStatsData, write_openmetrics},
+            state::{FinalStats, FunctionStats, FunctionsState, Measurement, process_measurement, trace_measurement, flush_batch},
         };
     } else {
         use super::timing::{
-            report::StatsData,
-            state::{FunctionStats, FunctionsState, Measurement, process_measurement, flush_batch},
+            report::{StatsData, write_openmetrics},
+            state::{FinalStats, FunctionStats, FunctionsState, Measurement, process_measurement, trace_measurement, flush_batch},
         };
     }
 }
 
 use super::MeasurementGuard;
-use crate::Format;
+use crate::{Format, SortBy};
 
 enum ReporterConfig {
     Format(Format),
@@ -107,6 +112,9 @@ pub struct FunctionsGuardBuilder {
     percentiles: Vec<u8>,
     reporter: ReporterConfig,
     limit: usize,
+    sort_by: SortBy,
+    report_interval: Option<std::time::Duration>,
+    report_delta: bool,
 }
 
 impl FunctionsGuardBuilder {
@@ -134,6 +142,9 @@ impl FunctionsGuardBuilder {
             percentiles: vec![95],
             reporter: ReporterConfig::None,
             limit: 15,
+            sort_by: SortBy::Total,
+            report_interval: None,
+            report_delta: false,
         }
     }
 
@@ -194,6 +205,31 @@ impl FunctionsGuardBuilder {
         self
     }
 
+    /// Sets the column used to rank functions in timing reports.
+    ///
+    /// Sorting by [`SortBy::SelfTime`] ranks functions by the time spent in their own
+    /// body, so wrappers like `main` no longer dominate the report. The `limit` is
+    /// applied after sorting. Allocation reports are always sorted by total bytes.
+    ///
+    /// Default: [`SortBy::Total`]
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #[cfg(feature = "hotpath")]
+    /// # {
+    /// use hotpath::{FunctionsGuardBuilder, SortBy};
+    ///
+    /// let _guard = FunctionsGuardBuilder::new("main")
+    ///     .sort_by(SortBy::SelfTime)
+    ///     .build();
+    /// # }
+    /// ```
+    pub fn sort_by(mut self, sort_by: SortBy) -> Self {
+        self.sort_by = sort_by;
+        self
+    }
+
     /// Sets the output format for the profiling report.
     ///
     /// # Arguments
@@ -261,6 +297,83 @@ impl FunctionsGuardBuilder {
         self
     }
 
+    /// Invokes the reporter periodically while the guard is alive.
+    ///
+    /// Every `interval` the background worker passes a fresh snapshot to the reporter,
+    /// so long-running services produce reports before shutdown. The final report is
+    /// still generated when the guard is dropped.
+    ///
+    /// Default: reports only on drop
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #[cfg(feature = "hotpath")]
+    /// # {
+    /// use std::time::Duration;
+    /// use hotpath::{FunctionsGuardBuilder, Format};
+    ///
+    /// let _guard = FunctionsGuardBuilder::new("server")
+    ///     .format(Format::Json)
+    ///     .report_interval(Duration::from_secs(60))
+    ///     .build();
+    /// # }
+    /// ```
+    pub fn report_interval(mut self, interval: std::time::Duration) -> Self {
+        self.report_interval = Some(interval);
+        self
+    }
+
+    /// Limits periodic reports to measurements collected since the previous report.
+    ///
+    /// With `report_delta(true)` each report set up by
+    /// [`report_interval`](Self::report_interval) covers only its own interval, which is
+    /// easier to compare over time. The report generated on drop always covers the
+    /// whole profiling session.
+    ///
+    /// Default: `false`
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #[cfg(feature = "hotpath")]
+    /// # {
+    /// use std::time::Duration;
+    /// use hotpath::FunctionsGuardBuilder;
+    ///
+    /// let _guard = FunctionsGuardBuilder::new("server")
+    ///     .report_interval(Duration::from_secs(10))
+    ///     .report_delta(true)
+    ///     .build();
+    /// # }
+    /// ```
+    pub fn report_delta(mut self, delta: bool) -> Self {
+        self.report_delta = delta;
+        self
+    }
+
+    /// Exports profiling stats to an OpenTelemetry collector via OTLP/HTTP.
+    ///
+    /// A snapshot is pushed from the background worker thread every
+    /// [`OtlpReporter::interval`](crate::OtlpReporter::interval), and a final one when the
+    /// guard is dropped. This replaces any format or custom reporter setting.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use hotpath::{FunctionsGuardBuilder, OtlpReporter};
+    ///
+    /// let _guard = FunctionsGuardBuilder::new("main")
+    ///     .otlp(OtlpReporter::new("http://localhost:4318"))
+    ///     .build();
+    /// ```
+    #[cfg(feature = "otlp")]
+    pub fn otlp(mut self, reporter: crate::OtlpReporter) -> Self {
+        self.report_interval = Some(reporter.export_interval());
+        self.reporter = ReporterConfig::Custom(Box::new(reporter));
+        self
+    }
+
     /// Builds and initializes the functions profiling guard.
     ///
     /// This method initializes the background profiling thread and returns a guard
@@ -288,6 +401,7 @@ impl FunctionsGuardBuilder {
                 Format::Table => Box::new(TableReporter),
                 Format::Json => Box::new(JsonReporter),
                 Format::JsonPretty => Box::new(JsonPrettyReporter),
+                Format::Folded => Box::new(FoldedReporter),
             },
             ReporterConfig::Custom(reporter) => reporter,
             ReporterConfig::None => Box::new(TableReporter),
@@ -298,11 +412,14 @@ impl FunctionsGuardBuilder {
             .and_then(|s| s.parse::<usize>().ok())
             .unwrap_or(50);
 
-        FunctionsGuard::new(
+        FunctionsGuard::start(
             self.caller_name,
             &self.percentiles,
             self.limit,
+            self.sort_by,
             reporter,
+            self.report_interval,
+            self.report_delta,
             recent_logs_limit,
         )
     }
@@ -349,16 +466,43 @@ impl FunctionsGuardBuilder {
 #[must_use = "guard is dropped immediately without generating a report"]
 pub struct FunctionsGuard {
     state: Arc<RwLock<FunctionsState>>,
-    reporter: Box<dyn Reporter>,
+    reporter: Arc<dyn Reporter>,
+    /// Thread running periodic reports, if a report interval is set
+    report_thread: Option<thread::JoinHandle<()>>,
     wrapper_guard: Option<MeasurementGuard>,
 }
 
 impl FunctionsGuard {
+    /// Starts profiling sorted by total time and without periodic reports, use
+    /// [`FunctionsGuardBuilder`] for the other options.
     pub fn new(
         caller_name: &'static str,
         percentiles: &[u8],
         limit: usize,
+        reporter: Box<dyn Reporter>,
+        recent_logs_limit: usize,
+    ) -> Self {
+        Self::start(
+            caller_name,
+            percentiles,
+            limit,
+            SortBy::Total,
+            reporter,
+            None,
+            false,
+            recent_logs_limit,
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn start(
+        caller_name: &'static str,
+        percentiles: &[u8],
+        limit: usize,
+        sort_by: SortBy,
         _reporter: Box<dyn Reporter>,
+        report_interval: Option<std::time::Duration>,
+        report_delta: bool,
         recent_logs_limit: usize,
     ) -> Self {
         // Disable allocation tracking during infrastructure initialization
@@ -381,8 +525,9 @@ impl FunctionsGuard {
 
         let (tx, rx) = unbounded::<Measurement>();
         let (shutdown_tx, shutdown_rx) = bounded::<()>(1);
-        let (completion_tx, completion_rx) = bounded::<HashMap<&'static str, FunctionStats>>(1);
+        let (completion_tx, completion_rx) = bounded::<FinalStats>(1);
         let (query_tx, query_rx) = unbounded::<FunctionsQuery>();
+        crate::trace::init_trace();
         let start_time = Instant::now();
 
         let state_arc = Arc::new(RwLock::new(FunctionsState {
@@ -394,25 +539,68 @@ impl FunctionsGuard {
             caller_name,
             percentiles: percentiles.clone(),
             limit,
+            sort_by,
         }));
 
         let worker_start_time = start_time;
         let worker_percentiles = percentiles.clone();
         let worker_caller_name = caller_name;
         let worker_limit = limit;
+        let worker_sort_by = sort_by;
         let worker_recent_logs_limit = recent_logs_limit;
 
+        // Override reporter with JsonReporter when HOTPATH_JSON env var is enabled
+        let reporter: Arc<dyn Reporter> = if std::env::var("HOTPATH_JSON")
+            .map(|v| v.eq_ignore_ascii_case("true") || v == "1")
+            .unwrap_or(false)
+        {
+            Arc::new(JsonReporter)
+        } else {
+            Arc::from(_reporter)
+        };
+
+        let report_tick = report_interval.map(tick).unwrap_or_else(never);
+        // Reporters can block on I/O (e.g. OTLP exports), so they run on their own thread
+        // and the worker keeps answering queries meanwhile. The thread stops once the
+        // worker drops `report_tx` on shutdown.
+        let (report_tx, report_thread) = report_interval
+            .map(|_| {
+                let (report_tx, report_rx) = bounded::<StatsSnapshot>(1);
+                let worker_reporter = Arc::clone(&reporter);
+                let report_thread = thread::Builder::new()
+                    .name("hp-report".into())
+                    .spawn(move || {
+                        for snapshot in report_rx {
+                            if let Err(e) = worker_reporter.report(&snapshot) {
+                                eprintln!("Failed to report hotpath metrics: {}", e);
+                            }
+                        }
+                    })
+                    .expect("Failed to spawn hotpath-report thread");
+                (report_tx, report_thread)
+            })
+            .unzip();
+
         thread::Builder::new()
             .name("hp-functions".into())
             .spawn(move || {
                 let mut local_stats = HashMap::<&'static str, FunctionStats>::new();
+                // Stats collected since the last periodic report, only kept in delta mode
+                let mut interval_stats = report_delta.then(HashMap::<&'static str, FunctionStats>::new);
+                let mut last_report_time = worker_start_time;
+                let mut rolling_stats = RollingStats::default();
+                let mut tagged_stats = TaggedStats::new();
+                // Stats only cover measurements completed after the last reset
+                let mut reset_time = worker_start_time;
 
                 loop {
                     select! {
                         recv(rx) -> result => {
                             match result {
                                 Ok(measurement) => {
-                                    process_measurement(&mut local_stats, measurement, worker_recent_logs_limit, worker_start_time);
+                                    if let Some(measurement) = since_reset(measurement, reset_time) {
+                                        record_measurement(&mut local_stats, interval_stats.as_mut(), &mut tagged_stats, &mut rolling_stats, measurement, worker_recent_logs_limit, worker_start_time);
+                                    }
                                 }
                                 Err(_) => break, // Channel disconnected
                             }
@@ -420,64 +608,139 @@ impl FunctionsGuard {
                         recv(shutdown_rx) -> _ => {
                             // Process remaining messages after shutdown signal
                             while let Ok(measurement) = rx.try_recv() {
-                                process_measurement(&mut local_stats, measurement, worker_recent_logs_limit, worker_start_time);
+                                if let Some(measurement) = since_reset(measurement, reset_time) {
+                                    record_measurement(&mut local_stats, None, &mut tagged_stats, &mut rolling_stats, measurement, worker_recent_logs_limit, worker_start_time);
+                                }
                             }
                             break;
                         }
+                        recv(report_tick) -> _ => {
+                            let now = Instant::now();
+                            let (stats, elapsed) = match interval_stats.as_ref() {
+                                Some(interval_stats) => (interval_stats, now.duration_since(last_report_time)),
+                                None => (&local_stats, now.duration_since(reset_time)),
+                            };
+                            let metrics_provider = StatsData::new(
+                                stats,
+                                elapsed,
+                                worker_percentiles.clone(),
+                                worker_caller_name,
+                                worker_limit,
+                            )
+                            .with_sort_by(worker_sort_by)
+                            .with_exact_calls(interval_stats.is_none());
+                            let snapshot = StatsSnapshot::capture(&metrics_provider, worker_sort_by);
+                            // Skip the tick while the previous report is still being exported,
+                            // in delta mode its stats roll into the next one
+                            let sent = report_tx.as_ref().is_some_and(|report_tx| report_tx.try_send(snapshot).is_ok());
+                            if sent {
+                                if let Some(interval_stats) = interval_stats.as_mut() {
+                                    interval_stats.clear();
+                                }
+                                last_report_time = now;
+                            }
+                        }
                         recv(query_rx) -> result => {
                             if let Ok(query_request) = result {
                                 match query_request {
-                                    FunctionsQuery::Alloc(response_tx) => {
+                                    FunctionsQuery::Alloc { window, tag, response_tx } => {
                                         cfg_if::cfg_if! {
                                             if #[cfg(feature = "hotpath-alloc")] {
                                                 // Create allocation metrics snapshot
                                                 use crate::output::MetricsProvider;
-                                                let total_elapsed = worker_start_time.elapsed();
+                                                let (window_stats, total_elapsed) = select_window(&rolling_stats, window, tag.as_deref(), worker_start_time.elapsed(), reset_time.elapsed());
+                                                let empty = HashMap::new();
                                                 let metrics_provider = StatsData::new(
-                                                    &local_stats,
+                                                    window_stats.as_ref().unwrap_or_else(|| select_tag(&local_stats, &tagged_stats, tag.as_deref(), &empty)),
                                                     total_elapsed,
                                                     worker_percentiles.clone(),
                                                     worker_caller_name,
                                                     worker_limit,
-                                                );
-                                                let metrics_json = FunctionsJson::from(&metrics_provider as &dyn MetricsProvider);
+                                                )
+                                                .with_sort_by(worker_sort_by)
+                                                .with_exact_calls(window.is_none() && tag.is_none());
+                                                let mut metrics_json = FunctionsJson::from(&metrics_provider as &dyn MetricsProvider);
+                                                metrics_json.tags = tag_names(&tagged_stats);
                                                 let _ = response_tx.send(Some(metrics_json));
                                             } else {
                                                 // Allocation profiling not available without hotpath-alloc feature
+                                                let _ = (window, tag);
                                                 let _ = response_tx.send(None);
                                             }
                                         }
                                     }
-                                    FunctionsQuery::Timing(response_tx) => {
+                                    FunctionsQuery::AllocRetained(response_tx) => {
+                                        cfg_if::cfg_if! {
+                                            if #[cfg(feature = "hotpath-alloc-retained")] {
+                                                let retained_json = super::alloc::retained::retained_json(
+                                                    &local_stats,
+                                                    reset_time.elapsed(),
+                                                    worker_caller_name,
+                                                );
+                                                let _ = response_tx.send(Some(retained_json));
+                                            } else {
+                                                let _ = response_tx.send(None);
+                                            }
+                                        }
+                                    }
+                                    FunctionsQuery::Timing { window, tag, response_tx } => {
+                                        let (window_stats, total_elapsed) = select_window(&rolling_stats, window, tag.as_deref(), worker_start_time.elapsed(), reset_time.elapsed());
+                                        let empty = HashMap::new();
+                                        let stats = window_stats.as_ref().unwrap_or_else(|| select_tag(&local_stats, &tagged_stats, tag.as_deref(), &empty));
                                         cfg_if::cfg_if! {
                                             if #[cfg(feature = "hotpath-alloc")] {
                                                 // Create timing metrics snapshot
                                                 use crate::output::MetricsProvider;
-                                                let total_elapsed = worker_start_time.elapsed();
                                                 let metrics_provider = TimingStatsData::new(
-                                                    &local_stats,
+                                                    stats,
                                                     total_elapsed,
                                                     worker_percentiles.clone(),
                                                     worker_caller_name,
                                                     worker_limit,
-                                                );
-                                                let metrics_json = FunctionsJson::from(&metrics_provider as &dyn MetricsProvider);
+                                                )
+                                                .with_sort_by(worker_sort_by)
+                                                .with_exact_calls(window.is_none() && tag.is_none());
+                                                let mut metrics_json = FunctionsJson::from(&metrics_provider as &dyn MetricsProvider);
+                                                metrics_json.tags = tag_names(&tagged_stats);
                                                 let _ = response_tx.send(metrics_json);
                                             } else {
                                                 use crate::output::MetricsProvider;
-                                                let total_elapsed = worker_start_time.elapsed();
                                                 let metrics_provider = StatsData::new(
-                                                    &local_stats,
+                                                    stats,
                                                     total_elapsed,
                                                     worker_percentiles.clone(),
                                                     worker_caller_name,
                                                     worker_limit,
-                                                );
-                                                let metrics_json = FunctionsJson::from(&metrics_provider as &dyn MetricsProvider);
+                                                )
+                                                .with_sort_by(worker_sort_by)
+                                                .with_exact_calls(window.is_none() && tag.is_none());
+                                                let mut metrics_json = FunctionsJson::from(&metrics_provider as &dyn MetricsProvider);
+                                                metrics_json.tags = tag_names(&tagged_stats);
                                                 let _ = response_tx.send(metrics_json);
                                             }
                                         }
                                     }
+                                    FunctionsQuery::TimingTree(response_tx) => {
+                                        let roots = super::callgraph::build_call_tree(
+                                            local_stats.values().map(|s| &s.stacks),
+                                        );
+                                        let _ = response_tx.send(FunctionsTreeJson {
+                                            total_elapsed: reset_time.elapsed().as_nanos() as u64,
+                                            caller_name: worker_caller_name.to_string(),
+                                            roots,
+                                        });
+                                    }
+                                    FunctionsQuery::TimingFolded(response_tx) => {
+                                        let folded = super::callgraph::build_folded_stacks(
+                                            local_stats.values().map(|s| &s.stacks),
+                                        );
+                                        let _ = response_tx.send(crate::output_on::folded_to_string(&folded));
+                                    }
+                                    FunctionsQuery::OpenMetrics(response_tx) => {
+                                        let mut writer = crate::openmetrics::MetricsWriter::default();
+                                        write_openmetrics(&local_stats, &mut writer);
+                                        let _ = response_tx.send(writer.into_string());
+                                    }
                                     FunctionsQuery::LogsTiming { function_name, response_tx } => {
                                         let response = if let Some(stats) = local_stats.get(function_name.as_str()) {
                                             cfg_if::cfg_if! {
@@ -511,6 +774,7 @@ impl FunctionsGuard {
                                                 function_name: function_name.clone(),
                                                 logs,
                                                 count: stats.count as usize,
+                                                size_classes: Vec::new(),
                                             })
                                         } else {
                                             // Function not found
@@ -537,6 +801,7 @@ impl FunctionsGuard {
                                                         function_name,
                                                         logs,
                                                         count: stats.count as usize, // Total invocations, not just recent logs
+                                                        size_classes: stats.size_classes_json(),
                                                     })
                                                 } else {
                                                     None
@@ -549,14 +814,28 @@ impl FunctionsGuard {
                                             }
                                         }
                                     }
+                                    FunctionsQuery::Reset { reset_time: time, response_tx } => {
+                                        local_stats.clear();
+                                        if let Some(interval_stats) = interval_stats.as_mut() {
+                                            interval_stats.clear();
+                                        }
+                                        rolling_stats = RollingStats::default();
+                                        tagged_stats.clear();
+                                        super::sampling::reset();
+                                        super::callgraph::reset_stacks();
+                                        reset_time = time;
+                                        last_report_time = last_report_time.max(time);
+                                        let _ = response_tx.send(());
+                                    }
                                 }
                             }
                         }
                     }
                 }
 
-                // Send stats via completion channel
-                let _ = completion_tx.send(local_stats);
+                // Stop the periodic reporter, then send stats via completion channel
+                drop(report_tx);
+                let _ = completion_tx.send((local_stats, reset_time));
             })
             .expect("Failed to spawn hotpath-worker thread");
 
@@ -569,16 +848,8 @@ impl FunctionsGuard {
         crate::channels::START_TIME.get_or_init(std::time::Instant::now);
 
         crate::http_server::start_metrics_server_once(*HTTP_SERVER_PORT);
-
-        // Override reporter with JsonReporter when HOTPATH_JSON env var is enabled
-        let reporter: Box<dyn Reporter> = if std::env::var("HOTPATH_JSON")
-            .map(|v| v.eq_ignore_ascii_case("true") || v == "1")
-            .unwrap_or(false)
-        {
-            Box::new(JsonReporter)
-        } else {
-            _reporter
-        };
+        #[cfg(feature = "tokio")]
+        crate::runtime::init_runtime_monitoring();
 
         let wrapper_guard = MeasurementGuard::build(caller_name, true, false);
 
@@ -591,11 +862,84 @@ impl FunctionsGuard {
         Self {
             state: Arc::clone(&state_arc),
             reporter,
+            report_thread,
             wrapper_guard: Some(wrapper_guard),
         }
     }
 }
 
+/// Aggregate a measurement into the session stats, the rolling window slots and, in
+/// delta report mode, into the stats of the current report interval.
+fn record_measurement(
+    stats: &mut HashMap<&'static str, FunctionStats>,
+    interval_stats: Option<&mut HashMap<&'static str, FunctionStats>>,
+    tagged_stats: &mut TaggedStats,
+    rolling_stats: &mut RollingStats,
+    measurement: Measurement,
+    recent_logs_limit: usize,
+    start_time: Instant,
+) {
+    trace_measurement(&measurement);
+    rolling_stats.record(&measurement, start_time);
+    if let Some(tag) = measurement.tag {
+        process_measurement(
+            tagged_stats.entry(tag).or_default(),
+            measurement.clone(),
+            recent_logs_limit,
+            start_time,
+        );
+    }
+    if let Some(interval_stats) = interval_stats {
+        process_measurement(
+            interval_stats,
+            measurement.clone(),
+            recent_logs_limit,
+            start_time,
+        );
+    }
+    process_measurement(stats, measurement, recent_logs_limit, start_time);
+}
+
+/// Drop calls started before the last reset, including the wrapper function, so the
+/// stats only cover a clean window.
+fn since_reset(measurement: Measurement, reset_time: Instant) -> Option<Measurement> {
+    (measurement.started_at() >= reset_time).then_some(measurement)
+}
+
+/// Stats of the requested rolling window, `None` for the since-start stats, paired
+/// with the time span they cover. Neither reaches back past the last reset.
+fn select_window(
+    rolling_stats: &RollingStats,
+    window: Option<std::time::Duration>,
+    tag: Option<&str>,
+    elapsed: std::time::Duration,
+    since_reset: std::time::Duration,
+) -> (
+    Option<HashMap<&'static str, FunctionStats>>,
+    std::time::Duration,
+) {
+    match window {
+        Some(window) => {
+            let (window_stats, covered) = rolling_stats.window(window, elapsed, tag);
+            (Some(window_stats), covered.min(since_reset))
+        }
+        None => (None, since_reset),
+    }
+}
+
+/// Since-start stats of all calls, or only of the calls tagged with `tag`.
+fn select_tag<'a>(
+    stats: &'a HashMap<&'static str, FunctionStats>,
+    tagged_stats: &'a TaggedStats,
+    tag: Option<&str>,
+    empty: &'a HashMap<&'static str, FunctionStats>,
+) -> &'a HashMap<&'static str, FunctionStats> {
+    match tag {
+        Some(tag) => tagged_stats.get(tag).unwrap_or(empty),
+        None => stats,
+    }
+}
+
 impl Drop for FunctionsGuard {
     fn drop(&mut self) {
         let wrapper_guard = self.wrapper_guard.take().unwrap();
@@ -625,16 +969,21 @@ impl Drop for FunctionsGuard {
 
         if let Some(rx_mutex) = completion_rx {
             if let Ok(rx) = rx_mutex.lock() {
-                if let Ok(stats) = rx.recv() {
+                if let Ok((stats, reset_time)) = rx.recv() {
+                    // Finish the last periodic report before the final one
+                    if let Some(report_thread) = self.report_thread.take() {
+                        let _ = report_thread.join();
+                    }
                     if let Ok(state_guard) = state.read() {
-                        let total_elapsed = end_time.duration_since(state_guard.start_time);
+                        let total_elapsed = end_time.duration_since(reset_time);
                         let metrics_provider = StatsData::new(
                             &stats,
                             total_elapsed,
                             state_guard.percentiles.clone(),
                             state_guard.caller_name,
                             state_guard.limit,
-                        );
+                        )
+                        .with_sort_by(state_guard.sort_by);
 
                         match self.reporter.report(&metrics_provider) {
                             Ok(()) => (),
@@ -648,5 +997,7 @@ impl Drop for FunctionsGuard {
         if let Some(arc_swap) = FUNCTIONS_STATE.get() {
             arc_swap.store(None);
         }
+
+        crate::trace::flush_trace();
     }
 }
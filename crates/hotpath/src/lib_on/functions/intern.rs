@@ -0,0 +1,47 @@
+//! Interning of runtime strings into `&'static str` keys of the stats maps.
+
+use std::collections::HashSet;
+use std::sync::{LazyLock, RwLock};
+
+/// Set of interned strings with a cap on distinct values.
+///
+/// Interned strings are leaked, they live as long as the process. Values past the cap
+/// are all mapped to the overflow value.
+pub(crate) struct Interner {
+    values: LazyLock<RwLock<HashSet<&'static str>>>,
+    max: usize,
+    overflow: &'static str,
+}
+
+impl Interner {
+    pub(crate) const fn new(max: usize, overflow: &'static str) -> Self {
+        Self {
+            values: LazyLock::new(|| RwLock::new(HashSet::new())),
+            max,
+            overflow,
+        }
+    }
+
+    /// Returns the interned copy of `value`, or the overflow value if the cap is reached.
+    pub(crate) fn intern(&self, value: &str) -> &'static str {
+        if let Ok(values) = self.values.read() {
+            if let Some(interned) = values.get(value) {
+                return interned;
+            }
+        }
+
+        let Ok(mut values) = self.values.write() else {
+            return self.overflow;
+        };
+        if let Some(interned) = values.get(value) {
+            return interned;
+        }
+        if values.len() >= self.max {
+            return self.overflow;
+        }
+
+        let interned: &'static str = Box::leak(value.to_owned().into_boxed_str());
+        values.insert(interned);
+        interned
+    }
+}
@@ -0,0 +1,22 @@
+//! Labels of `measure_block_dyn!`, only known at runtime.
+//!
+//! Labels are interned so they can key the stats maps like the static labels of
+//! `measure_block!`. Their number is capped at [`MAX_LABELS`] to bound memory, blocks
+//! with labels past the cap are all measured as [`OTHER_LABEL`].
+
+use super::intern::Interner;
+
+/// Maximum number of distinct dynamic labels
+pub(crate) const MAX_LABELS: usize = 256;
+
+/// Label of blocks whose label did not fit in [`MAX_LABELS`], in the `hotpath::` namespace
+/// so it can't merge with a block or function of the profiled program
+pub(crate) const OTHER_LABEL: &str = "hotpath::other";
+
+static LABELS: Interner = Interner::new(MAX_LABELS, OTHER_LABEL);
+
+/// Returns the stable key of a runtime label of a measured block.
+#[doc(hidden)]
+pub fn intern_label(label: &str) -> &'static str {
+    LABELS.intern(label)
+}
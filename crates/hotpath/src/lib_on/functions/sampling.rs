@@ -0,0 +1,282 @@
+//! Call sampling for very hot functions.
+//!
+//! Every measured function gets a [`Sampler`] static from `#[hotpath::measure]`. With a rate
+//! above 1, from `sample = N` or `HOTPATH_SAMPLE_RATE`, only every Nth call is measured. The
+//! sampler still counts every call, so reports show exact call counts and scale the totals
+//! of the measured calls up to all of them.
+//!
+//! Skipped calls still take a frame on the call stack, so measured calls made inside them
+//! are nested under the skipped function instead of its caller.
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Mutex, OnceLock};
+
+use super::callgraph::CallFrame;
+use super::{MeasuredFuture, MeasurementGuard, MeasurementGuardWithLog};
+
+/// Samplers of the functions that skipped calls, registered on their first call
+static SAMPLERS: Mutex<Vec<&'static Sampler>> = Mutex::new(Vec::new());
+
+static GLOBAL_SAMPLE_RATE: OnceLock<u64> = OnceLock::new();
+
+/// Sample rate of functions without `sample = N`, from `HOTPATH_SAMPLE_RATE`
+fn global_sample_rate() -> u64 {
+    *GLOBAL_SAMPLE_RATE.get_or_init(|| {
+        std::env::var("HOTPATH_SAMPLE_RATE")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(1)
+    })
+}
+
+/// Decides which calls of a measured function are measured.
+#[doc(hidden)]
+pub struct Sampler {
+    name: &'static str,
+    /// Measure 1 in `rate` calls, 0 falls back to `HOTPATH_SAMPLE_RATE`
+    rate: u64,
+    calls: AtomicU64,
+    sampled: AtomicU64,
+    registered: AtomicBool,
+}
+
+impl Sampler {
+    pub const fn new(name: &'static str, rate: u64) -> Self {
+        Self {
+            name,
+            rate,
+            calls: AtomicU64::new(0),
+            sampled: AtomicU64::new(0),
+            registered: AtomicBool::new(false),
+        }
+    }
+
+    /// Counts a call and returns whether it should be measured.
+    #[inline]
+    pub fn sample(&'static self) -> bool {
+        let rate = if self.rate > 0 {
+            self.rate
+        } else {
+            global_sample_rate()
+        };
+        if rate <= 1 {
+            return true;
+        }
+
+        let call = self.calls.fetch_add(1, Ordering::Relaxed);
+        if !self.registered.load(Ordering::Relaxed) {
+            self.register();
+        }
+        if !call.is_multiple_of(rate) {
+            return false;
+        }
+        self.sampled.fetch_add(1, Ordering::Relaxed);
+        true
+    }
+
+    #[cold]
+    fn register(&'static self) {
+        if !self.registered.swap(true, Ordering::AcqRel) {
+            if let Ok(mut samplers) = SAMPLERS.lock() {
+                samplers.push(self);
+            }
+        }
+    }
+
+    /// Guard of a sync call, measuring it if it's sampled.
+    #[inline]
+    pub fn guard(&'static self, tag: Option<&str>) -> SampleGuard {
+        if !self.sample() {
+            return SampleGuard::Skipped(SkippedCall::enter(self.name));
+        }
+
+        let guard = MeasurementGuard::new(self.name, false, false);
+        SampleGuard::Measured(match tag {
+            Some(tag) => guard.with_tag(tag),
+            None => guard,
+        })
+    }
+
+    /// Measures a sampled sync call and logs its return value.
+    #[inline]
+    pub fn measure_with_log<T: std::fmt::Debug, F: FnOnce() -> T>(
+        &'static self,
+        tag: Option<&str>,
+        f: F,
+    ) -> T {
+        if !self.sample() {
+            let _skipped = SkippedCall::enter(self.name);
+            return f();
+        }
+
+        let guard = MeasurementGuardWithLog::new(self.name, false, false);
+        let guard = match tag {
+            Some(tag) => guard.with_tag(tag),
+            None => guard,
+        };
+        let result = f();
+        guard.finish_with_result(&result);
+        result
+    }
+
+    /// Measures a sampled async call.
+    pub fn measure_async<F: Future>(
+        &'static self,
+        tag: Option<&str>,
+        inner: F,
+    ) -> MeasuredFuture<F> {
+        let sampled = self.sample();
+        MeasuredFuture {
+            inner,
+            name: self.name,
+            sampled,
+            tag: tag.filter(|_| sampled).map(super::tags::intern),
+            guard: None,
+            skipped: None,
+            log_result: None,
+        }
+    }
+
+    /// Measures a sampled async call and logs its return value.
+    pub fn measure_with_log_async<T: std::fmt::Debug, F, Fut>(
+        &'static self,
+        tag: Option<&str>,
+        f: F,
+    ) -> MeasuredFuture<Fut>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = T>,
+    {
+        let sampled = self.sample();
+        MeasuredFuture {
+            inner: f(),
+            name: self.name,
+            sampled,
+            tag: tag.filter(|_| sampled).map(super::tags::intern),
+            guard: None,
+            skipped: None,
+            log_result: Some(|guard: MeasurementGuardWithLog, result: &T| {
+                guard.finish_with_result(result)
+            }),
+        }
+    }
+}
+
+/// Guard returned by [`Sampler::guard`].
+#[doc(hidden)]
+#[must_use = "guard is dropped immediately without measuring anything"]
+pub enum SampleGuard {
+    Measured(MeasurementGuard),
+    Skipped(SkippedCall),
+}
+
+/// Frame of a call skipped by its sampler.
+///
+/// Measured calls inside it are charged to this frame, which is dropped untimed, so the
+/// caller's self time still covers the whole skipped call.
+#[doc(hidden)]
+pub struct SkippedCall {
+    frame: CallFrame,
+}
+
+impl SkippedCall {
+    #[inline]
+    pub(crate) fn enter(name: &'static str) -> Self {
+        Self {
+            frame: CallFrame::enter(name),
+        }
+    }
+
+    #[inline]
+    pub(crate) fn suspend(&mut self) {
+        self.frame.suspend();
+    }
+
+    #[inline]
+    pub(crate) fn resume(&mut self) {
+        self.frame.resume();
+    }
+}
+
+impl Drop for SkippedCall {
+    #[inline]
+    fn drop(&mut self) {
+        self.frame.exit(0);
+    }
+}
+
+/// Ratio of all calls to the measured calls of a sampled function, the default scale of
+/// functions measuring every call leaves values unchanged.
+#[derive(Clone, Copy, Default)]
+pub(crate) struct Scale {
+    calls: u64,
+    sampled: u64,
+}
+
+impl Scale {
+    /// Scales a count or total of the measured calls up to all calls.
+    #[inline]
+    pub(crate) fn apply(self, value: u64) -> u64 {
+        if self.sampled == 0 {
+            return value;
+        }
+        (value as u128 * self.calls as u128 / self.sampled as u128) as u64
+    }
+
+    /// Scales every count of a histogram, e.g. its cumulative bucket counts.
+    pub(crate) fn apply_all(self, mut values: Vec<u64>) -> Vec<u64> {
+        for value in &mut values {
+            *value = self.apply(*value);
+        }
+        values
+    }
+
+    /// Call count of a function with `measured` completed measured calls. It's exact when
+    /// the stats cover every call since the last reset, else estimated like the totals.
+    #[inline]
+    pub(crate) fn calls(self, measured: u64, exact: bool) -> u64 {
+        if exact && self.sampled > 0 {
+            self.calls
+        } else {
+            self.apply(measured)
+        }
+    }
+}
+
+/// Scales of all sampled functions by name.
+pub(crate) fn scales() -> HashMap<&'static str, Scale> {
+    let Ok(samplers) = SAMPLERS.lock() else {
+        return HashMap::new();
+    };
+
+    let mut scales = HashMap::<&'static str, Scale>::new();
+    for sampler in samplers.iter() {
+        let scale = scales.entry(sampler.name).or_default();
+        scale.calls += sampler.calls.load(Ordering::Relaxed);
+        scale.sampled += sampler.sampled.load(Ordering::Relaxed);
+    }
+    scales
+}
+
+/// Note appended to report descriptions when some functions are sampled.
+pub(crate) fn sampling_note() -> Option<String> {
+    match scales().len() {
+        0 => None,
+        1 => Some("1 sampled function has totals estimated from its measured calls.".to_string()),
+        n => Some(format!(
+            "{n} sampled functions have totals estimated from their measured calls."
+        )),
+    }
+}
+
+/// Restarts the call counts of all sampled functions.
+pub(crate) fn reset() {
+    if let Ok(samplers) = SAMPLERS.lock() {
+        for sampler in samplers.iter() {
+            sampler.calls.store(0, Ordering::Relaxed);
+            sampler.sampled.store(0, Ordering::Relaxed);
+        }
+    }
+}
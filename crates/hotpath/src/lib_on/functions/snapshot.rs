@@ -0,0 +1,99 @@
+//! Owned copy of a report, so periodic reporters can run off the profiler worker.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use crate::output::{
+    sort_value, CallTreeNode, FunctionsJson, MetricType, MetricsProvider, ProfilingMode,
+};
+use crate::SortBy;
+
+cfg_if::cfg_if! {
+    if #[cfg(feature = "hotpath-alloc")] {
+        use super::alloc::{report::StatsData, state::FunctionStats};
+    } else {
+        use super::timing::{report::StatsData, state::FunctionStats};
+    }
+}
+
+pub(crate) struct StatsSnapshot {
+    functions: FunctionsJson,
+    folded_stacks: Vec<(String, u64)>,
+    entry_counts: (usize, usize),
+    has_untracked_allocs: bool,
+    sort_by: SortBy,
+}
+
+impl StatsSnapshot {
+    /// Copy everything a reporter can ask `metrics_provider` for.
+    pub(crate) fn capture(metrics_provider: &dyn MetricsProvider<'_>, sort_by: SortBy) -> Self {
+        Self {
+            functions: FunctionsJson::from(metrics_provider),
+            folded_stacks: metrics_provider.folded_stacks(),
+            entry_counts: metrics_provider.entry_counts(),
+            has_untracked_allocs: metrics_provider.has_unsupported_async(),
+            sort_by,
+        }
+    }
+}
+
+impl<'a> MetricsProvider<'a> for StatsSnapshot {
+    fn description(&self) -> String {
+        self.functions.description.clone()
+    }
+
+    fn profiling_mode(&self) -> ProfilingMode {
+        self.functions.hotpath_profiling_mode
+    }
+
+    fn headers(&self) -> Vec<String> {
+        self.functions.headers()
+    }
+
+    fn percentiles(&self) -> Vec<u8> {
+        self.functions.percentiles.clone()
+    }
+
+    fn metric_data(&self) -> HashMap<String, Vec<MetricType>> {
+        self.functions.data.0.clone()
+    }
+
+    fn sort_key(&self, metrics: &[MetricType]) -> f64 {
+        sort_value(&self.functions.headers, metrics, self.sort_by)
+    }
+
+    fn has_unsupported_async(&self) -> bool {
+        self.has_untracked_allocs
+    }
+
+    fn call_tree(&self) -> Vec<CallTreeNode> {
+        self.functions.call_tree.clone()
+    }
+
+    fn folded_stacks(&self) -> Vec<(String, u64)> {
+        self.folded_stacks.clone()
+    }
+
+    fn entry_counts(&self) -> (usize, usize) {
+        self.entry_counts
+    }
+
+    fn new(
+        stats: &'a HashMap<&'static str, FunctionStats>,
+        total_elapsed: Duration,
+        percentiles: Vec<u8>,
+        caller_name: &'static str,
+        limit: usize,
+    ) -> Self {
+        let stats_data = StatsData::new(stats, total_elapsed, percentiles, caller_name, limit);
+        Self::capture(&stats_data, SortBy::Total)
+    }
+
+    fn total_elapsed(&self) -> u64 {
+        self.functions.total_elapsed
+    }
+
+    fn caller_name(&self) -> &str {
+        &self.functions.caller_name
+    }
+}
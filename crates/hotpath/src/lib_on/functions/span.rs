@@ -0,0 +1,119 @@
+//! Measurements started and finished by hand, across scopes, threads and tasks.
+//!
+//! A [`Span`] is not kept on the call stack of the thread that started it, so it can be
+//! stored in a struct or moved to another thread and finished there. Its caller is the
+//! measured function active at [`span_start`]. Measured calls made while a span is open are
+//! not subtracted from its self time, and its allocations are not tracked.
+//!
+//! The caller's self time isn't reduced by the span either, both cover the same time. Spans
+//! are left out of folded stacks so flamegraphs don't count it twice.
+
+// quanta reads a thread-local clock, which is gone when a span is dropped during
+// thread teardown
+use std::time::Instant;
+
+use super::callgraph::CallSite;
+
+/// Starts measuring `name` until the returned [`Span`] is finished or dropped.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// let span = hotpath::span_start("upload");
+/// std::thread::spawn(move || {
+///     // ...
+///     span.finish();
+/// });
+/// ```
+pub fn span_start(name: &'static str) -> Span {
+    Span {
+        name,
+        start: Instant::now(),
+        tid: crate::tid::current_tid(),
+        tag: None,
+        call_site: super::callgraph::detached_call_site(name),
+        finished: false,
+    }
+}
+
+/// Open measurement started with [`span_start`].
+///
+/// Spans finished on another thread than the one they were started on are recorded as
+/// cross-thread, without a thread id.
+#[must_use = "span is finished immediately when dropped"]
+pub struct Span {
+    name: &'static str,
+    start: Instant,
+    tid: u64,
+    tag: Option<&'static str>,
+    call_site: CallSite,
+    finished: bool,
+}
+
+impl Span {
+    /// Tags the span, its stats are also kept per tag.
+    pub fn with_tag(mut self, tag: &str) -> Self {
+        self.tag = Some(super::tags::intern(tag));
+        self
+    }
+
+    /// Finishes the span and records its duration.
+    pub fn finish(mut self) {
+        self.record();
+    }
+
+    fn record(&mut self) {
+        self.finished = true;
+        let duration = self.start.elapsed();
+        let cross_thread = crate::tid::current_tid() != self.tid;
+        let tid = if cross_thread { None } else { Some(self.tid) };
+
+        cfg_if::cfg_if! {
+            if #[cfg(feature = "hotpath-alloc")] {
+                // The thread-local is gone when a span is dropped during thread teardown
+                let was_enabled = super::alloc::core::ALLOCATIONS
+                    .try_with(|stack| stack.tracking_enabled.replace(false))
+                    .ok();
+                super::alloc::state::send_alloc_measurement(
+                    self.name,
+                    0,
+                    0,
+                    0,
+                    Default::default(),
+                    0,
+                    0,
+                    duration,
+                    0,
+                    self.call_site,
+                    true,
+                    false,
+                    cross_thread,
+                    tid,
+                    self.tag,
+                );
+                if let Some(was_enabled) = was_enabled {
+                    let _ = super::alloc::core::ALLOCATIONS
+                        .try_with(|stack| stack.tracking_enabled.set(was_enabled));
+                }
+            } else {
+                super::timing::state::send_duration_measurement(
+                    self.name,
+                    duration,
+                    0,
+                    self.call_site,
+                    false,
+                    tid,
+                    self.tag,
+                );
+            }
+        }
+    }
+}
+
+impl Drop for Span {
+    fn drop(&mut self) {
+        if !self.finished {
+            self.record();
+        }
+    }
+}
@@ -0,0 +1,41 @@
+//! Tags of measured calls, to break the stats of a function down by endpoint, tenant, etc.
+//!
+//! Tags are interned into `&'static str` so measurements stay cheap to copy. They are meant
+//! to have low cardinality, tags past [`MAX_TAGS`] distinct values are merged into
+//! [`OTHER_TAG`].
+
+use std::collections::HashMap;
+
+use super::intern::Interner;
+
+cfg_if::cfg_if! {
+    if #[cfg(feature = "hotpath-alloc")] {
+        use super::alloc::state::FunctionStats;
+    } else {
+        use super::timing::state::FunctionStats;
+    }
+}
+
+/// Maximum number of distinct tags
+pub(crate) const MAX_TAGS: usize = 64;
+
+/// Tag of calls whose tag did not fit in [`MAX_TAGS`], prefixed so it can't merge with a
+/// real `other` tag
+pub(crate) const OTHER_TAG: &str = "hotpath::other";
+
+static TAGS: Interner = Interner::new(MAX_TAGS, OTHER_TAG);
+
+/// Function stats of tagged calls, by tag and function name.
+pub(crate) type TaggedStats = HashMap<&'static str, HashMap<&'static str, FunctionStats>>;
+
+/// Returns the interned copy of `tag`.
+pub(crate) fn intern(tag: &str) -> &'static str {
+    TAGS.intern(tag)
+}
+
+/// Sorted tags that have stats.
+pub(crate) fn tag_names(stats: &TaggedStats) -> Vec<String> {
+    let mut names: Vec<String> = stats.keys().map(|tag| tag.to_string()).collect();
+    names.sort();
+    names
+}
@@ -4,6 +4,7 @@ use quanta::Instant;
 #[cfg(not(target_os = "linux"))]
 use std::time::Instant;
 
+use super::super::callgraph::CallFrame;
 use super::super::truncate_result;
 
 #[doc(hidden)]
@@ -13,6 +14,8 @@ pub struct MeasurementGuard {
     start: Instant,
     wrapper: bool,
     tid: u64,
+    tag: Option<&'static str>,
+    frame: CallFrame,
 }
 
 impl MeasurementGuard {
@@ -20,20 +23,38 @@ impl MeasurementGuard {
     pub fn new(name: &'static str, wrapper: bool, _unsupported_sync: bool) -> Self {
         Self {
             name,
+            frame: CallFrame::enter(name),
             start: Instant::now(),
             wrapper,
             tid: crate::tid::current_tid(),
+            tag: None,
         }
     }
+
+    /// Tags the call, its stats are also kept per tag.
+    #[inline]
+    pub fn with_tag(mut self, tag: &str) -> Self {
+        self.tag = Some(super::super::tags::intern(tag));
+        self
+    }
 }
 
 impl Drop for MeasurementGuard {
     #[inline]
     fn drop(&mut self) {
         let dur = self.start.elapsed();
+        let child_ns = self.frame.exit(dur.as_nanos() as u64);
         let cross_thread = crate::tid::current_tid() != self.tid;
         let tid = if cross_thread { None } else { Some(self.tid) };
-        super::state::send_duration_measurement(self.name, dur, self.wrapper, tid);
+        super::state::send_duration_measurement(
+            self.name,
+            dur,
+            child_ns,
+            self.frame.call_site(),
+            self.wrapper,
+            tid,
+            self.tag,
+        );
     }
 }
 
@@ -44,6 +65,8 @@ pub struct MeasurementGuardWithLog {
     start: Instant,
     wrapper: bool,
     tid: u64,
+    tag: Option<&'static str>,
+    frame: CallFrame,
     finished: bool,
 }
 
@@ -52,25 +75,48 @@ impl MeasurementGuardWithLog {
     pub fn new(name: &'static str, wrapper: bool, _unsupported_sync: bool) -> Self {
         Self {
             name,
+            frame: CallFrame::enter(name),
             start: Instant::now(),
             wrapper,
             tid: crate::tid::current_tid(),
+            tag: None,
             finished: false,
         }
     }
 
+    /// Tags the call, its stats are also kept per tag.
+    #[inline]
+    pub fn with_tag(mut self, tag: &str) -> Self {
+        self.tag = Some(super::super::tags::intern(tag));
+        self
+    }
+
+    #[inline]
+    pub(crate) fn suspend(&mut self) {
+        self.frame.suspend();
+    }
+
+    #[inline]
+    pub(crate) fn resume(&mut self) {
+        self.frame.resume();
+    }
+
     #[inline]
     pub fn finish_with_result<T: std::fmt::Debug>(mut self, result: &T) {
         self.finished = true;
         let dur = self.start.elapsed();
+        let child_ns = self.frame.exit(dur.as_nanos() as u64);
         let cross_thread = crate::tid::current_tid() != self.tid;
         let tid = if cross_thread { None } else { Some(self.tid) };
         let result_str = truncate_result(format!("{:?}", result));
         super::state::send_duration_measurement_with_log(
             self.name,
             dur,
+            child_ns,
+            self.frame.call_site(),
             self.wrapper,
             tid,
+            self.tag,
             Some(result_str),
         );
     }
@@ -81,13 +127,17 @@ impl Drop for MeasurementGuardWithLog {
     fn drop(&mut self) {
         if !self.finished {
             let dur = self.start.elapsed();
+            let child_ns = self.frame.exit(dur.as_nanos() as u64);
             let cross_thread = crate::tid::current_tid() != self.tid;
             let tid = if cross_thread { None } else { Some(self.tid) };
             super::state::send_duration_measurement_with_log(
                 self.name,
                 dur,
+                child_ns,
+                self.frame.call_site(),
                 self.wrapper,
                 tid,
+                self.tag,
                 None,
             );
         }
@@ -1,10 +1,11 @@
 use std::collections::HashMap;
 use std::time::Duration;
 
-use crate::ProfilingMode;
+use crate::{ProfilingMode, SortBy};
 
 use super::state::FunctionStats;
-use crate::output::{MetricType, MetricsProvider};
+use crate::openmetrics::{FunctionSample, MetricsWriter, DURATION_BUCKETS_NS};
+use crate::output::{sort_value, CallTreeNode, MetricType, MetricsProvider};
 
 pub struct StatsData<'a> {
     pub stats: &'a HashMap<&'static str, FunctionStats>,
@@ -12,6 +13,18 @@ pub struct StatsData<'a> {
     pub percentiles: Vec<u8>,
     pub caller_name: &'static str,
     pub limit: usize,
+    pub sort_by: SortBy,
+    /// The stats cover every call since the last reset, not a window, tag or report interval
+    pub exact_calls: bool,
+}
+
+impl StatsData<'_> {
+    /// Whether the stats cover every call since the last reset, so the call counts of
+    /// sampled functions can be exact instead of estimated.
+    pub(crate) fn with_exact_calls(mut self, exact_calls: bool) -> Self {
+        self.exact_calls = exact_calls;
+        self
+    }
 }
 
 impl<'a> MetricsProvider<'a> for StatsData<'a> {
@@ -28,15 +41,25 @@ impl<'a> MetricsProvider<'a> for StatsData<'a> {
             percentiles,
             caller_name,
             limit,
+            sort_by: SortBy::Total,
+            exact_calls: true,
         }
     }
 
+    fn with_sort_by(mut self, sort_by: SortBy) -> Self {
+        self.sort_by = sort_by;
+        self
+    }
+
     fn percentiles(&self) -> Vec<u8> {
         self.percentiles.clone()
     }
 
     fn description(&self) -> String {
-        "Execution duration of functions.".to_string()
+        match super::super::sampling::sampling_note() {
+            Some(note) => format!("Execution duration of functions. {note}"),
+            None => "Execution duration of functions.".to_string(),
+        }
     }
 
     fn profiling_mode(&self) -> ProfilingMode {
@@ -54,9 +77,17 @@ impl<'a> MetricsProvider<'a> for StatsData<'a> {
 
         let mut entries: Vec<_> = self.stats.iter().filter(|(_, s)| s.has_data).collect();
 
+        let sort_value = |stats: &FunctionStats| match self.sort_by {
+            SortBy::Total => stats.total_duration_ns,
+            SortBy::SelfTime => stats.total_self_ns,
+        };
+        let scales = super::super::sampling::scales();
+        let scale = |name: &str| scales.get(name).copied().unwrap_or_default();
+
         entries.sort_by(|a, b| {
-            b.1.total_duration_ns
-                .cmp(&a.1.total_duration_ns)
+            scale(b.0)
+                .apply(sort_value(b.1))
+                .cmp(&scale(a.0).apply(sort_value(a.1)))
                 .then_with(|| a.0.cmp(b.0))
         });
 
@@ -69,14 +100,16 @@ impl<'a> MetricsProvider<'a> for StatsData<'a> {
         entries
             .into_iter()
             .map(|(function_name, stats)| {
+                let scale = scale(function_name);
+                let total_duration_ns = scale.apply(stats.total_duration_ns);
                 let percentage = if reference_total > 0 {
-                    (stats.total_duration_ns as f64 / reference_total as f64) * 100.0
+                    (total_duration_ns as f64 / reference_total as f64) * 100.0
                 } else {
                     0.0
                 };
 
                 let mut metrics = vec![
-                    MetricType::CallsCount(stats.count),
+                    MetricType::CallsCount(scale.calls(stats.count, self.exact_calls)),
                     MetricType::DurationNs(stats.avg_duration_ns()),
                 ];
 
@@ -85,7 +118,8 @@ impl<'a> MetricsProvider<'a> for StatsData<'a> {
                     metrics.push(MetricType::DurationNs(value.as_nanos() as u64));
                 }
 
-                metrics.push(MetricType::DurationNs(stats.total_duration_ns));
+                metrics.push(MetricType::DurationNs(total_duration_ns));
+                metrics.push(MetricType::DurationNs(scale.apply(stats.total_self_ns)));
                 metrics.push(MetricType::Percentage((percentage * 100.0) as u64));
 
                 (function_name.to_string(), metrics)
@@ -93,6 +127,18 @@ impl<'a> MetricsProvider<'a> for StatsData<'a> {
             .collect()
     }
 
+    fn sort_key(&self, metrics: &[MetricType]) -> f64 {
+        sort_value(&self.headers(), metrics, self.sort_by)
+    }
+
+    fn call_tree(&self) -> Vec<CallTreeNode> {
+        super::super::callgraph::build_call_tree(self.stats.values().map(|s| &s.stacks))
+    }
+
+    fn folded_stacks(&self) -> Vec<(String, u64)> {
+        super::super::callgraph::build_folded_stacks(self.stats.values().map(|s| &s.stacks))
+    }
+
     fn total_elapsed(&self) -> u64 {
         self.total_elapsed.as_nanos() as u64
     }
@@ -113,3 +159,27 @@ impl<'a> MetricsProvider<'a> for StatsData<'a> {
         (displayed_count, total_count)
     }
 }
+
+pub(crate) fn write_openmetrics(
+    stats: &HashMap<&'static str, FunctionStats>,
+    w: &mut MetricsWriter,
+) {
+    let scales = super::super::sampling::scales();
+    let mut functions: Vec<FunctionSample<'_>> = stats
+        .iter()
+        .filter(|(_, s)| s.has_data)
+        .map(|(name, s)| {
+            let scale = scales.get(name).copied().unwrap_or_default();
+            FunctionSample {
+                name,
+                calls: scale.calls(s.count, true),
+                total_ns: scale.apply(s.total_duration_ns),
+                self_ns: scale.apply(s.total_self_ns),
+                duration_buckets: scale.apply_all(s.duration_buckets(&DURATION_BUCKETS_NS)),
+            }
+        })
+        .collect();
+    functions.sort_by(|a, b| a.name.cmp(b.name));
+
+    crate::openmetrics::write_functions(w, &functions);
+}
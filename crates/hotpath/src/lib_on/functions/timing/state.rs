@@ -5,6 +5,8 @@ use std::collections::{HashMap, VecDeque};
 use std::sync::Mutex;
 use std::time::{Duration, Instant};
 
+use super::super::callgraph::{merge_stacks, CallSite, StacksMap};
+
 const BATCH_SIZE: usize = 64;
 const FLUSH_INTERVAL_MS: u64 = 50;
 
@@ -25,12 +27,16 @@ impl MeasurementBatch {
         }
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn add(
         &mut self,
         name: &'static str,
         duration: Duration,
+        child_ns: u64,
+        call_site: CallSite,
         wrapper: bool,
         tid: Option<u64>,
+        tag: Option<&'static str>,
         result_log: Option<String>,
     ) {
         if self.sender.is_none() {
@@ -39,6 +45,7 @@ impl MeasurementBatch {
                     if let Ok(state_guard) = state.read() {
                         self.sender = state_guard.sender.clone();
                         self.start_time = Some(state_guard.start_time);
+                        crate::trace::note_current_thread();
                     }
                 }
             }
@@ -48,12 +55,16 @@ impl MeasurementBatch {
             return;
         };
 
+        let duration_ns = duration.as_nanos() as u64;
         let measurement = Measurement {
-            duration_ns: duration.as_nanos() as u64,
+            duration_ns,
+            self_duration_ns: duration_ns.saturating_sub(child_ns),
+            call_site,
             measurement_time: Instant::now(),
             name,
             wrapper,
             tid,
+            tag,
             result_log,
         };
 
@@ -97,32 +108,54 @@ pub(crate) fn flush_batch() {
     });
 }
 
+#[derive(Clone)]
 pub struct Measurement {
     pub duration_ns: u64,
+    pub self_duration_ns: u64,
+    pub call_site: CallSite,
     pub measurement_time: Instant,
     pub name: &'static str,
     pub wrapper: bool,
     pub tid: Option<u64>,
+    /// Tag of the call, from `#[hotpath::measure(tag = ..)]`
+    pub tag: Option<&'static str>,
     pub result_log: Option<String>,
 }
 
+impl Measurement {
+    /// When the measured call started.
+    pub(crate) fn started_at(&self) -> Instant {
+        self.measurement_time
+            .checked_sub(Duration::from_nanos(self.duration_ns))
+            .unwrap_or(self.measurement_time)
+    }
+}
+
 #[derive(Debug)]
 pub struct FunctionStats {
     pub total_duration_ns: u64,
+    pub total_self_ns: u64,
     pub count: u64,
     hist: Option<Histogram<u64>>,
     pub has_data: bool,
     pub wrapper: bool,
     pub recent_logs: VecDeque<(u64, Duration, Option<u64>, Option<String>)>, // (duration_ns, elapsed, tid, result_log)
+    pub stacks: StacksMap,
 }
 
 impl FunctionStats {
     const LOW_NS: u64 = 1;
     const HIGH_NS: u64 = 1_000_000_000_000; // 1000s
     const SIGFIGS: u8 = 3;
+    /// Rolling window slots exist per function every few seconds, so they use
+    /// smaller auto-resizing histograms.
+    const WINDOW_SIGFIGS: u8 = 2;
 
+    #[allow(clippy::too_many_arguments)]
     pub fn new_duration(
         first_ns: u64,
+        self_ns: u64,
+        call_site: CallSite,
         elapsed: Duration,
         wrapper: bool,
         recent_logs_limit: usize,
@@ -135,13 +168,21 @@ impl FunctionStats {
         let mut recent_logs = VecDeque::with_capacity(recent_logs_limit);
         recent_logs.push_back((first_ns, elapsed, tid, result_log));
 
+        let mut stacks = StacksMap::new();
+        stacks
+            .entry(call_site.stack)
+            .or_default()
+            .record(first_ns, self_ns);
+
         let mut s = Self {
             total_duration_ns: first_ns,
+            total_self_ns: self_ns,
             count: 1,
             hist: Some(hist),
             has_data: true,
             wrapper,
             recent_logs,
+            stacks,
         };
         s.record_time(first_ns);
         s
@@ -158,13 +199,20 @@ impl FunctionStats {
     pub fn update_duration(
         &mut self,
         duration_ns: u64,
+        self_ns: u64,
+        call_site: CallSite,
         elapsed: Duration,
         tid: Option<u64>,
         result_log: Option<String>,
     ) {
         self.total_duration_ns += duration_ns;
+        self.total_self_ns += self_ns;
         self.count += 1;
         self.record_time(duration_ns);
+        self.stacks
+            .entry(call_site.stack)
+            .or_default()
+            .record(duration_ns, self_ns);
 
         if self.recent_logs.len() == self.recent_logs.capacity() && self.recent_logs.capacity() > 0
         {
@@ -174,15 +222,61 @@ impl FunctionStats {
             .push_back((duration_ns, elapsed, tid, result_log));
     }
 
-    pub fn avg_duration_ns(&self) -> u64 {
-        if self.count == 0 {
-            0
-        } else {
-            self.total_duration_ns / self.count
+    /// Empty stats of a rolling window slot, without recent logs.
+    pub(crate) fn new_window(wrapper: bool) -> Self {
+        Self {
+            total_duration_ns: 0,
+            total_self_ns: 0,
+            count: 0,
+            hist: Some(Histogram::<u64>::new(Self::WINDOW_SIGFIGS).expect("hdrhistogram init")),
+            has_data: false,
+            wrapper,
+            recent_logs: VecDeque::new(),
+            stacks: StacksMap::new(),
+        }
+    }
+
+    pub(crate) fn record_window(&mut self, m: &Measurement) {
+        self.total_duration_ns += m.duration_ns;
+        self.total_self_ns += m.self_duration_ns;
+        self.count += 1;
+        self.has_data = true;
+        self.record_time(m.duration_ns);
+        self.stacks
+            .entry(m.call_site.stack)
+            .or_default()
+            .record(m.duration_ns, m.self_duration_ns);
+    }
+
+    /// Adds the calls of another rolling window slot.
+    pub(crate) fn merge(&mut self, other: &Self) {
+        self.total_duration_ns += other.total_duration_ns;
+        self.total_self_ns += other.total_self_ns;
+        self.count += other.count;
+        self.has_data |= other.has_data;
+        self.wrapper |= other.wrapper;
+        if let (Some(hist), Some(other_hist)) = (self.hist.as_mut(), other.hist.as_ref()) {
+            let _ = hist.add(other_hist);
         }
+        merge_stacks(&mut self.stacks, &other.stacks);
+    }
+
+    pub fn avg_duration_ns(&self) -> u64 {
+        self.total_duration_ns.checked_div(self.count).unwrap_or(0)
     }
 
     #[inline]
+    /// Cumulative number of calls that took at most each of `bounds_ns`.
+    pub fn duration_buckets(&self, bounds_ns: &[u64]) -> Vec<u64> {
+        bounds_ns
+            .iter()
+            .map(|bound| match &self.hist {
+                Some(hist) => hist.count_between(Self::LOW_NS, (*bound).min(Self::HIGH_NS)),
+                None => 0,
+            })
+            .collect()
+    }
+
     pub fn percentile(&self, p: f64) -> Duration {
         if self.count == 0 || self.hist.is_none() {
             return Duration::ZERO;
@@ -193,15 +287,35 @@ impl FunctionStats {
     }
 }
 
+/// Stats sent by the worker when it shuts down, with the time they were last reset.
+pub(crate) type FinalStats = (HashMap<&'static str, FunctionStats>, Instant);
+
 pub(crate) struct FunctionsState {
     pub sender: Option<Sender<Measurement>>,
     pub shutdown_tx: Option<Sender<()>>,
-    pub completion_rx: Option<Mutex<Receiver<HashMap<&'static str, FunctionStats>>>>,
+    pub completion_rx: Option<Mutex<Receiver<FinalStats>>>,
     pub query_tx: Option<Sender<FunctionsQuery>>,
     pub start_time: Instant,
     pub caller_name: &'static str,
     pub percentiles: Vec<u8>,
     pub limit: usize,
+    pub sort_by: crate::SortBy,
+}
+
+/// Record a measurement on the trace timeline if `HOTPATH_TRACE_FILE` is set.
+pub(crate) fn trace_measurement(m: &Measurement) {
+    if !crate::trace::is_enabled() {
+        return;
+    }
+
+    crate::trace::record_span(
+        m.name,
+        "function",
+        m.tid,
+        m.measurement_time,
+        m.duration_ns,
+        None,
+    );
 }
 
 pub(crate) fn process_measurement(
@@ -211,13 +325,23 @@ pub(crate) fn process_measurement(
     start_time: Instant,
 ) {
     let elapsed = m.measurement_time.duration_since(start_time);
+
     if let Some(s) = stats.get_mut(m.name) {
-        s.update_duration(m.duration_ns, elapsed, m.tid, m.result_log);
+        s.update_duration(
+            m.duration_ns,
+            m.self_duration_ns,
+            m.call_site,
+            elapsed,
+            m.tid,
+            m.result_log,
+        );
     } else {
         stats.insert(
             m.name,
             FunctionStats::new_duration(
                 m.duration_ns,
+                m.self_duration_ns,
+                m.call_site,
                 elapsed,
                 m.wrapper,
                 recent_logs_limit,
@@ -234,17 +358,26 @@ use super::super::FUNCTIONS_STATE;
 pub fn send_duration_measurement(
     name: &'static str,
     duration: Duration,
+    child_ns: u64,
+    call_site: CallSite,
     wrapper: bool,
     tid: Option<u64>,
+    tag: Option<&'static str>,
 ) {
-    send_duration_measurement_with_log(name, duration, wrapper, tid, None);
+    send_duration_measurement_with_log(
+        name, duration, child_ns, call_site, wrapper, tid, tag, None,
+    );
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn send_duration_measurement_with_log(
     name: &'static str,
     duration: Duration,
+    child_ns: u64,
+    call_site: CallSite,
     wrapper: bool,
     tid: Option<u64>,
+    tag: Option<&'static str>,
     result_log: Option<String>,
 ) {
     if FUNCTIONS_STATE.get().is_none() {
@@ -253,9 +386,11 @@ pub fn send_duration_measurement_with_log(
         );
     }
 
-    MEASUREMENT_BATCH.with(|batch| {
-        batch
-            .borrow_mut()
-            .add(name, duration, wrapper, tid, result_log);
+    // The batch is gone when a span is dropped during thread teardown, the
+    // measurement is dropped with it.
+    let _ = MEASUREMENT_BATCH.try_with(|batch| {
+        batch.borrow_mut().add(
+            name, duration, child_ns, call_site, wrapper, tid, tag, result_log,
+        );
     });
 }
@@ -0,0 +1,127 @@
+//! Rolling window statistics - recent function stats next to the since-start aggregates.
+//!
+//! The worker thread keeps the last few minutes of measurements split into fixed time
+//! slots. A window query merges the slots it covers, so a regression hours into a run
+//! shows up in the percentiles instead of being averaged away.
+
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
+
+use super::tags::TaggedStats;
+
+cfg_if::cfg_if! {
+    if #[cfg(feature = "hotpath-alloc")] {
+        use super::alloc::state::{FunctionStats, Measurement};
+    } else {
+        use super::timing::state::{FunctionStats, Measurement};
+    }
+}
+
+/// Time span of a single slot, windows are rounded up to whole slots.
+pub(crate) const SLOT: Duration = Duration::from_secs(5);
+
+/// Longest window that can be queried.
+pub(crate) const MAX_WINDOW: Duration = Duration::from_secs(300);
+
+/// Slots covering the longest window plus the one still being filled.
+const SLOT_COUNT: u64 = MAX_WINDOW.as_secs() / SLOT.as_secs() + 1;
+
+struct Slot {
+    index: u64,
+    stats: HashMap<&'static str, FunctionStats>,
+    tagged: TaggedStats,
+}
+
+#[derive(Default)]
+pub(crate) struct RollingStats {
+    slots: VecDeque<Slot>,
+}
+
+impl RollingStats {
+    pub(crate) fn record(&mut self, m: &Measurement, start_time: Instant) {
+        let index = slot_index(m.measurement_time.saturating_duration_since(start_time));
+        let newest = self
+            .slots
+            .back()
+            .map_or(index, |slot| slot.index.max(index));
+        if index + SLOT_COUNT <= newest {
+            return;
+        }
+
+        // Batches from different threads arrive out of order, so a slot may be inserted
+        // before the newest one.
+        let pos = match self.slots.binary_search_by_key(&index, |slot| slot.index) {
+            Ok(pos) => pos,
+            Err(pos) => {
+                self.slots.insert(
+                    pos,
+                    Slot {
+                        index,
+                        stats: HashMap::new(),
+                        tagged: TaggedStats::new(),
+                    },
+                );
+                pos
+            }
+        };
+        let slot = &mut self.slots[pos];
+        slot.stats
+            .entry(m.name)
+            .or_insert_with(|| FunctionStats::new_window(m.wrapper))
+            .record_window(m);
+        if let Some(tag) = m.tag {
+            slot.tagged
+                .entry(tag)
+                .or_default()
+                .entry(m.name)
+                .or_insert_with(|| FunctionStats::new_window(m.wrapper))
+                .record_window(m);
+        }
+
+        while self
+            .slots
+            .front()
+            .is_some_and(|slot| slot.index + SLOT_COUNT <= newest)
+        {
+            self.slots.pop_front();
+        }
+    }
+
+    /// Merged stats of the calls completed during the last `window`, only of the calls
+    /// tagged with `tag` if set, and the time span they actually cover after rounding up
+    /// to whole slots.
+    pub(crate) fn window(
+        &self,
+        window: Duration,
+        elapsed: Duration,
+        tag: Option<&str>,
+    ) -> (HashMap<&'static str, FunctionStats>, Duration) {
+        let window = window.min(MAX_WINDOW);
+        let slots = window.as_nanos().div_ceil(SLOT.as_nanos()) as u64;
+        let first = slot_index(elapsed).saturating_sub(slots);
+
+        let mut stats = HashMap::<&'static str, FunctionStats>::new();
+        for slot in self.slots.iter().filter(|slot| slot.index >= first) {
+            let slot_stats = match tag {
+                Some(tag) => match slot.tagged.get(tag) {
+                    Some(tagged) => tagged,
+                    None => continue,
+                },
+                None => &slot.stats,
+            };
+            for (name, slot_stats) in slot_stats {
+                stats
+                    .entry(*name)
+                    .or_insert_with(|| FunctionStats::new_window(slot_stats.wrapper))
+                    .merge(slot_stats);
+            }
+        }
+
+        let covered = elapsed.saturating_sub(SLOT * first as u32);
+        (stats, covered)
+    }
+}
+
+fn slot_index(elapsed: Duration) -> u64 {
+    (elapsed.as_nanos() / SLOT.as_nanos()) as u64
+}
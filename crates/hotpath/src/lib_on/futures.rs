@@ -1,8 +1,8 @@
 //! Futures instrumentation module - tracks async Future lifecycle and poll statistics.
 
 use crate::channels::{get_log_limit, resolve_label, START_TIME};
-use crate::http_server::HTTP_SERVER_PORT;
-use crossbeam_channel::{unbounded, Sender as CbSender};
+use crate::http_server::{HTTP_SERVER_PORT, RECV_TIMEOUT_MS};
+use crossbeam_channel::{bounded, unbounded, Sender as CbSender};
 use std::collections::{HashMap, VecDeque};
 use std::sync::atomic::AtomicU64;
 use std::sync::{Arc, OnceLock, RwLock};
@@ -80,6 +80,12 @@ impl FutureStats {
         self.calls.iter().map(|c| c.poll_count).sum()
     }
 
+    /// Drop recorded calls, the future stays registered with its label.
+    fn reset(&mut self) {
+        self.calls.clear();
+        self.call_count = 0;
+    }
+
     /// Find a call by ID
     fn find_call_mut(&mut self, id: u64) -> Option<&mut FutureCall> {
         self.calls.iter_mut().find(|c| c.id == id)
@@ -134,6 +140,9 @@ pub(crate) enum FutureEvent {
         future_id: u64,
         call_id: u64,
     },
+    Reset {
+        done_tx: CbSender<()>,
+    },
 }
 
 /// State type: event sender + shared stats map
@@ -149,6 +158,7 @@ static FUTURES_STATE: OnceLock<FuturesStatsState> = OnceLock::new();
 pub fn init_futures_state() {
     FUTURES_STATE.get_or_init(|| {
         START_TIME.get_or_init(Instant::now);
+        crate::trace::init_trace();
 
         crate::http_server::start_metrics_server_once(*HTTP_SERVER_PORT);
 
@@ -234,6 +244,10 @@ fn process_future_event(stats_map: &mut HashMap<u64, FutureStats>, event: Future
                 }
             }
         }
+        FutureEvent::Reset { done_tx } => {
+            stats_map.values_mut().for_each(FutureStats::reset);
+            let _ = done_tx.send(());
+        }
     }
 }
 
@@ -368,3 +382,13 @@ macro_rules! future {
         $crate::InstrumentFutureLog::instrument_future_log($fut, FUTURE_LOC)
     }};
 }
+
+/// Drop the recorded calls of all instrumented futures, events sent before the reset are applied first.
+pub(crate) fn reset_futures() {
+    if let Some((tx, _)) = FUTURES_STATE.get() {
+        let (done_tx, done_rx) = bounded::<()>(1);
+        if tx.send(FutureEvent::Reset { done_tx }).is_ok() {
+            let _ = done_rx.recv_timeout(std::time::Duration::from_millis(RECV_TIMEOUT_MS));
+        }
+    }
+}
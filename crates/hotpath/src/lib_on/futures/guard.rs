@@ -124,6 +124,8 @@ impl Default for FuturesGuard {
 
 impl Drop for FuturesGuard {
     fn drop(&mut self) {
+        crate::trace::flush_trace();
+
         let elapsed = self.start_time.elapsed();
         let futures_json = get_futures_json();
 
@@ -133,7 +135,7 @@ impl Drop for FuturesGuard {
         }
 
         match self.format {
-            Format::Table => {
+            Format::Table | Format::Folded => {
                 println!(
                     "\n=== Future Statistics (runtime: {:.2}s) ===",
                     elapsed.as_secs_f64()
@@ -1,5 +1,6 @@
 //! Instrumented Future wrapper that tracks lifecycle events.
 
+use crate::channels::resolve_label;
 use crate::functions::truncate_result;
 
 use super::{
@@ -51,6 +52,26 @@ fn create_instrumented_waker(waker: &Waker) -> Waker {
     unsafe { Waker::from_raw(raw) }
 }
 
+/// `span_label` caches the resolved label, so it's only built on the first traced poll.
+fn record_poll_span(
+    span_label: &mut Option<String>,
+    source: &'static str,
+    poll_start: std::time::Instant,
+    ready: bool,
+) {
+    let poll_end = std::time::Instant::now();
+    let label = span_label.get_or_insert_with(|| resolve_label(source, None, None));
+    crate::trace::note_current_thread();
+    crate::trace::record_span(
+        label,
+        "future",
+        Some(crate::tid::current_tid()),
+        poll_end,
+        poll_end.duration_since(poll_start).as_nanos() as u64,
+        Some(serde_json::json!({ "result": if ready { "ready" } else { "pending" } })),
+    );
+}
+
 pin_project! {
     /// A wrapper around a future that tracks lifecycle events.
     ///
@@ -66,6 +87,8 @@ pin_project! {
         inner: F,
         future_id: u64,
         call_id: u64,
+        source: &'static str,
+        span_label: Option<String>,
         completed: bool,
     }
 
@@ -98,6 +121,8 @@ impl<F: Future> InstrumentedFuture<F> {
             inner,
             future_id,
             call_id,
+            source: location,
+            span_label: None,
             completed: false,
         }
     }
@@ -114,7 +139,11 @@ impl<F: Future> Future for InstrumentedFuture<F> {
         let instrumented_waker = create_instrumented_waker(cx.waker());
         let mut instrumented_cx = Context::from_waker(&instrumented_waker);
 
+        let poll_start = crate::trace::is_enabled().then(std::time::Instant::now);
         let result = this.inner.poll(&mut instrumented_cx);
+        if let Some(poll_start) = poll_start {
+            record_poll_span(this.span_label, this.source, poll_start, result.is_ready());
+        }
 
         let poll_result = match &result {
             Poll::Pending => PollResult::Pending,
@@ -153,6 +182,8 @@ pin_project! {
         inner: F,
         future_id: u64,
         call_id: u64,
+        source: &'static str,
+        span_label: Option<String>,
         completed: bool,
     }
 
@@ -185,6 +216,8 @@ impl<F: Future> InstrumentedFutureLog<F> {
             inner,
             future_id,
             call_id,
+            source: location,
+            span_label: None,
             completed: false,
         }
     }
@@ -204,7 +237,11 @@ where
         let instrumented_waker = create_instrumented_waker(cx.waker());
         let mut instrumented_cx = Context::from_waker(&instrumented_waker);
 
+        let poll_start = crate::trace::is_enabled().then(std::time::Instant::now);
         let result = this.inner.poll(&mut instrumented_cx);
+        if let Some(poll_start) = poll_start {
+            record_poll_span(this.span_label, this.source, poll_start, result.is_ready());
+        }
 
         let (poll_result, log_message) = match &result {
             Poll::Pending => (PollResult::Pending, None),
@@ -0,0 +1,368 @@
+//! OpenMetrics text exposition served on the `/metrics` route for Prometheus scrapers.
+
+use std::fmt::{Display, Write};
+
+use crate::channels::get_sorted_channel_stats;
+use crate::json::{SerializableChannelStats, SerializableStreamStats};
+use crate::streams::get_sorted_stream_stats;
+
+pub(crate) const CONTENT_TYPE: &str = "application/openmetrics-text; version=1.0.0; charset=utf-8";
+
+/// Upper bounds of the function duration histogram buckets, in nanoseconds.
+pub(crate) const DURATION_BUCKETS_NS: [u64; 9] = [
+    1_000,
+    10_000,
+    100_000,
+    1_000_000,
+    10_000_000,
+    100_000_000,
+    1_000_000_000,
+    10_000_000_000,
+    100_000_000_000,
+];
+
+/// Accumulates metric families in OpenMetrics text format.
+#[derive(Default)]
+pub(crate) struct MetricsWriter {
+    out: String,
+}
+
+impl MetricsWriter {
+    pub(crate) fn family(&mut self, name: &str, kind: &str, unit: Option<&str>, help: &str) {
+        let _ = writeln!(self.out, "# TYPE {} {}", name, kind);
+        if let Some(unit) = unit {
+            let _ = writeln!(self.out, "# UNIT {} {}", name, unit);
+        }
+        let _ = writeln!(self.out, "# HELP {} {}", name, help);
+    }
+
+    pub(crate) fn sample(&mut self, name: &str, labels: &[(&str, &str)], value: impl Display) {
+        self.out.push_str(name);
+        if !labels.is_empty() {
+            self.out.push('{');
+            for (i, (key, value)) in labels.iter().enumerate() {
+                if i > 0 {
+                    self.out.push(',');
+                }
+                let _ = write!(self.out, "{}=\"{}\"", key, escape_label(value));
+            }
+            self.out.push('}');
+        }
+        let _ = writeln!(self.out, " {}", value);
+    }
+
+    /// Write the samples of one histogram series from cumulative bucket counts.
+    pub(crate) fn duration_histogram(
+        &mut self,
+        name: &str,
+        labels: &[(&str, &str)],
+        cumulative_counts: &[u64],
+        count: u64,
+        sum_ns: u64,
+    ) {
+        let bucket = format!("{}_bucket", name);
+        for (bound_ns, bucket_count) in DURATION_BUCKETS_NS.iter().zip(cumulative_counts) {
+            let le = seconds(*bound_ns).to_string();
+            let mut bucket_labels = labels.to_vec();
+            bucket_labels.push(("le", &le));
+            self.sample(&bucket, &bucket_labels, bucket_count);
+        }
+        let mut inf_labels = labels.to_vec();
+        inf_labels.push(("le", "+Inf"));
+        self.sample(&bucket, &inf_labels, count);
+        self.sample(&format!("{}_count", name), labels, count);
+        self.sample(&format!("{}_sum", name), labels, seconds(sum_ns));
+    }
+
+    pub(crate) fn push_str(&mut self, text: &str) {
+        self.out.push_str(text);
+    }
+
+    pub(crate) fn into_string(self) -> String {
+        self.out
+    }
+}
+
+pub(crate) fn seconds(ns: u64) -> f64 {
+    ns as f64 / 1_000_000_000.0
+}
+
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// Call count and timing of one measured function.
+pub(crate) struct FunctionSample<'a> {
+    pub(crate) name: &'a str,
+    pub(crate) calls: u64,
+    pub(crate) total_ns: u64,
+    pub(crate) self_ns: u64,
+    pub(crate) duration_buckets: Vec<u64>,
+}
+
+pub(crate) fn write_functions(w: &mut MetricsWriter, functions: &[FunctionSample<'_>]) {
+    if functions.is_empty() {
+        return;
+    }
+
+    w.family(
+        "hotpath_function_calls",
+        "counter",
+        None,
+        "Completed calls of a measured function.",
+    );
+    for f in functions {
+        w.sample(
+            "hotpath_function_calls_total",
+            &[("function", f.name)],
+            f.calls,
+        );
+    }
+
+    w.family(
+        "hotpath_function_duration_seconds",
+        "histogram",
+        Some("seconds"),
+        "Duration of calls of a measured function.",
+    );
+    for f in functions {
+        w.duration_histogram(
+            "hotpath_function_duration_seconds",
+            &[("function", f.name)],
+            &f.duration_buckets,
+            f.calls,
+            f.total_ns,
+        );
+    }
+
+    w.family(
+        "hotpath_function_self_seconds",
+        "counter",
+        Some("seconds"),
+        "Time spent in a measured function excluding measured callees.",
+    );
+    for f in functions {
+        w.sample(
+            "hotpath_function_self_seconds_total",
+            &[("function", f.name)],
+            seconds(f.self_ns),
+        );
+    }
+}
+
+/// Render every available metric family, terminated by `# EOF`.
+pub(crate) fn render() -> String {
+    let mut w = MetricsWriter::default();
+
+    w.push_str(&crate::functions::get_functions_openmetrics());
+    write_channels(&mut w);
+    write_streams(&mut w);
+    #[cfg(feature = "threads")]
+    write_threads(&mut w);
+    #[cfg(feature = "tokio")]
+    write_runtime(&mut w);
+
+    w.push_str("# EOF\n");
+    w.into_string()
+}
+
+fn write_channels(w: &mut MetricsWriter) {
+    let channels: Vec<SerializableChannelStats> = get_sorted_channel_stats()
+        .iter()
+        .map(SerializableChannelStats::from)
+        .collect();
+    if channels.is_empty() {
+        return;
+    }
+
+    w.family(
+        "hotpath_channel_sent",
+        "counter",
+        None,
+        "Messages sent to an instrumented channel.",
+    );
+    for c in &channels {
+        w.sample(
+            "hotpath_channel_sent_total",
+            &[("channel", &c.label)],
+            c.sent_count,
+        );
+    }
+
+    w.family(
+        "hotpath_channel_received",
+        "counter",
+        None,
+        "Messages received from an instrumented channel.",
+    );
+    for c in &channels {
+        w.sample(
+            "hotpath_channel_received_total",
+            &[("channel", &c.label)],
+            c.received_count,
+        );
+    }
+
+    w.family(
+        "hotpath_channel_queued",
+        "gauge",
+        None,
+        "Messages currently queued in an instrumented channel.",
+    );
+    for c in &channels {
+        w.sample("hotpath_channel_queued", &[("channel", &c.label)], c.queued);
+    }
+
+    w.family(
+        "hotpath_channel_queued_bytes",
+        "gauge",
+        Some("bytes"),
+        "Size of the messages currently queued in an instrumented channel.",
+    );
+    for c in &channels {
+        w.sample(
+            "hotpath_channel_queued_bytes",
+            &[("channel", &c.label)],
+            c.queued_bytes,
+        );
+    }
+
+    if channels.iter().all(|c| c.subscribers.is_empty()) {
+        return;
+    }
+
+    w.family(
+        "hotpath_channel_lagged",
+        "counter",
+        None,
+        "Messages a broadcast or watch channel receiver never saw.",
+    );
+    for c in &channels {
+        for sub in &c.subscribers {
+            w.sample(
+                "hotpath_channel_lagged_total",
+                &[("channel", &c.label), ("subscriber", &sub.label)],
+                sub.lagged_count,
+            );
+        }
+    }
+}
+
+fn write_streams(w: &mut MetricsWriter) {
+    let streams: Vec<SerializableStreamStats> = get_sorted_stream_stats()
+        .iter()
+        .map(SerializableStreamStats::from)
+        .collect();
+    if streams.is_empty() {
+        return;
+    }
+
+    w.family(
+        "hotpath_stream_yielded",
+        "counter",
+        None,
+        "Items yielded by an instrumented stream.",
+    );
+    for s in &streams {
+        w.sample(
+            "hotpath_stream_yielded_total",
+            &[("stream", &s.label)],
+            s.items_yielded,
+        );
+    }
+}
+
+#[cfg(feature = "threads")]
+fn write_threads(w: &mut MetricsWriter) {
+    let threads = crate::threads::get_threads_json();
+
+    if !threads.threads.is_empty() {
+        w.family(
+            "hotpath_thread_cpu_seconds",
+            "counter",
+            Some("seconds"),
+            "CPU time spent by a thread in user and system mode.",
+        );
+        for t in &threads.threads {
+            let tid = t.os_tid.to_string();
+            w.sample(
+                "hotpath_thread_cpu_seconds_total",
+                &[("thread", &t.name), ("tid", &tid), ("mode", "user")],
+                t.cpu_user,
+            );
+            w.sample(
+                "hotpath_thread_cpu_seconds_total",
+                &[("thread", &t.name), ("tid", &tid), ("mode", "system")],
+                t.cpu_sys,
+            );
+        }
+    }
+
+    if let Some(rss_bytes) = threads.rss_bytes {
+        w.family(
+            "hotpath_process_resident_memory_bytes",
+            "gauge",
+            Some("bytes"),
+            "Resident set size of the process.",
+        );
+        w.sample("hotpath_process_resident_memory_bytes", &[], rss_bytes);
+    }
+}
+
+#[cfg(feature = "tokio")]
+fn write_runtime(w: &mut MetricsWriter) {
+    let Some(runtime) = crate::runtime::get_runtime_json() else {
+        return;
+    };
+
+    w.family(
+        "hotpath_runtime_alive_tasks",
+        "gauge",
+        None,
+        "Tasks spawned on the Tokio runtime and not yet completed.",
+    );
+    w.sample("hotpath_runtime_alive_tasks", &[], runtime.num_alive_tasks);
+
+    w.family(
+        "hotpath_runtime_global_queue_depth",
+        "gauge",
+        None,
+        "Tasks in the global queue of the Tokio runtime.",
+    );
+    w.sample(
+        "hotpath_runtime_global_queue_depth",
+        &[],
+        runtime.global_queue_depth,
+    );
+
+    w.family(
+        "hotpath_runtime_worker_busy_seconds",
+        "counter",
+        Some("seconds"),
+        "Time a Tokio runtime worker spent polling tasks.",
+    );
+    for worker in &runtime.workers {
+        w.sample(
+            "hotpath_runtime_worker_busy_seconds_total",
+            &[("worker", &worker.index.to_string())],
+            worker.busy_total,
+        );
+    }
+
+    w.family(
+        "hotpath_runtime_worker_parks",
+        "counter",
+        None,
+        "Times a Tokio runtime worker parked.",
+    );
+    for worker in &runtime.workers {
+        w.sample(
+            "hotpath_runtime_worker_parks_total",
+            &[("worker", &worker.index.to_string())],
+            worker.park_count,
+        );
+    }
+}
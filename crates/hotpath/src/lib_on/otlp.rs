@@ -0,0 +1,352 @@
+//! OTLP exporter - pushes function and channel stats to an OpenTelemetry collector.
+
+use std::collections::BTreeMap;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde_json::{json, Value};
+
+use crate::channels::get_sorted_channel_stats;
+use crate::json::SerializableChannelStats;
+use crate::output::{MetricType, MetricsProvider, Reporter};
+
+const DEFAULT_INTERVAL: Duration = Duration::from_secs(60);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// OTLP `AggregationTemporality::CUMULATIVE`
+const CUMULATIVE: u8 = 2;
+
+/// Reporter that exports profiling stats as OTLP/HTTP JSON metrics.
+///
+/// Function stats are exported with a `function` attribute, channel stats with a
+/// `channel` attribute. Use it with [`FunctionsGuardBuilder::otlp`](crate::FunctionsGuardBuilder::otlp)
+/// to push a snapshot every [`interval`](OtlpReporter::interval) and once more when the
+/// guard is dropped.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use std::time::Duration;
+/// use hotpath::{FunctionsGuardBuilder, OtlpReporter};
+///
+/// let _guard = FunctionsGuardBuilder::new("server")
+///     .otlp(
+///         OtlpReporter::new("http://localhost:4318")
+///             .service_name("checkout")
+///             .interval(Duration::from_secs(10)),
+///     )
+///     .build();
+/// ```
+pub struct OtlpReporter {
+    url: String,
+    service_name: String,
+    headers: Vec<(String, String)>,
+    interval: Duration,
+    agent: ureq::Agent,
+}
+
+impl OtlpReporter {
+    /// Creates a reporter for the collector at `endpoint`, e.g. `http://localhost:4318`.
+    ///
+    /// `/v1/metrics` is appended unless the endpoint already ends with it.
+    pub fn new(endpoint: impl Into<String>) -> Self {
+        let endpoint = endpoint.into();
+        let endpoint = endpoint.trim_end_matches('/');
+        let url = if endpoint.ends_with("/v1/metrics") {
+            endpoint.to_string()
+        } else {
+            format!("{}/v1/metrics", endpoint)
+        };
+
+        let service_name = std::env::current_exe()
+            .ok()
+            .and_then(|p| p.file_name().map(|n| n.to_string_lossy().into_owned()))
+            .unwrap_or_else(|| "hotpath".to_string());
+
+        let agent: ureq::Agent = ureq::Agent::config_builder()
+            .timeout_global(Some(REQUEST_TIMEOUT))
+            .build()
+            .into();
+
+        Self {
+            url,
+            service_name,
+            headers: Vec::new(),
+            interval: DEFAULT_INTERVAL,
+            agent,
+        }
+    }
+
+    /// Sets the `service.name` resource attribute. Defaults to the executable name.
+    pub fn service_name(mut self, service_name: impl Into<String>) -> Self {
+        self.service_name = service_name.into();
+        self
+    }
+
+    /// Adds an HTTP header sent with every export, e.g. for collector authentication.
+    pub fn header(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((key.into(), value.into()));
+        self
+    }
+
+    /// Sets how often stats are pushed while the guard is alive. Defaults to 60 seconds.
+    pub fn interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    pub(crate) fn export_interval(&self) -> Duration {
+        self.interval
+    }
+
+    /// Builds the `ExportMetricsServiceRequest` JSON body for a stats snapshot.
+    pub fn metrics_request(&self, metrics_provider: &dyn MetricsProvider<'_>) -> Value {
+        let now = SystemTime::now();
+        let time_unix_nano = unix_nanos(now);
+        let start_time_unix_nano =
+            unix_nanos(now - Duration::from_nanos(metrics_provider.total_elapsed()));
+
+        let mut metrics = Metrics::new(start_time_unix_nano, time_unix_nano);
+        metrics.add_functions(metrics_provider);
+        metrics.add_channels();
+
+        json!({
+            "resourceMetrics": [{
+                "resource": {
+                    "attributes": [
+                        attribute("service.name", &self.service_name),
+                        attribute("hotpath.caller", metrics_provider.caller_name()),
+                        attribute("hotpath.profiling_mode", &metrics_provider.profiling_mode().to_string()),
+                    ],
+                },
+                "scopeMetrics": [{
+                    "scope": {
+                        "name": "hotpath",
+                        "version": env!("CARGO_PKG_VERSION"),
+                    },
+                    "metrics": metrics.into_json(),
+                }],
+            }],
+        })
+    }
+}
+
+impl Reporter for OtlpReporter {
+    fn report(
+        &self,
+        metrics_provider: &dyn MetricsProvider<'_>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let body = self.metrics_request(metrics_provider);
+
+        let mut request = self.agent.post(&self.url);
+        for (key, value) in &self.headers {
+            request = request.header(key, value);
+        }
+        request.send_json(&body)?;
+        Ok(())
+    }
+}
+
+fn unix_nanos(time: SystemTime) -> String {
+    time.duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos()
+        .to_string()
+}
+
+fn attribute(key: &str, value: &str) -> Value {
+    json!({ "key": key, "value": { "stringValue": value } })
+}
+
+enum Kind {
+    Sum,
+    Gauge,
+}
+
+struct Metric {
+    unit: &'static str,
+    description: &'static str,
+    kind: Kind,
+    data_points: Vec<Value>,
+}
+
+/// Data points grouped by metric name, in a stable order.
+struct Metrics {
+    start_time_unix_nano: String,
+    time_unix_nano: String,
+    metrics: BTreeMap<&'static str, Metric>,
+}
+
+impl Metrics {
+    fn new(start_time_unix_nano: String, time_unix_nano: String) -> Self {
+        Self {
+            start_time_unix_nano,
+            time_unix_nano,
+            metrics: BTreeMap::new(),
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn push(
+        &mut self,
+        name: &'static str,
+        unit: &'static str,
+        description: &'static str,
+        kind: Kind,
+        attributes: Vec<Value>,
+        value_key: &str,
+        value: Value,
+    ) {
+        let mut data_point = json!({
+            "attributes": attributes,
+            "startTimeUnixNano": self.start_time_unix_nano,
+            "timeUnixNano": self.time_unix_nano,
+        });
+        data_point[value_key] = value;
+
+        self.metrics
+            .entry(name)
+            .or_insert_with(|| Metric {
+                unit,
+                description,
+                kind,
+                data_points: Vec::new(),
+            })
+            .data_points
+            .push(data_point);
+    }
+
+    fn add_functions(&mut self, metrics_provider: &dyn MetricsProvider<'_>) {
+        let headers = metrics_provider.headers();
+        let mut rows: Vec<_> = metrics_provider.metric_data().into_iter().collect();
+        rows.sort_by(|a, b| a.0.cmp(&b.0));
+
+        for (function, values) in rows {
+            for (header, value) in headers.iter().skip(1).zip(values) {
+                let stat = header.trim_start_matches("% ").to_lowercase();
+                let attributes =
+                    || vec![attribute("function", &function), attribute("stat", &stat)];
+
+                match value {
+                    MetricType::CallsCount(count) if stat == "calls" => self.push(
+                        "hotpath.function.calls",
+                        "{call}",
+                        "Completed calls of a measured function.",
+                        Kind::Sum,
+                        vec![attribute("function", &function)],
+                        "asInt",
+                        json!(count.to_string()),
+                    ),
+                    MetricType::DurationNs(ns) => self.push(
+                        "hotpath.function.duration",
+                        "ns",
+                        "Duration statistic of a measured function.",
+                        Kind::Gauge,
+                        attributes(),
+                        "asInt",
+                        json!(ns.to_string()),
+                    ),
+                    MetricType::Alloc(bytes, count) => {
+                        self.push(
+                            "hotpath.function.alloc.bytes",
+                            "By",
+                            "Allocated bytes statistic of a measured function.",
+                            Kind::Gauge,
+                            attributes(),
+                            "asInt",
+                            json!(bytes.to_string()),
+                        );
+                        self.push(
+                            "hotpath.function.alloc.count",
+                            "{allocation}",
+                            "Allocation count statistic of a measured function.",
+                            Kind::Gauge,
+                            attributes(),
+                            "asInt",
+                            json!(count.to_string()),
+                        );
+                    }
+                    MetricType::CallsCount(count) => self.push(
+                        "hotpath.function.count",
+                        "1",
+                        "Event count statistic of a measured function.",
+                        Kind::Gauge,
+                        attributes(),
+                        "asInt",
+                        json!(count.to_string()),
+                    ),
+                    MetricType::Percentage(basis_points) => self.push(
+                        "hotpath.function.share",
+                        "%",
+                        "Share of the total measured by a function.",
+                        Kind::Gauge,
+                        vec![attribute("function", &function)],
+                        "asDouble",
+                        json!(basis_points as f64 / 100.0),
+                    ),
+                    MetricType::Unsupported => {}
+                }
+            }
+        }
+    }
+
+    fn add_channels(&mut self) {
+        for stats in get_sorted_channel_stats() {
+            let channel = SerializableChannelStats::from(&stats);
+            let attributes = || vec![attribute("channel", &channel.label)];
+
+            self.push(
+                "hotpath.channel.sent",
+                "{message}",
+                "Messages sent to an instrumented channel.",
+                Kind::Sum,
+                attributes(),
+                "asInt",
+                json!(channel.sent_count.to_string()),
+            );
+            self.push(
+                "hotpath.channel.received",
+                "{message}",
+                "Messages received from an instrumented channel.",
+                Kind::Sum,
+                attributes(),
+                "asInt",
+                json!(channel.received_count.to_string()),
+            );
+            self.push(
+                "hotpath.channel.queued",
+                "{message}",
+                "Messages currently queued in an instrumented channel.",
+                Kind::Gauge,
+                attributes(),
+                "asInt",
+                json!(channel.queued.to_string()),
+            );
+        }
+    }
+
+    fn into_json(self) -> Vec<Value> {
+        self.metrics
+            .into_iter()
+            .map(|(name, metric)| {
+                let mut value = json!({
+                    "name": name,
+                    "unit": metric.unit,
+                    "description": metric.description,
+                });
+                match metric.kind {
+                    Kind::Sum => {
+                        value["sum"] = json!({
+                            "aggregationTemporality": CUMULATIVE,
+                            "isMonotonic": true,
+                            "dataPoints": metric.data_points,
+                        });
+                    }
+                    Kind::Gauge => {
+                        value["gauge"] = json!({ "dataPoints": metric.data_points });
+                    }
+                }
+                value
+            })
+            .collect()
+    }
+}
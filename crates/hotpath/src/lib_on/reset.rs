@@ -0,0 +1,56 @@
+//! Stats reset - clears collected stats without restarting the process.
+//!
+//! Instrumented channels, streams and futures stay registered with their labels, only
+//! their counters and logs are zeroed. Measured functions show up again on their next call.
+
+use crate::json::ResetScope;
+
+/// Clears the stats of functions, channels, streams and futures.
+///
+/// Useful to warm up a service and then measure a clean window. The same reset is
+/// available over HTTP with `POST /reset`.
+///
+/// # Examples
+///
+/// ```rust
+/// fn warm_up() {}
+///
+/// warm_up();
+/// hotpath::reset();
+/// ```
+pub fn reset() {
+    reset_functions();
+    reset_channels();
+    reset_streams();
+    reset_futures();
+}
+
+/// Clears the stats of measured functions, the report starts over from the reset.
+pub fn reset_functions() {
+    crate::functions::reset_functions();
+}
+
+/// Zeroes message counts and logs of instrumented channels.
+pub fn reset_channels() {
+    crate::channels::reset_channels();
+}
+
+/// Zeroes yielded item counts and logs of instrumented streams.
+pub fn reset_streams() {
+    crate::streams::reset_streams();
+}
+
+/// Drops the recorded calls of instrumented futures.
+pub fn reset_futures() {
+    crate::futures::reset_futures();
+}
+
+pub(crate) fn reset_scope(scope: ResetScope) {
+    match scope {
+        ResetScope::All => reset(),
+        ResetScope::Functions => reset_functions(),
+        ResetScope::Channels => reset_channels(),
+        ResetScope::Streams => reset_streams(),
+        ResetScope::Futures => reset_futures(),
+    }
+}
@@ -0,0 +1,154 @@
+//! This module samples Tokio runtime metrics - worker busy time, park counts,
+//! queue depths and the blocking pool size.
+//!
+//! Local queue depths and blocking pool metrics are only available when the
+//! profiled program is built with `RUSTFLAGS="--cfg tokio_unstable"`.
+
+use std::sync::{Arc, OnceLock, RwLock};
+use std::time::{Duration, Instant};
+
+use tokio::runtime::{Handle, RuntimeMetrics};
+
+pub use crate::json::{RuntimeJson, RuntimeWorkerMetrics};
+
+/// Internal state for runtime monitoring
+struct RuntimeState {
+    /// Current metrics snapshot
+    current: Option<RuntimeJson>,
+    /// Start time for elapsed calculation
+    start_time: Instant,
+}
+
+type RuntimeStateRef = Arc<RwLock<RuntimeState>>;
+
+static RUNTIME_STATE: OnceLock<RuntimeStateRef> = OnceLock::new();
+
+const DEFAULT_SAMPLE_INTERVAL_MS: u64 = 1000;
+
+// Initialize runtime monitoring for the Tokio runtime of the calling thread
+// Called on guard creation and on the first poll of measured async functions
+pub fn init_runtime_monitoring() {
+    if RUNTIME_STATE.get().is_some() {
+        return;
+    }
+    let Ok(handle) = Handle::try_current() else {
+        return;
+    };
+
+    RUNTIME_STATE.get_or_init(|| {
+        let sample_interval_ms = std::env::var("HOTPATH_RUNTIME_INTERVAL")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(DEFAULT_SAMPLE_INTERVAL_MS);
+
+        let sample_interval = Duration::from_millis(sample_interval_ms);
+
+        let state = Arc::new(RwLock::new(RuntimeState {
+            current: None,
+            start_time: Instant::now(),
+        }));
+
+        let state_clone = Arc::clone(&state);
+
+        std::thread::Builder::new()
+            .name("hp-runtime".into())
+            .spawn(move || {
+                collector_loop(state_clone, handle, sample_interval);
+            })
+            .expect("Failed to spawn runtime-metrics-collector thread");
+
+        state
+    });
+}
+
+fn collector_loop(state: RuntimeStateRef, handle: Handle, interval: Duration) {
+    let flavor = format!("{:?}", handle.runtime_flavor());
+    let metrics = handle.metrics();
+    // Busy time of every worker at the previous sample
+    let mut previous: Option<(Instant, Vec<Duration>)> = None;
+
+    loop {
+        let now = Instant::now();
+        let busy: Vec<Duration> = (0..metrics.num_workers())
+            .map(|worker| metrics.worker_total_busy_duration(worker))
+            .collect();
+
+        let workers = busy
+            .iter()
+            .enumerate()
+            .map(|(index, busy_total)| {
+                let busy_percent = previous.as_ref().and_then(|(sampled_at, prev_busy)| {
+                    let prev = prev_busy.get(index)?;
+                    let elapsed = now.duration_since(*sampled_at).as_secs_f64();
+                    (elapsed > 0.0)
+                        .then(|| busy_total.saturating_sub(*prev).as_secs_f64() / elapsed * 100.0)
+                });
+
+                RuntimeWorkerMetrics {
+                    index,
+                    busy_total: busy_total.as_secs_f64(),
+                    busy_percent,
+                    park_count: metrics.worker_park_count(index),
+                    park_unpark_count: metrics.worker_park_unpark_count(index),
+                    local_queue_depth: local_queue_depth(&metrics, index),
+                }
+            })
+            .collect();
+
+        let (num_blocking_threads, num_idle_blocking_threads, blocking_queue_depth) =
+            blocking_pool(&metrics);
+
+        if let Ok(mut state_guard) = state.write() {
+            let current_elapsed_ns = state_guard.start_time.elapsed().as_nanos() as u64;
+            state_guard.current = Some(RuntimeJson {
+                current_elapsed_ns,
+                sample_interval_ms: interval.as_millis() as u64,
+                flavor: flavor.clone(),
+                num_workers: metrics.num_workers(),
+                num_alive_tasks: metrics.num_alive_tasks(),
+                global_queue_depth: metrics.global_queue_depth(),
+                num_blocking_threads,
+                num_idle_blocking_threads,
+                blocking_queue_depth,
+                workers,
+            });
+        }
+
+        previous = Some((now, busy));
+        std::thread::sleep(interval);
+    }
+}
+
+#[cfg(tokio_unstable)]
+fn local_queue_depth(metrics: &RuntimeMetrics, worker: usize) -> Option<usize> {
+    Some(metrics.worker_local_queue_depth(worker))
+}
+
+#[cfg(not(tokio_unstable))]
+fn local_queue_depth(_metrics: &RuntimeMetrics, _worker: usize) -> Option<usize> {
+    None
+}
+
+/// Total, idle and queued tasks of the blocking pool
+#[cfg(tokio_unstable)]
+fn blocking_pool(metrics: &RuntimeMetrics) -> (Option<usize>, Option<usize>, Option<usize>) {
+    (
+        Some(metrics.num_blocking_threads()),
+        Some(metrics.num_idle_blocking_threads()),
+        Some(metrics.blocking_queue_depth()),
+    )
+}
+
+#[cfg(not(tokio_unstable))]
+fn blocking_pool(_metrics: &RuntimeMetrics) -> (Option<usize>, Option<usize>, Option<usize>) {
+    (None, None, None)
+}
+
+/// Get the latest runtime metrics sample, None until a runtime is monitored
+pub fn get_runtime_json() -> Option<RuntimeJson> {
+    let state = RUNTIME_STATE.get()?;
+    let state_guard = state.read().ok()?;
+    let mut json = state_guard.current.clone()?;
+    json.current_elapsed_ns = state_guard.start_time.elapsed().as_nanos() as u64;
+    Some(json)
+}
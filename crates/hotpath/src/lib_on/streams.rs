@@ -1,6 +1,6 @@
 //! Stream instrumentation module - tracks items yielded and stream lifecycle.
 
-use crossbeam_channel::{unbounded, Sender as CbSender};
+use crossbeam_channel::{bounded, unbounded, Sender as CbSender};
 use std::collections::{HashMap, VecDeque};
 use std::sync::atomic::AtomicU64;
 use std::sync::{Arc, OnceLock, RwLock};
@@ -16,7 +16,7 @@ pub use guard::{StreamsGuard, StreamsGuardBuilder};
 
 pub(crate) mod wrapper;
 
-use crate::http_server::HTTP_SERVER_PORT;
+use crate::http_server::{HTTP_SERVER_PORT, RECV_TIMEOUT_MS};
 pub use crate::json::{ChannelState, LogEntry, SerializableStreamStats, StreamLogs, StreamsJson};
 pub use crate::Format;
 
@@ -77,6 +77,12 @@ impl StreamStats {
             iter,
         }
     }
+
+    /// Zero the yielded items and logs, the stream stays registered with its label and state.
+    fn reset(&mut self) {
+        self.items_yielded = 0;
+        self.logs.clear();
+    }
 }
 
 /// Events sent to the background stream statistics collection thread.
@@ -97,6 +103,9 @@ pub(crate) enum StreamEvent {
     Completed {
         id: u64,
     },
+    Reset {
+        done_tx: CbSender<()>,
+    },
 }
 
 pub(crate) type StreamStatsState = (
@@ -167,6 +176,10 @@ pub(crate) fn init_streams_state() -> &'static StreamStatsState {
                                 stream_stats.state = ChannelState::Closed;
                             }
                         }
+                        StreamEvent::Reset { done_tx } => {
+                            stats.values_mut().for_each(StreamStats::reset);
+                            let _ = done_tx.send(());
+                        }
                     }
                 }
             })
@@ -337,7 +350,7 @@ pub fn get_stream_logs(stream_id: &str) -> Option<StreamLogs> {
         let mut yielded_logs: Vec<LogEntry> = stream_stats.logs.iter().cloned().collect();
 
         // Sort by index descending (most recent first)
-        yielded_logs.sort_by(|a, b| b.index.cmp(&a.index));
+        yielded_logs.sort_by_key(|log| std::cmp::Reverse(log.index));
 
         StreamLogs {
             id: stream_id.to_string(),
@@ -345,3 +358,13 @@ pub fn get_stream_logs(stream_id: &str) -> Option<StreamLogs> {
         }
     })
 }
+
+/// Zero the stats of all instrumented streams, events sent before the reset are applied first.
+pub(crate) fn reset_streams() {
+    if let Some((tx, _)) = STREAMS_STATE.get() {
+        let (done_tx, done_rx) = bounded::<()>(1);
+        if tx.send(StreamEvent::Reset { done_tx }).is_ok() {
+            let _ = done_rx.recv_timeout(std::time::Duration::from_millis(RECV_TIMEOUT_MS));
+        }
+    }
+}
@@ -132,7 +132,7 @@ impl Drop for StreamsGuard {
         }
 
         match self.format {
-            Format::Table => {
+            Format::Table | Format::Folded => {
                 println!(
                     "\n=== Stream Statistics (runtime: {:.2}s) ===",
                     elapsed.as_secs_f64()
@@ -0,0 +1,361 @@
+//! Trace recording module - streams every measurement to a Chrome Trace Event JSON file.
+//!
+//! Enabled by setting `HOTPATH_TRACE_FILE` to an output path. The resulting file can be
+//! opened in [Perfetto](https://ui.perfetto.dev) or `chrome://tracing`, with one lane per
+//! OS thread and one lane per instrumented channel.
+
+use crossbeam_channel::{bounded, unbounded, Receiver, Sender};
+use serde::Serialize;
+use std::cell::Cell;
+use std::fs::File;
+use std::io::{BufWriter, Seek, SeekFrom, Write};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{LazyLock, OnceLock};
+use std::time::Duration;
+
+use crate::http_server::RECV_TIMEOUT_MS;
+
+pub(crate) static HOTPATH_TRACE_FILE: LazyLock<Option<PathBuf>> = LazyLock::new(|| {
+    std::env::var("HOTPATH_TRACE_FILE")
+        .ok()
+        .filter(|p| !p.is_empty())
+        .map(PathBuf::from)
+});
+
+/// Closing bytes written on every flush, then overwritten by the next event.
+const TRAILER: &[u8] = b"\n]\n";
+
+/// Channel lanes count down from the top of the tid space so they never collide with OS tids.
+const CHANNEL_LANE_BASE: u64 = u32::MAX as u64;
+
+static TRACE_SENDER: OnceLock<Option<Sender<TraceEvent>>> = OnceLock::new();
+
+static ASYNC_SPAN_ID: AtomicU64 = AtomicU64::new(0);
+
+thread_local! {
+    static THREAD_NOTED: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Common time origin for all trace events, anchored on both clocks used by hotpath.
+struct Origin {
+    std: std::time::Instant,
+    #[cfg(target_os = "linux")]
+    quanta: quanta::Instant,
+}
+
+static ORIGIN: LazyLock<Origin> = LazyLock::new(|| Origin {
+    std: std::time::Instant::now(),
+    #[cfg(target_os = "linux")]
+    quanta: quanta::Instant::now(),
+});
+
+/// Instant types that can be placed on the trace timeline.
+pub(crate) trait TraceInstant {
+    /// Nanoseconds elapsed between the trace origin and this instant.
+    fn trace_nanos(self) -> u64;
+}
+
+impl TraceInstant for std::time::Instant {
+    fn trace_nanos(self) -> u64 {
+        self.saturating_duration_since(ORIGIN.std).as_nanos() as u64
+    }
+}
+
+#[cfg(target_os = "linux")]
+impl TraceInstant for quanta::Instant {
+    fn trace_nanos(self) -> u64 {
+        self.saturating_duration_since(ORIGIN.quanta).as_nanos() as u64
+    }
+}
+
+enum TraceEvent {
+    Complete {
+        name: String,
+        cat: &'static str,
+        tid: u64,
+        start_ns: u64,
+        dur_ns: u64,
+        args: Option<serde_json::Value>,
+    },
+    Async {
+        name: String,
+        cat: &'static str,
+        id: u64,
+        start_ns: u64,
+        dur_ns: u64,
+        args: Option<serde_json::Value>,
+    },
+    Instant {
+        name: String,
+        cat: &'static str,
+        tid: u64,
+        ts_ns: u64,
+        args: Option<serde_json::Value>,
+    },
+    ThreadName {
+        tid: u64,
+        name: String,
+    },
+    Flush(Sender<()>),
+}
+
+#[derive(Serialize)]
+struct ChromeEvent<'a> {
+    name: &'a str,
+    #[serde(skip_serializing_if = "str::is_empty")]
+    cat: &'a str,
+    ph: &'static str,
+    ts: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    dur: Option<f64>,
+    pid: u32,
+    tid: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    id: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    s: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    args: Option<&'a serde_json::Value>,
+}
+
+impl<'a> ChromeEvent<'a> {
+    fn new(name: &'a str, cat: &'a str, ph: &'static str, ts_ns: u64, tid: u64) -> Self {
+        Self {
+            name,
+            cat,
+            ph,
+            ts: micros(ts_ns),
+            dur: None,
+            pid: std::process::id(),
+            tid,
+            id: None,
+            s: None,
+            args: None,
+        }
+    }
+}
+
+fn micros(ns: u64) -> f64 {
+    ns as f64 / 1000.0
+}
+
+/// Start the trace writer thread if `HOTPATH_TRACE_FILE` is set (idempotent).
+pub(crate) fn init_trace() {
+    TRACE_SENDER.get_or_init(|| {
+        let path = HOTPATH_TRACE_FILE.as_ref()?;
+
+        let file = match File::create(path) {
+            Ok(file) => file,
+            Err(e) => {
+                eprintln!(
+                    "[hotpath] Failed to create trace file {}: {}",
+                    path.display(),
+                    e
+                );
+                return None;
+            }
+        };
+
+        LazyLock::force(&ORIGIN);
+
+        let (tx, rx) = unbounded::<TraceEvent>();
+
+        std::thread::Builder::new()
+            .name("hp-trace".into())
+            .spawn(move || {
+                if let Err(e) = write_events(file, rx) {
+                    eprintln!("[hotpath] Failed to write trace file: {}", e);
+                }
+            })
+            .expect("Failed to spawn trace writer thread");
+
+        Some(tx)
+    });
+}
+
+#[inline]
+fn sender() -> Option<&'static Sender<TraceEvent>> {
+    TRACE_SENDER.get().and_then(Option::as_ref)
+}
+
+/// Returns true if trace recording is active.
+#[inline]
+pub(crate) fn is_enabled() -> bool {
+    sender().is_some()
+}
+
+fn send(event: TraceEvent) {
+    if let Some(tx) = sender() {
+        let _ = tx.send(event);
+    }
+}
+
+/// Name the current thread's lane, once per thread.
+pub(crate) fn note_current_thread() {
+    if !is_enabled() || THREAD_NOTED.with(|noted| noted.replace(true)) {
+        return;
+    }
+
+    let tid = crate::tid::current_tid();
+    let name = std::thread::current()
+        .name()
+        .map(str::to_string)
+        .unwrap_or_else(|| format!("thread-{}", tid));
+
+    send(TraceEvent::ThreadName { tid, name });
+}
+
+/// Record a span that ended at `end`. Spans that migrated between threads (`tid == None`)
+/// are recorded as async slices since they cannot nest on a single thread lane.
+pub(crate) fn record_span(
+    name: &str,
+    cat: &'static str,
+    tid: Option<u64>,
+    end: impl TraceInstant,
+    dur_ns: u64,
+    args: Option<serde_json::Value>,
+) {
+    let start_ns = end.trace_nanos().saturating_sub(dur_ns);
+    let name = name.to_string();
+
+    match tid {
+        Some(tid) => send(TraceEvent::Complete {
+            name,
+            cat,
+            tid,
+            start_ns,
+            dur_ns,
+            args,
+        }),
+        None => send(TraceEvent::Async {
+            name,
+            cat,
+            id: ASYNC_SPAN_ID.fetch_add(1, Ordering::Relaxed),
+            start_ns,
+            dur_ns,
+            args,
+        }),
+    }
+}
+
+/// Name the lane of an instrumented channel.
+pub(crate) fn record_channel_created(channel_id: u64, label: &str) {
+    send(TraceEvent::ThreadName {
+        tid: CHANNEL_LANE_BASE - channel_id,
+        name: format!("channel {}", label),
+    });
+}
+
+/// Record a send or receive event on a channel lane.
+pub(crate) fn record_channel_event(
+    channel_id: u64,
+    name: &'static str,
+    at: impl TraceInstant,
+    args: Option<serde_json::Value>,
+) {
+    send(TraceEvent::Instant {
+        name: name.to_string(),
+        cat: "channel",
+        tid: CHANNEL_LANE_BASE - channel_id,
+        ts_ns: at.trace_nanos(),
+        args,
+    });
+}
+
+/// Write all events received so far and terminate the JSON array, so the file is valid
+/// even if the process exits right after. Later events keep appending to the same array.
+pub(crate) fn flush_trace() {
+    let Some(tx) = sender() else {
+        return;
+    };
+
+    let (ack_tx, ack_rx) = bounded::<()>(1);
+    if tx.send(TraceEvent::Flush(ack_tx)).is_ok() {
+        let _ = ack_rx.recv_timeout(Duration::from_millis(RECV_TIMEOUT_MS));
+    }
+}
+
+fn write_events(file: File, rx: Receiver<TraceEvent>) -> std::io::Result<()> {
+    let mut out = BufWriter::new(file);
+
+    out.write_all(b"[\n")?;
+    let process_name = serde_json::json!({
+        "name": std::env::current_exe()
+            .ok()
+            .and_then(|p| p.file_name().map(|n| n.to_string_lossy().into_owned()))
+            .unwrap_or_else(|| "hotpath".to_string()),
+    });
+    let mut metadata = ChromeEvent::new("process_name", "", "M", 0, 0);
+    metadata.args = Some(&process_name);
+    serde_json::to_writer(&mut out, &metadata)?;
+
+    while let Ok(event) = rx.recv() {
+        match event {
+            TraceEvent::Flush(ack) => {
+                out.write_all(TRAILER)?;
+                out.flush()?;
+                out.seek(SeekFrom::Current(-(TRAILER.len() as i64)))?;
+                let _ = ack.send(());
+                continue;
+            }
+            TraceEvent::Complete {
+                name,
+                cat,
+                tid,
+                start_ns,
+                dur_ns,
+                args,
+            } => {
+                let mut event = ChromeEvent::new(&name, cat, "X", start_ns, tid);
+                event.dur = Some(micros(dur_ns));
+                event.args = args.as_ref();
+                write_event(&mut out, &event)?;
+            }
+            TraceEvent::Async {
+                name,
+                cat,
+                id,
+                start_ns,
+                dur_ns,
+                args,
+            } => {
+                let mut begin = ChromeEvent::new(&name, cat, "b", start_ns, 0);
+                begin.id = Some(id);
+                begin.args = args.as_ref();
+                write_event(&mut out, &begin)?;
+
+                let mut end = ChromeEvent::new(&name, cat, "e", start_ns + dur_ns, 0);
+                end.id = Some(id);
+                write_event(&mut out, &end)?;
+            }
+            TraceEvent::Instant {
+                name,
+                cat,
+                tid,
+                ts_ns,
+                args,
+            } => {
+                let mut event = ChromeEvent::new(&name, cat, "i", ts_ns, tid);
+                event.s = Some("t");
+                event.args = args.as_ref();
+                write_event(&mut out, &event)?;
+            }
+            TraceEvent::ThreadName { tid, name } => {
+                let args = serde_json::json!({ "name": name });
+                let mut event = ChromeEvent::new("thread_name", "", "M", 0, tid);
+                event.args = Some(&args);
+                write_event(&mut out, &event)?;
+            }
+        }
+    }
+
+    out.write_all(TRAILER)?;
+    out.flush()
+}
+
+fn write_event(out: &mut BufWriter<File>, event: &ChromeEvent<'_>) -> std::io::Result<()> {
+    out.write_all(b",\n")?;
+    serde_json::to_writer(&mut *out, event)?;
+    Ok(())
+}
@@ -9,6 +9,8 @@ use std::time::Duration;
 
 #[cfg(feature = "hotpath")]
 use crate::FunctionStats;
+#[cfg(feature = "hotpath")]
+use crate::SortBy;
 
 /// Find the nearest valid char boundary at or before `index`.
 /// Used to safely truncate UTF-8 strings from the right.
@@ -49,7 +51,7 @@ pub fn truncate_result(s: String) -> String {
 ///
 /// # Variants
 ///
-/// * `CallsCount(u64)` - Number of function calls
+/// * `CallsCount(u64)` - Number of function calls, or of other events such as reallocations
 /// * `DurationNs(u64)` - Duration in nanoseconds (formatted as human-readable time)
 /// * `AllocBytes(u64)` - Bytes allocated (formatted with KB/MB/GB units)
 /// * `AllocCount(u64)` - Allocation count
@@ -181,7 +183,7 @@ pub trait Reporter: Send + Sync {
 /// * `Timing` - Time-based profiling (execution duration)
 /// * `Alloc` - Combined allocation profiling (both bytes and count)
 #[allow(dead_code)]
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
 #[serde(rename_all = "kebab-case")]
 pub enum ProfilingMode {
     Timing,
@@ -225,6 +227,44 @@ pub struct FunctionLogsJson {
     pub logs: Vec<FunctionLogEntry>,
     /// Total number of times this function was invoked (used to calculate invocation numbers)
     pub count: usize,
+    /// Allocations of all calls by size class (only for memory mode), empty classes are skipped
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub size_classes: Vec<SizeClassJson>,
+}
+
+/// Number of allocations of `min_bytes..max_bytes` bytes made by a function.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SizeClassJson {
+    pub min_bytes: u64,
+    /// Exclusive upper bound, None for the last class
+    pub max_bytes: Option<u64>,
+    pub count: u64,
+}
+
+/// A node of the caller→callee tree of measured functions.
+///
+/// Values aggregate the calls made with the exact chain of callers leading to the node,
+/// recursive calls are counted in the node of the outermost call.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CallTreeNode {
+    pub name: String,
+    /// Number of calls made from the parent node
+    pub calls: u64,
+    /// Inclusive duration of these calls in nanoseconds
+    pub total_ns: u64,
+    /// Duration of these calls excluding nested measured functions in nanoseconds
+    pub self_ns: u64,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<CallTreeNode>,
+}
+
+/// Response containing the caller→callee tree of measured functions
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FunctionsTreeJson {
+    pub total_elapsed: u64,
+    pub caller_name: String,
+    /// Functions called outside of any other measured function
+    pub roots: Vec<CallTreeNode>,
 }
 
 /// JSON representation of profiling metrics.
@@ -235,7 +275,13 @@ pub struct FunctionsJson {
     pub description: String,
     pub caller_name: String,
     pub percentiles: Vec<u8>,
+    /// Column headers, starting with Function, the rest match the metric rows in `data`
+    pub headers: Vec<String>,
     pub data: FunctionsDataJson,
+    /// Caller→callee tree, empty in allocation mode
+    pub call_tree: Vec<CallTreeNode>,
+    /// Tags of measured calls, the data can be limited to one of them
+    pub tags: Vec<String>,
 }
 
 #[derive(Deserialize)]
@@ -245,6 +291,12 @@ struct MetricsJsonRaw {
     description: String,
     caller_name: String,
     output: serde_json::Value,
+    #[serde(default)]
+    headers: Option<Vec<String>>,
+    #[serde(default)]
+    call_tree: Vec<CallTreeNode>,
+    #[serde(default)]
+    tags: Vec<String>,
 }
 
 impl TryFrom<MetricsJsonRaw> for FunctionsJson {
@@ -254,6 +306,12 @@ impl TryFrom<MetricsJsonRaw> for FunctionsJson {
         let percentiles =
             extract_percentiles_from_json(&raw.output).map_err(serde::de::Error::custom)?;
 
+        // Reports written before the headers were serialized only have the column keys
+        let headers = match raw.headers {
+            Some(headers) => headers,
+            None => present_headers(&raw.output, &raw.hotpath_profiling_mode, &percentiles),
+        };
+
         let output = FunctionsDataJson::deserialize_with_mode(
             raw.output,
             &raw.hotpath_profiling_mode,
@@ -267,7 +325,10 @@ impl TryFrom<MetricsJsonRaw> for FunctionsJson {
             description: raw.description,
             caller_name: raw.caller_name,
             percentiles,
+            headers,
             data: output,
+            call_tree: raw.call_tree,
+            tags: raw.tags,
         })
     }
 }
@@ -286,7 +347,15 @@ impl<'de> Deserialize<'de> for FunctionsJson {
 #[derive(Debug, Clone)]
 pub struct FunctionsDataJson(pub HashMap<String, Vec<MetricType>>);
 
-fn build_headers(percentiles: &[u8]) -> Vec<String> {
+/// Columns between Total and % Total: self time for timing, reallocations for alloc reports.
+fn extra_headers(profiling_mode: &ProfilingMode) -> &'static [&'static str] {
+    match profiling_mode {
+        ProfilingMode::Timing => &["Self"],
+        ProfilingMode::Alloc => &["Reallocs", "Grown"],
+    }
+}
+
+fn build_headers(percentiles: &[u8], extra: &[&str]) -> Vec<String> {
     let mut headers = vec![
         "Function".to_string(),
         "Calls".to_string(),
@@ -298,11 +367,79 @@ fn build_headers(percentiles: &[u8]) -> Vec<String> {
     }
 
     headers.push("Total".to_string());
+    headers.extend(extra.iter().map(|h| h.to_string()));
     headers.push("% Total".to_string());
 
     headers
 }
 
+/// JSON key of a column, e.g. `percent_total` for `% Total`.
+fn header_key(header: &str) -> String {
+    header
+        .to_lowercase()
+        .replace(' ', "_")
+        .replace('%', "percent")
+}
+
+/// Headers whose keys appear in the serialized rows, the Function column is always present.
+fn present_headers(
+    output: &serde_json::Value,
+    profiling_mode: &ProfilingMode,
+    percentiles: &[u8],
+) -> Vec<String> {
+    let headers = build_headers(percentiles, extra_headers(profiling_mode));
+    let Some(first_function) = output
+        .as_object()
+        .and_then(|map| map.values().next())
+        .and_then(|function| function.as_object())
+    else {
+        return headers;
+    };
+
+    headers
+        .into_iter()
+        .enumerate()
+        .filter(|(i, header)| *i == 0 || first_function.contains_key(&header_key(header)))
+        .map(|(_, header)| header)
+        .collect()
+}
+
+/// Position of the `name` column in a metric row, which doesn't include the Function column.
+pub(crate) fn column_index(headers: &[String], name: &str) -> Option<usize> {
+    headers.iter().skip(1).position(|header| header == name)
+}
+
+/// Sort key of a metric row for the `sort_by` column, rows with higher keys come first.
+#[cfg(all(feature = "hotpath", not(feature = "hotpath-off")))]
+pub(crate) fn sort_value(headers: &[String], metrics: &[MetricType], sort_by: SortBy) -> f64 {
+    let column = match sort_by {
+        SortBy::Total => "% Total",
+        SortBy::SelfTime => "Self",
+    };
+    match column_index(headers, column).and_then(|i| metrics.get(i)) {
+        Some(MetricType::Percentage(basis_points)) => *basis_points as f64 / 100.0,
+        Some(MetricType::DurationNs(ns)) => *ns as f64,
+        _ => 0.0,
+    }
+}
+
+impl FunctionsJson {
+    /// Headers of a report with every column of `profiling_mode`.
+    pub fn default_headers(profiling_mode: &ProfilingMode, percentiles: &[u8]) -> Vec<String> {
+        build_headers(percentiles, extra_headers(profiling_mode))
+    }
+
+    /// Column headers matching the metric rows in [`FunctionsJson::data`].
+    pub fn headers(&self) -> Vec<String> {
+        self.headers.clone()
+    }
+
+    /// Value of the `name` column (e.g. `"Self"`) in one of the [`FunctionsJson::data`] rows.
+    pub fn column<'m>(&self, row: &'m [MetricType], name: &str) -> Option<&'m MetricType> {
+        column_index(&self.headers, name).and_then(|i| row.get(i))
+    }
+}
+
 struct MetricsDataSerializer<'a> {
     data: &'a HashMap<String, Vec<MetricType>>,
     headers: &'a [String],
@@ -335,20 +472,32 @@ impl Serialize for FunctionsJson {
     {
         use serde::ser::SerializeStruct;
 
-        let headers = build_headers(&self.percentiles);
-        let mut state = serializer.serialize_struct("MetricsJson", 5)?;
+        let mut state = serializer.serialize_struct("MetricsJson", 8)?;
 
         state.serialize_field("hotpath_profiling_mode", &self.hotpath_profiling_mode)?;
         state.serialize_field("total_elapsed", &self.total_elapsed)?;
         state.serialize_field("description", &self.description)?;
         state.serialize_field("caller_name", &self.caller_name)?;
+        state.serialize_field("headers", &self.headers)?;
 
         let output_serializer = MetricsDataSerializer {
             data: &self.data.0,
-            headers: &headers,
+            headers: &self.headers,
         };
         state.serialize_field("output", &output_serializer)?;
 
+        if self.call_tree.is_empty() {
+            state.skip_field("call_tree")?;
+        } else {
+            state.serialize_field("call_tree", &self.call_tree)?;
+        }
+
+        if self.tags.is_empty() {
+            state.skip_field("tags")?;
+        } else {
+            state.serialize_field("tags", &self.tags)?;
+        }
+
         state.end()
     }
 }
@@ -393,7 +542,7 @@ impl FunctionsDataJson {
             .as_object()
             .ok_or("Expected object for output field")?;
 
-        let headers = build_headers(percentiles);
+        let headers = build_headers(percentiles, extra_headers(profiling_mode));
         let mut data = HashMap::new();
 
         for (function_name, function_data) in map {
@@ -403,11 +552,7 @@ impl FunctionsDataJson {
 
             let mut row = Vec::new();
             for header in headers.iter().skip(1) {
-                // Convert header to JSON key format (lowercase, replace spaces and %)
-                let key = header
-                    .to_lowercase()
-                    .replace(' ', "_")
-                    .replace('%', "percent");
+                let key = header_key(header);
 
                 if let Some(value) = function_obj.get(&key) {
                     if value.is_null() {
@@ -429,6 +574,7 @@ impl FunctionsDataJson {
 fn create_metric_type(field_name: &str, value: u64, profiling_mode: &ProfilingMode) -> MetricType {
     match field_name {
         "calls" => MetricType::CallsCount(value),
+        "reallocs" => MetricType::CallsCount(value),
         "percent_total" => MetricType::Percentage(value),
         // Percentiles
         name if name.starts_with('p') && name[1..].chars().all(|c| c.is_ascii_digit()) => {
@@ -437,7 +583,7 @@ fn create_metric_type(field_name: &str, value: u64, profiling_mode: &ProfilingMo
                 ProfilingMode::Alloc => MetricType::Alloc(value, 0),
             }
         }
-        "avg" | "total" => match profiling_mode {
+        "avg" | "total" | "self" | "grown" => match profiling_mode {
             ProfilingMode::Timing => MetricType::DurationNs(value),
             ProfilingMode::Alloc => MetricType::Alloc(value, 0),
         },
@@ -459,11 +605,7 @@ impl<'a> Serialize for FunctionDataSerializer<'a> {
 
         for (i, header) in self.headers.iter().enumerate().skip(1) {
             if i - 1 < self.row.len() {
-                let key = header
-                    .to_lowercase()
-                    .replace(' ', "_")
-                    .replace('%', "percent");
-                map.serialize_entry(&key, &self.row[i - 1])?;
+                map.serialize_entry(&header_key(header), &self.row[i - 1])?;
             }
         }
 
@@ -506,20 +648,7 @@ pub trait MetricsProvider<'a> {
     fn description(&self) -> String;
     fn profiling_mode(&self) -> ProfilingMode;
     fn headers(&self) -> Vec<String> {
-        let mut headers = vec![
-            "Function".to_string(),
-            "Calls".to_string(),
-            "Avg".to_string(),
-        ];
-
-        for &p in &self.percentiles() {
-            headers.push(format!("P{}", p));
-        }
-
-        headers.push("Total".to_string());
-        headers.push("% Total".to_string());
-
-        headers
+        build_headers(&self.percentiles(), extra_headers(&self.profiling_mode()))
     }
     fn percentiles(&self) -> Vec<u8>;
 
@@ -534,10 +663,20 @@ pub trait MetricsProvider<'a> {
         }
     }
 
+    /// Whether some calls have no allocation data, i.e. spans in allocation mode.
     fn has_unsupported_async(&self) -> bool {
         false // Default implementation for time-based measurements
     }
 
+    fn call_tree(&self) -> Vec<CallTreeNode> {
+        Vec::new()
+    }
+
+    /// Collapsed stacks (`outer;inner`) paired with self time in nanoseconds.
+    fn folded_stacks(&self) -> Vec<(String, u64)> {
+        Vec::new()
+    }
+
     fn entry_counts(&self) -> (usize, usize);
 
     #[cfg(feature = "hotpath")]
@@ -551,6 +690,15 @@ pub trait MetricsProvider<'a> {
     where
         Self: Sized;
 
+    /// Order the rows by the given column, providers without it keep their default order.
+    #[cfg(feature = "hotpath")]
+    fn with_sort_by(self, _sort_by: SortBy) -> Self
+    where
+        Self: Sized,
+    {
+        self
+    }
+
     fn total_elapsed(&self) -> u64;
 
     fn caller_name(&self) -> &str;
@@ -644,6 +792,7 @@ mod tests {
             "total_elapsed": 125189584,
             "caller_name": "basic::main",
             "description": "Time metrics",
+            "headers": ["Function", "Calls", "Avg", "P95", "Total", "% Total"],
             "output": {
                 "basic::async_function": {
                     "calls": 100,
@@ -686,6 +835,11 @@ mod tests {
 
         // Verify that the internal structure is correctly parsed
         assert_eq!(metrics.percentiles, vec![95]);
+        // Reports without serialized headers get the columns present in the rows
+        assert_eq!(
+            metrics.headers(),
+            vec!["Function", "Calls", "Avg", "P95", "Total", "% Total"]
+        );
         assert_eq!(metrics.data.0.len(), 1);
         assert!(metrics.data.0.contains_key("test_function"));
 
@@ -754,6 +908,48 @@ mod tests {
         ));
         assert!(matches!(html_response_row[4], MetricType::Percentage(62)));
     }
+
+    #[test]
+    fn test_self_column_roundtrip() {
+        let json_str = r#"{
+            "hotpath_profiling_mode": "timing",
+            "total_elapsed": 125189584,
+            "caller_name": "basic::main",
+            "description": "Time metrics",
+            "headers": ["Function", "Calls", "Avg", "P95", "Total", "Self", "% Total"],
+            "output": {
+                "basic::main": {
+                    "calls": 1,
+                    "avg": 125000000,
+                    "p95": 125000000,
+                    "total": 125000000,
+                    "self": 1250000,
+                    "percent_total": 10000
+                }
+            }
+        }"#;
+
+        let metrics: FunctionsJson = serde_json::from_str(json_str).expect("Failed to deserialize");
+
+        assert_eq!(
+            metrics.headers(),
+            vec!["Function", "Calls", "Avg", "P95", "Total", "Self", "% Total"]
+        );
+
+        let row = &metrics.data.0["basic::main"];
+        assert_eq!(row.len(), 6);
+        assert!(matches!(row[4], MetricType::DurationNs(1250000))); // self
+        assert!(matches!(row[5], MetricType::Percentage(10000))); // percent_total
+        assert!(matches!(
+            metrics.column(row, "Self"),
+            Some(MetricType::DurationNs(1250000))
+        ));
+
+        let serialized_str = serde_json::to_string(&metrics).expect("Failed to serialize");
+        let original_json: Value = serde_json::from_str(json_str).unwrap();
+        let serialized_json: Value = serde_json::from_str(&serialized_str).unwrap();
+        assert_eq!(serialized_json, original_json);
+    }
 }
 
 #[cfg(test)]
@@ -90,13 +90,8 @@ pub(crate) fn display_table(metrics_provider: &dyn MetricsProvider<'_>) {
     if metrics_provider.has_unsupported_async() {
         println!();
         println!(
-            "* {} for async methods is currently only available for tokio {} runtime.",
-            "alloc profiling".yellow().bold(),
-            "current_thread".green().bold()
-        );
-        println!(
-            "  Please use {} to enable it.",
-            "#[tokio::main(flavor = \"current_thread\")]".cyan().bold()
+            "* {} don't track allocations, their allocation columns are N/A.",
+            "spans".yellow().bold()
         );
     }
 }
@@ -191,6 +186,29 @@ impl Reporter for JsonPrettyReporter {
     }
 }
 
+pub(crate) fn folded_to_string(stacks: &[(String, u64)]) -> String {
+    let mut output = String::new();
+    for (stack, value) in stacks {
+        output.push_str(stack);
+        output.push(' ');
+        output.push_str(&value.to_string());
+        output.push('\n');
+    }
+    output
+}
+
+pub(crate) struct FoldedReporter;
+
+impl Reporter for FoldedReporter {
+    fn report(
+        &self,
+        metrics_provider: &dyn MetricsProvider<'_>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        print!("{}", folded_to_string(&metrics_provider.folded_stacks()));
+        Ok(())
+    }
+}
+
 impl From<&dyn MetricsProvider<'_>> for FunctionsJson {
     fn from(metrics: &dyn MetricsProvider<'_>) -> Self {
         let hotpath_profiling_mode = metrics.profiling_mode();
@@ -205,7 +223,10 @@ impl From<&dyn MetricsProvider<'_>> for FunctionsJson {
             description: metrics.description(),
             caller_name: metrics.caller_name().to_string(),
             percentiles,
+            headers: metrics.headers(),
             data: FunctionsDataJson(data),
+            call_tree: metrics.call_tree(),
+            tags: Vec::new(),
         }
     }
 }
@@ -0,0 +1,276 @@
+#[cfg(test)]
+pub mod tests {
+    use std::process::Command;
+
+    fn path_sep() -> &'static str {
+        if cfg!(windows) {
+            "\\"
+        } else {
+            "/"
+        }
+    }
+
+    #[test]
+    fn test_basic_output() {
+        let output = Command::new("cargo")
+            .args([
+                "run",
+                "-p",
+                "test-channels-async-channel",
+                "--example",
+                "basic_async_channel",
+                "--features",
+                "hotpath",
+            ])
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(
+            output.status.success(),
+            "Command failed with status: {}",
+            output.status
+        );
+
+        assert!(!output.stderr.is_empty(), "Stderr is empty");
+        let sep = path_sep();
+        let basic_async_channel_path = format!("examples{sep}basic_async_channel.rs");
+        let all_expected = [
+            basic_async_channel_path.as_str(),
+            "hello-there",
+            "unbounded",
+            "bounded[10]",
+            "bounded[1]",
+        ];
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        for expected in all_expected {
+            assert!(
+                stdout.contains(expected),
+                "Expected:\n{expected}\n\nGot:\n{stdout}",
+            );
+        }
+    }
+
+    #[test]
+    fn test_closed_channels_output() {
+        let output = Command::new("cargo")
+            .args([
+                "run",
+                "-p",
+                "test-channels-async-channel",
+                "--example",
+                "closed_async_channel",
+                "--features",
+                "hotpath",
+            ])
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(
+            output.status.success(),
+            "Command failed with status: {}",
+            output.status
+        );
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+
+        // Check that all three channels have "closed" state
+        for label in ["closed-sender", "closed-receiver", "closed-unbounded"] {
+            assert!(
+                stdout
+                    .lines()
+                    .any(|line| line.contains(label) && line.contains("| closed ")),
+                "Expected closed {label} channel in output:\n{stdout}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_basic_json_output() {
+        let output = Command::new("cargo")
+            .args([
+                "run",
+                "-p",
+                "test-channels-async-channel",
+                "--example",
+                "basic_json_async_channel",
+                "--features",
+                "hotpath",
+            ])
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(
+            output.status.success(),
+            "Command failed with status: {}",
+            output.status
+        );
+
+        let all_expected = ["\"label\": \"bounded\"", "\"label\": \"unbounded\""];
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+
+        for expected in all_expected {
+            assert!(
+                stdout.contains(expected),
+                "Expected:\n{expected}\n\nGot:\n{stdout}",
+            );
+        }
+    }
+
+    #[test]
+    fn test_iter_output() {
+        let output = Command::new("cargo")
+            .args([
+                "run",
+                "-p",
+                "test-channels-async-channel",
+                "--example",
+                "iter_async_channel",
+                "--features",
+                "hotpath",
+            ])
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(
+            output.status.success(),
+            "Command failed with status: {}",
+            output.status
+        );
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+
+        let sep = path_sep();
+        let iter_path = format!("examples{sep}iter_async_channel.rs:13");
+        let iter_path_2 = format!("examples{sep}iter_async_channel.rs:13-2");
+        let iter_path_3 = format!("examples{sep}iter_async_channel.rs:13-3");
+        let all_expected = [
+            "bounded",
+            "bounded-2",
+            "bounded-3",
+            iter_path.as_str(),
+            iter_path_2.as_str(),
+            iter_path_3.as_str(),
+        ];
+
+        for expected in all_expected {
+            assert!(
+                stdout.contains(expected),
+                "Expected:\n{expected}\n\nGot:\n{stdout}",
+            );
+        }
+    }
+
+    #[test]
+    fn test_slow_consumer_no_panic() {
+        let output = Command::new("cargo")
+            .args([
+                "run",
+                "-p",
+                "test-channels-async-channel",
+                "--example",
+                "slow_consumer_async_channel",
+                "--features",
+                "hotpath",
+            ])
+            .output()
+            .expect("Failed to execute command");
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+
+        assert!(
+            output.status.success(),
+            "Command failed with status: {}\nStdout:\n{}\nStderr:\n{}",
+            output.status,
+            stdout,
+            stderr
+        );
+
+        assert!(
+            stdout.contains("Slow consumer example completed!"),
+            "Expected completion message not found.\nOutput:\n{}",
+            stdout
+        );
+    }
+
+    #[test]
+    fn test_data_endpoints() {
+        use hotpath::json::ChannelsJson;
+        use std::{thread::sleep, time::Duration};
+
+        // Spawn example process
+        let mut child = Command::new("cargo")
+            .args([
+                "run",
+                "-p",
+                "test-channels-async-channel",
+                "--example",
+                "basic_async_channel",
+                "--features",
+                "hotpath",
+            ])
+            .env("HOTPATH_HTTP_PORT", "6780")
+            .env("TEST_SLEEP_SECONDS", "10")
+            .spawn()
+            .expect("Failed to spawn command");
+
+        let mut json_text = String::new();
+        let mut last_error = None;
+
+        // Test /channels endpoint
+        // Give the server some time to start up
+
+        for _attempt in 0..12 {
+            sleep(Duration::from_millis(500));
+
+            match ureq::get("http://localhost:6780/channels").call() {
+                Ok(mut response) => {
+                    json_text = response
+                        .body_mut()
+                        .read_to_string()
+                        .expect("Failed to read response body");
+                    last_error = None;
+                    break;
+                }
+                Err(e) => {
+                    last_error = Some(format!("Request error: {}", e));
+                }
+            }
+        }
+
+        if let Some(error) = last_error {
+            let _ = child.kill();
+            panic!("Failed after 12 retries: {}", error);
+        }
+
+        let all_expected = ["basic_async_channel.rs", "unbounded", "hello-there"];
+        for expected in all_expected {
+            assert!(
+                json_text.contains(expected),
+                "Expected:\n{expected}\n\nGot:\n{json_text}",
+            );
+        }
+
+        // Test /channels/:id/logs endpoint
+        let channels_response: ChannelsJson =
+            serde_json::from_str(&json_text).expect("Failed to parse channels JSON");
+
+        if let Some(first_channel) = channels_response.channels.first() {
+            let logs_url = format!("http://localhost:6780/channels/{}/logs", first_channel.id);
+            let response = ureq::get(&logs_url)
+                .call()
+                .expect("Failed to call /channels/:id/logs endpoint");
+
+            assert_eq!(
+                response.status(),
+                200,
+                "Expected status 200 for /channels/:id/logs endpoint"
+            );
+        }
+
+        let _ = child.kill();
+        let _ = child.wait();
+    }
+}
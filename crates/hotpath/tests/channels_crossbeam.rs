@@ -166,6 +166,65 @@ pub mod tests {
         }
     }
 
+    #[test]
+    fn test_direct_output() {
+        use hotpath::json::{ChannelType, ChannelsJson};
+
+        let output = Command::new("cargo")
+            .args([
+                "run",
+                "-p",
+                "test-channels-crossbeam",
+                "--example",
+                "direct_crossbeam",
+                "--features",
+                "hotpath",
+            ])
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(
+            output.status.success(),
+            "Command failed with status: {}",
+            output.status
+        );
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(
+            stdout.contains("[Sender] Accepted: 3"),
+            "Expected exact channel capacity, got:\n{stdout}"
+        );
+
+        let json_line = stdout
+            .lines()
+            .find(|line| line.starts_with('{'))
+            .unwrap_or_else(|| panic!("Expected JSON output, got:\n{stdout}"));
+        let channels_json: ChannelsJson =
+            serde_json::from_str(json_line).expect("Failed to parse channels JSON");
+
+        let bounded = channels_json
+            .channels
+            .iter()
+            .find(|c| c.label == "direct-bounded")
+            .expect("Expected direct-bounded channel");
+        assert_eq!(bounded.channel_type, ChannelType::Bounded(3));
+        assert_eq!(bounded.state.as_str(), "active");
+        assert_eq!(bounded.sent_count, 3);
+        assert_eq!(bounded.received_count, 1);
+        assert_eq!(bounded.queued, 2);
+
+        let unbounded = channels_json
+            .channels
+            .iter()
+            .find(|c| c.label == "direct-unbounded")
+            .expect("Expected direct-unbounded channel");
+        assert_eq!(unbounded.channel_type, ChannelType::Unbounded);
+        assert_eq!(unbounded.state.as_str(), "closed");
+        assert_eq!(unbounded.sent_count, 5);
+        assert_eq!(unbounded.received_count, 5);
+        assert_eq!(unbounded.queued, 0);
+    }
+
     #[test]
     fn test_slow_consumer_no_panic() {
         let output = Command::new("cargo")
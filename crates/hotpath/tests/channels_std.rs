@@ -139,6 +139,176 @@ pub mod tests {
         }
     }
 
+    #[test]
+    fn test_direct_output() {
+        use hotpath::json::{ChannelType, ChannelsJson};
+
+        let output = Command::new("cargo")
+            .args([
+                "run",
+                "-p",
+                "test-channels-std",
+                "--example",
+                "direct_std",
+                "--features",
+                "hotpath",
+            ])
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(
+            output.status.success(),
+            "Command failed with status: {}",
+            output.status
+        );
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(
+            stdout.contains("[Sender] Accepted: 3"),
+            "Expected exact channel capacity, got:\n{stdout}"
+        );
+
+        let json_line = stdout
+            .lines()
+            .find(|line| line.starts_with('{'))
+            .unwrap_or_else(|| panic!("Expected JSON output, got:\n{stdout}"));
+        let channels_json: ChannelsJson =
+            serde_json::from_str(json_line).expect("Failed to parse channels JSON");
+
+        let bounded = channels_json
+            .channels
+            .iter()
+            .find(|c| c.label == "direct-bounded")
+            .expect("Expected direct-bounded channel");
+        assert_eq!(bounded.channel_type, ChannelType::Bounded(3));
+        assert_eq!(bounded.state.as_str(), "full");
+        assert_eq!(bounded.sent_count, 3);
+        assert_eq!(bounded.received_count, 0);
+        assert_eq!(bounded.queued, 3);
+
+        let unbounded = channels_json
+            .channels
+            .iter()
+            .find(|c| c.label == "direct-unbounded")
+            .expect("Expected direct-unbounded channel");
+        assert_eq!(unbounded.channel_type, ChannelType::Unbounded);
+        assert_eq!(unbounded.state.as_str(), "closed");
+        assert_eq!(unbounded.sent_count, 5);
+        assert_eq!(unbounded.received_count, 2);
+        assert_eq!(unbounded.queued, 3);
+
+        let proxied = channels_json
+            .channels
+            .iter()
+            .find(|c| c.label == "proxied")
+            .expect("Expected proxied channel");
+        assert_eq!(proxied.sent_count, 1);
+        assert_eq!(proxied.received_count, 1);
+    }
+
+    #[test]
+    fn test_latency_output() {
+        use hotpath::json::ChannelsJson;
+
+        let output = Command::new("cargo")
+            .args([
+                "run",
+                "-p",
+                "test-channels-std",
+                "--example",
+                "latency_std",
+                "--features",
+                "hotpath",
+            ])
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(
+            output.status.success(),
+            "Command failed with status: {}",
+            output.status
+        );
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let json_line = stdout
+            .lines()
+            .find(|line| line.starts_with('{'))
+            .unwrap_or_else(|| panic!("Expected JSON output, got:\n{stdout}"));
+        let channels_json: ChannelsJson =
+            serde_json::from_str(json_line).expect("Failed to parse channels JSON");
+
+        let channel = channels_json
+            .channels
+            .iter()
+            .find(|c| c.label == "slow-consumer")
+            .expect("Expected slow-consumer channel");
+        let latency = channel.latency.expect("Expected latency percentiles");
+
+        // Messages wait 0, 50, 100, 150 and 200ms for the consumer
+        assert!(latency.p50_ns >= 90_000_000, "Unexpected p50: {latency:?}");
+        assert!(latency.p99_ns >= 190_000_000, "Unexpected p99: {latency:?}");
+        assert!(latency.p50_ns <= latency.p95_ns && latency.p95_ns <= latency.p99_ns);
+    }
+
+    #[test]
+    fn test_backpressure_output() {
+        use hotpath::json::ChannelsJson;
+
+        let output = Command::new("cargo")
+            .args([
+                "run",
+                "-p",
+                "test-channels-std",
+                "--example",
+                "backpressure_std",
+                "--features",
+                "hotpath",
+            ])
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(
+            output.status.success(),
+            "Command failed with status: {}",
+            output.status
+        );
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let json_line = stdout
+            .lines()
+            .find(|line| line.starts_with('{'))
+            .unwrap_or_else(|| panic!("Expected JSON output, got:\n{stdout}"));
+        let channels_json: ChannelsJson =
+            serde_json::from_str(json_line).expect("Failed to parse channels JSON");
+
+        for label in ["direct", "proxied"] {
+            let channel = channels_json
+                .channels
+                .iter()
+                .find(|c| c.label == label)
+                .unwrap_or_else(|| panic!("Expected {label} channel"));
+            let backpressure = channel
+                .backpressure
+                .unwrap_or_else(|| panic!("Expected {label} backpressure"));
+
+            assert!(backpressure.blocked_count > 0, "{label}: {backpressure:?}");
+            assert!(
+                backpressure.blocked_total_ns >= backpressure.p99_ns,
+                "{label}: {backpressure:?}"
+            );
+            assert!(backpressure.p50_ns > 0, "{label}: {backpressure:?}");
+        }
+
+        // Only wrappers without proxies see the try_send calls of the producer
+        let direct = channels_json
+            .channels
+            .iter()
+            .find(|c| c.label == "direct")
+            .and_then(|c| c.backpressure)
+            .expect("Expected direct backpressure");
+        assert_eq!(direct.try_send_failures, 1);
+    }
+
     #[test]
     fn test_slow_consumer_no_panic() {
         let output = Command::new("cargo")
@@ -247,6 +417,33 @@ pub mod tests {
             );
         }
 
+        // Test /reset/channels endpoint, channels stay registered with zeroed counters
+        match ureq::get("http://localhost:6770/reset/channels").call() {
+            Err(ureq::Error::StatusCode(code)) => assert_eq!(code, 405),
+            other => panic!("Expected 405 for GET /reset/channels, got {other:?}"),
+        }
+
+        let response = ureq::post("http://localhost:6770/reset/channels")
+            .send_empty()
+            .expect("Failed to call /reset/channels endpoint");
+        assert_eq!(response.status(), 204);
+
+        let reset_response: ChannelsJson = ureq::get("http://localhost:6770/channels")
+            .call()
+            .expect("Failed to call /channels endpoint")
+            .body_mut()
+            .read_json()
+            .expect("Failed to parse channels JSON");
+
+        assert_eq!(
+            reset_response.channels.len(),
+            channels_response.channels.len()
+        );
+        for channel in &reset_response.channels {
+            assert_eq!(channel.sent_count, 0, "{}", channel.label);
+            assert_eq!(channel.received_count, 0, "{}", channel.label);
+        }
+
         let _ = child.kill();
         let _ = child.wait();
     }
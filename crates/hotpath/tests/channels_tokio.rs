@@ -161,6 +161,71 @@ pub mod tests {
         }
     }
 
+    #[test]
+    fn test_pubsub_output() {
+        use hotpath::json::{ChannelType, ChannelsJson};
+
+        let output = Command::new("cargo")
+            .args([
+                "run",
+                "-p",
+                "test-channels-tokio",
+                "--example",
+                "pubsub_tokio",
+                "--features",
+                "hotpath",
+            ])
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(
+            output.status.success(),
+            "Command failed with status: {}",
+            output.status
+        );
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let json_line = stdout
+            .lines()
+            .find(|line| line.starts_with('{'))
+            .unwrap_or_else(|| panic!("Expected JSON output, got:\n{stdout}"));
+        let channels_json: ChannelsJson =
+            serde_json::from_str(json_line).expect("Failed to parse channels JSON");
+
+        let events = channels_json
+            .channels
+            .iter()
+            .find(|c| c.label == "events")
+            .expect("Expected events channel");
+        assert_eq!(events.channel_type, ChannelType::Broadcast(4));
+        assert_eq!(events.sent_count, 10);
+        assert_eq!(events.subscribers.len(), 2);
+
+        let fast = &events.subscribers[0];
+        assert_eq!(fast.label, "#0");
+        assert_eq!(fast.received_count, 10);
+        assert_eq!(fast.lagged_count, 0);
+
+        let slow = &events.subscribers[1];
+        assert_eq!(slow.label, "slow");
+        assert_eq!(slow.received_count, 4);
+        assert_eq!(slow.lagged_count, 6);
+
+        let config = channels_json
+            .channels
+            .iter()
+            .find(|c| c.label == "config")
+            .expect("Expected config channel");
+        assert_eq!(config.channel_type, ChannelType::Watch);
+        assert_eq!(config.sent_count, 4);
+        assert_eq!(config.subscribers[0].received_count, 4);
+
+        let watcher = &config.subscribers[1];
+        assert_eq!(watcher.label, "watcher");
+        assert_eq!(watcher.state.as_str(), "closed");
+        assert_eq!(watcher.received_count, 3);
+    }
+
     #[test]
     fn test_iter_output() {
         let output = Command::new("cargo")
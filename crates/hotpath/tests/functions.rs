@@ -97,14 +97,14 @@ pub mod tests {
     }
 
     #[test]
-    fn test_unsupported_async_output() {
+    fn test_async_multithread_alloc_output() {
         let output = Command::new("cargo")
             .args([
                 "run",
                 "-p",
                 "test-tokio-async",
                 "--example",
-                "unsupported_async",
+                "async_multithread",
                 "--features",
                 "hotpath,hotpath-alloc",
             ])
@@ -112,14 +112,17 @@ pub mod tests {
             .expect("Failed to execute command");
         let stdout = String::from_utf8_lossy(&output.stdout);
 
-        let all_expected = ["N/A*", "only available for tokio current_thread"];
+        assert!(!stdout.contains("N/A*"), "Got:\n{stdout}");
 
-        for expected in all_expected {
-            assert!(
-                stdout.contains(expected),
-                "Expected:\n{expected}\n\nGot:\n{stdout}",
-            );
-        }
+        // Both vecs are allocated by every call, the second one after an await
+        let async_row = stdout
+            .lines()
+            .find(|line| line.contains("async_multithread::async_function"))
+            .unwrap_or_else(|| panic!("Missing async_function row, got:\n{stdout}"));
+        assert!(
+            async_row.contains("| 100 ") && async_row.contains("| 72 B "),
+            "Got:\n{stdout}"
+        );
     }
 
     #[test]
@@ -289,17 +292,342 @@ pub mod tests {
             String::from_utf8_lossy(&output.stderr)
         );
 
-        let all_expected = ["N/A*", "only available for tokio current_thread"];
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(!stdout.contains("N/A*"), "Got:\n{stdout}");
+
+        let async_row = stdout
+            .lines()
+            .find(|line| line.contains("basic_smol::async_function"))
+            .unwrap_or_else(|| panic!("Missing async_function row, got:\n{stdout}"));
+        assert!(async_row.contains("| 72 B "), "Got:\n{stdout}");
+    }
+
+    #[test]
+    fn test_realloc_output() {
+        use hotpath::json::FunctionsJson;
+        use hotpath::MetricType;
+
+        let output = Command::new("cargo")
+            .args([
+                "run",
+                "-p",
+                "test-tokio-async",
+                "--example",
+                "realloc",
+                "--features",
+                "hotpath,hotpath-alloc",
+            ])
+            .env("HOTPATH_DISABLE_HTTP", "1")
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(
+            output.status.success(),
+            "Process did not exit successfully.\n\nstderr:\n{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
 
         let stdout = String::from_utf8_lossy(&output.stdout);
-        for expected in all_expected {
-            assert!(
-                stdout.contains(expected),
-                "Expected:\n{expected}\n\nGot:\n{stdout}",
+        let json_line = stdout
+            .lines()
+            .find(|line| line.starts_with('{'))
+            .unwrap_or_else(|| panic!("Expected JSON output, got:\n{stdout}"));
+        let metrics: FunctionsJson =
+            serde_json::from_str(json_line).expect("Failed to parse JSON output");
+
+        assert_eq!(
+            metrics.headers(),
+            vec!["Function", "Calls", "Avg", "P95", "Total", "Reallocs", "Grown", "% Total"]
+        );
+
+        // Growing reallocations are not counted as allocations
+        let grow = &metrics.data.0["realloc::grow_vec"];
+        assert!(matches!(grow[1], MetricType::Alloc(8, _)), "{grow:?}");
+        assert!(matches!(grow[4], MetricType::CallsCount(30)), "{grow:?}");
+        assert!(matches!(grow[5], MetricType::Alloc(560, _)), "{grow:?}");
+
+        let zeroed = &metrics.data.0["realloc::zeroed_vec"];
+        assert!(
+            matches!(zeroed[1], MetricType::Alloc(1024, _)),
+            "{zeroed:?}"
+        );
+        assert!(matches!(zeroed[4], MetricType::CallsCount(0)), "{zeroed:?}");
+    }
+
+    #[test]
+    fn test_alloc_depth_overflow_output() {
+        let output = Command::new("cargo")
+            .args([
+                "run",
+                "-p",
+                "test-tokio-async",
+                "--example",
+                "deep_recursion",
+                "--features",
+                "hotpath,hotpath-alloc",
+            ])
+            .env("HOTPATH_DISABLE_HTTP", "1")
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(
+            output.status.success(),
+            "Process did not exit successfully.\n\nstderr:\n{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        // main takes the first frame, descend the next 62
+        assert!(
+            stdout.contains("39 calls nested deeper than 63 measured functions"),
+            "Got:\n{stdout}"
+        );
+        assert!(stdout.contains("deep_recursion::descend"), "Got:\n{stdout}");
+        // Exited threads give their slots back
+        assert!(!stdout.contains("per-thread stats"), "Got:\n{stdout}");
+    }
+
+    #[test]
+    fn test_sampling_output() {
+        use hotpath::json::FunctionsJson;
+        use hotpath::MetricType;
+
+        let output = Command::new("cargo")
+            .args([
+                "run",
+                "-p",
+                "test-tokio-async",
+                "--example",
+                "sampling",
+                "--features",
+                "hotpath,hotpath-alloc",
+            ])
+            .env("HOTPATH_DISABLE_HTTP", "1")
+            .env("HOTPATH_SAMPLE_RATE", "10")
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(
+            output.status.success(),
+            "Process did not exit successfully.\n\nstderr:\n{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let json_line = stdout
+            .lines()
+            .find(|line| line.starts_with('{'))
+            .unwrap_or_else(|| panic!("Expected JSON output, got:\n{stdout}"));
+        let metrics: FunctionsJson =
+            serde_json::from_str(json_line).expect("Failed to parse JSON output");
+
+        assert!(
+            metrics.description.contains("4 sampled functions"),
+            "{}",
+            metrics.description
+        );
+
+        // Calls are exact and totals are scaled up from the measured calls
+        let hot = &metrics.data.0["sampling::hot_path"];
+        assert!(matches!(hot[0], MetricType::CallsCount(10_000)), "{hot:?}");
+        assert!(matches!(hot[3], MetricType::Alloc(640_000, _)), "{hot:?}");
+
+        // Sampled with HOTPATH_SAMPLE_RATE
+        let warm = &metrics.data.0["sampling::warm_path"];
+        assert!(matches!(warm[0], MetricType::CallsCount(1_000)), "{warm:?}");
+        assert!(matches!(warm[3], MetricType::Alloc(256_000, _)), "{warm:?}");
+
+        let sampled_async = &metrics.data.0["sampling::sampled_async"];
+        assert!(
+            matches!(sampled_async[0], MetricType::CallsCount(100)),
+            "{sampled_async:?}"
+        );
+
+        // Calls made inside skipped calls stay nested under the sampled function
+        let output = Command::new("cargo")
+            .args([
+                "run",
+                "-p",
+                "test-tokio-async",
+                "--example",
+                "sampling",
+                "--features",
+                "hotpath",
+            ])
+            .env("HOTPATH_DISABLE_HTTP", "1")
+            .output()
+            .expect("Failed to execute command");
+        assert!(
+            output.status.success(),
+            "Process did not exit successfully.\n\nstderr:\n{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let json_line = stdout
+            .lines()
+            .find(|line| line.starts_with('{'))
+            .unwrap_or_else(|| panic!("Expected JSON output, got:\n{stdout}"));
+        let metrics: FunctionsJson =
+            serde_json::from_str(json_line).expect("Failed to parse JSON output");
+
+        let main = metrics
+            .call_tree
+            .iter()
+            .find(|n| n.name == "sampling::main")
+            .unwrap_or_else(|| panic!("Expected sampling::main root, got:\n{stdout}"));
+        assert!(
+            !main
+                .children
+                .iter()
+                .any(|n| n.name == "sampling::measured_child"),
+            "Expected measured_child nested in sampled_parent, got:\n{stdout}"
+        );
+        let parent = main
+            .children
+            .iter()
+            .find(|n| n.name == "sampling::sampled_parent")
+            .unwrap_or_else(|| panic!("Expected sampled_parent in main, got:\n{stdout}"));
+        assert_eq!(parent.calls, 10);
+        let child = parent
+            .children
+            .iter()
+            .find(|n| n.name == "sampling::measured_child")
+            .unwrap_or_else(|| panic!("Expected measured_child in sampled_parent, got:\n{stdout}"));
+        assert_eq!(child.calls, 100);
+    }
+
+    #[test]
+    fn test_tags_endpoint() {
+        use hotpath::{json::FunctionsJson, MetricType};
+        use std::{thread::sleep, time::Duration};
+
+        let mut child = Command::new("cargo")
+            .args([
+                "run",
+                "-p",
+                "test-tokio-async",
+                "--example",
+                "tags",
+                "--features",
+                "hotpath",
+            ])
+            .env("HOTPATH_HTTP_PORT", "6778")
+            .env("TEST_SLEEP_SECONDS", "10")
+            .spawn()
+            .expect("Failed to spawn command");
+
+        let fetch = |query: &str| -> Result<FunctionsJson, String> {
+            let body = ureq::get(&format!("http://localhost:6778/functions_timing{query}"))
+                .call()
+                .map_err(|e| format!("Request error: {}", e))?
+                .body_mut()
+                .read_to_string()
+                .map_err(|e| e.to_string())?;
+            serde_json::from_str(&body).map_err(|e| format!("{e}: {body}"))
+        };
+
+        let mut all = None;
+        let mut last_error = None;
+
+        for _attempt in 0..60 {
+            sleep(Duration::from_millis(500));
+
+            match fetch("") {
+                // Wait for the example to make all its calls
+                Ok(json) if json.tags.len() == 6 => {
+                    all = Some(json);
+                    break;
+                }
+                Ok(json) => last_error = Some(format!("Tags so far: {:?}", json.tags)),
+                Err(e) => last_error = Some(e),
+            }
+        }
+
+        let all = all.unwrap_or_else(|| {
+            let _ = child.kill();
+            panic!("Failed to fetch all tags: {:?}", last_error)
+        });
+
+        let calls = |json: &FunctionsJson, name: &str| match json.data.0.get(name) {
+            Some(row) => match row[0] {
+                MetricType::CallsCount(calls) => calls,
+                _ => 0,
+            },
+            None => 0,
+        };
+        let tagged = [
+            ("/checkout", "tags::handle_request", 10),
+            ("/search", "tags::handle_request", 20),
+            ("acme", "tags::load_account", 2),
+            ("globex", "tags::load_account", 1),
+            ("eu", "replicate", 1),
+            ("us", "replicate", 2),
+        ];
+        let results = tagged.map(|(tag, _, _)| fetch(&format!("?tag={}", tag.replace('/', "%2F"))));
+
+        let _ = child.kill();
+        let _ = child.wait();
+
+        assert_eq!(calls(&all, "tags::handle_request"), 30);
+        assert_eq!(
+            all.tags,
+            ["/checkout", "/search", "acme", "eu", "globex", "us"],
+            "Got tags: {:?}",
+            all.tags
+        );
+
+        for ((tag, name, expected), result) in tagged.iter().zip(results) {
+            let json = result.unwrap_or_else(|e| panic!("Failed to fetch tag {tag}: {e}"));
+            assert_eq!(
+                calls(&json, name),
+                *expected,
+                "Calls of {name} tagged {tag}"
+            );
+            assert_eq!(
+                json.data.0.len(),
+                1,
+                "Expected only {name} for tag {tag}, got: {:?}",
+                json.data.0.keys()
             );
         }
     }
 
+    #[test]
+    fn test_custom_allocator_output() {
+        let output = Command::new("cargo")
+            .args([
+                "run",
+                "-p",
+                "test-tokio-async",
+                "--example",
+                "custom_allocator",
+                "--features",
+                "hotpath,hotpath-alloc-custom",
+            ])
+            .env("HOTPATH_DISABLE_HTTP", "1")
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(
+            output.status.success(),
+            "Process did not exit successfully.\n\nstderr:\n{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(
+            stdout.contains("shipped allocator used: true"),
+            "Got:\n{stdout}"
+        );
+
+        let allocate_row = stdout
+            .lines()
+            .find(|line| line.contains("custom_allocator::allocate"))
+            .unwrap_or_else(|| panic!("Missing allocate row, got:\n{stdout}"));
+        assert!(allocate_row.contains("| 40.0 KB "), "Got:\n{stdout}");
+    }
+
     #[test]
     fn test_all_features_output() {
         let output = Command::new("cargo")
@@ -369,7 +697,7 @@ pub mod tests {
         let report_content = fs::read_to_string(report_path).expect("Failed to read report file");
 
         let expected_content = [
-            "Function, Calls, Avg, P50, P90, P95, Total, % Total",
+            "Function, Calls, Avg, P50, P90, P95, Total, Self, % Total",
             "Functions measured: 4",
             "csv_file_reporter::async_function, 100",
             "csv_file_reporter::sync_function, 100",
@@ -413,7 +741,7 @@ pub mod tests {
 
         let expected_content = [
             "HotPath Report for: main",
-            "Headers: Function, Calls, Avg, P50, P90, P95, Total, % Total",
+            "Headers: Function, Calls, Avg, P50, P90, P95, Total, Self, % Total",
             "tracing_reporter::async_function, 100",
             "tracing_reporter::sync_function, 100",
             "custom_block, 100",
@@ -475,6 +803,43 @@ pub mod tests {
         }
     }
 
+    #[test]
+    fn test_sort_by_self_time_output() {
+        let output = Command::new("cargo")
+            .args([
+                "run",
+                "-p",
+                "test-tokio-async",
+                "--example",
+                "sort_self",
+                "--features",
+                "hotpath",
+            ])
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(
+            output.status.success(),
+            "Process did not exit successfully.\n\nstderr:\n{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+
+        // Only the function with the highest self time fits in the limit
+        assert!(
+            stdout.contains("sort_self::inner") && stdout.contains("Self"),
+            "Expected sort_self::inner with a Self column, got:\n{stdout}",
+        );
+
+        for not_expected in ["sort_self::outer", "| sort_self::main"] {
+            assert!(
+                !stdout.contains(not_expected),
+                "Not expected:\n{not_expected}\n\nGot:\n{stdout}"
+            );
+        }
+    }
+
     #[test]
     fn test_measure_all_mod_output() {
         let output = Command::new("cargo")
@@ -722,6 +1087,94 @@ pub mod tests {
         let timing_response: FunctionsJson =
             serde_json::from_str(&timing_json).expect("Failed to parse timing JSON");
 
+        // Test /functions_timing/tree endpoint
+        let tree_json = ureq::get("http://localhost:6775/functions_timing/tree")
+            .call()
+            .expect("Failed to call /functions_timing/tree endpoint")
+            .body_mut()
+            .read_to_string()
+            .expect("Failed to read tree response body");
+
+        let tree_response: hotpath::json::FunctionsTreeJson =
+            serde_json::from_str(&tree_json).expect("Failed to parse tree JSON");
+        let main_node = tree_response
+            .roots
+            .iter()
+            .find(|n| n.name == "basic::main")
+            .unwrap_or_else(|| panic!("Expected basic::main root, got:\n{tree_json}"));
+        for expected in timing_expected {
+            assert!(
+                main_node.children.iter().any(|n| n.name == expected),
+                "Expected {expected} nested in basic::main, got:\n{tree_json}",
+            );
+        }
+
+        // Test /functions_timing/folded endpoint
+        let folded = ureq::get("http://localhost:6775/functions_timing/folded")
+            .call()
+            .expect("Failed to call /functions_timing/folded endpoint")
+            .body_mut()
+            .read_to_string()
+            .expect("Failed to read folded response body");
+
+        for expected in timing_expected {
+            let stack = format!("basic::main;{expected} ");
+            assert!(
+                folded.lines().any(|line| line.starts_with(&stack)),
+                "Expected {stack} stack, got:\n{folded}",
+            );
+        }
+
+        // Test /metrics endpoint
+        let mut metrics_response = ureq::get("http://localhost:6775/metrics")
+            .call()
+            .expect("Failed to call /metrics endpoint");
+        let content_type = metrics_response
+            .headers()
+            .get("Content-Type")
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or_default()
+            .to_string();
+        assert!(
+            content_type.starts_with("application/openmetrics-text"),
+            "Unexpected /metrics content type: {content_type}"
+        );
+        let metrics = metrics_response
+            .body_mut()
+            .read_to_string()
+            .expect("Failed to read metrics response body");
+
+        assert!(metrics.ends_with("# EOF\n"), "Got:\n{metrics}");
+        for expected in [
+            "# TYPE hotpath_function_duration_seconds histogram",
+            "hotpath_function_calls_total{function=\"basic::sync_function\"}",
+            "hotpath_function_duration_seconds_bucket{function=\"basic::sync_function\",le=\"+Inf\"}",
+            "hotpath_function_self_seconds_total{function=\"custom_block\"}",
+            "hotpath_function_alloc_bytes_total{function=\"basic::sync_function\"}",
+        ] {
+            assert!(
+                metrics.contains(expected),
+                "Expected:\n{expected}\n\nGot:\n{metrics}",
+            );
+        }
+
+        // Test /runtime endpoint, basic example runs on a current_thread runtime
+        let runtime_json = ureq::get("http://localhost:6775/runtime")
+            .call()
+            .expect("Failed to call /runtime endpoint")
+            .body_mut()
+            .read_to_string()
+            .expect("Failed to read runtime response body");
+        let runtime: hotpath::json::RuntimeJson =
+            serde_json::from_str(&runtime_json).expect("Failed to parse runtime JSON");
+        assert_eq!(runtime.flavor, "CurrentThread", "Got:\n{runtime_json}");
+        assert_eq!(runtime.num_workers, 1, "Got:\n{runtime_json}");
+        assert_eq!(runtime.workers.len(), 1, "Got:\n{runtime_json}");
+        assert!(
+            metrics.contains("hotpath_runtime_worker_busy_seconds_total{worker=\"0\"}"),
+            "Got:\n{metrics}"
+        );
+
         // Test /functions_alloc endpoint
         let mut alloc_response = ureq::get("http://localhost:6775/functions_alloc")
             .call()
@@ -750,15 +1203,68 @@ pub mod tests {
         let _alloc_response: FunctionsJson =
             serde_json::from_str(&alloc_json).expect("Failed to parse alloc JSON");
 
-        // Test function logs endpoints using first function from timing response
-        if let Some(first_function_name) = timing_response.data.0.keys().next() {
-            use base64::Engine;
-            let encoded_name =
-                base64::engine::general_purpose::STANDARD.encode(first_function_name.as_bytes());
-
-            // Test timing logs endpoint
-            let timing_logs_url = format!(
-                "http://localhost:6775/functions_timing/{}/logs",
+        // Test rolling window queries, all calls are made within the first minute
+        // so the window matches the since-start stats
+        let calls = |url: &str| {
+            let json = ureq::get(url)
+                .call()
+                .unwrap_or_else(|e| panic!("Failed to call {url}: {e}"))
+                .body_mut()
+                .read_to_string()
+                .expect("Failed to read response body");
+            let value: serde_json::Value =
+                serde_json::from_str(&json).expect("Failed to parse JSON");
+            let total_elapsed = value["total_elapsed"].as_u64().unwrap();
+            (
+                value["output"]["basic::sync_function"]["calls"].clone(),
+                total_elapsed,
+            )
+        };
+        for endpoint in ["functions_timing", "functions_alloc"] {
+            let since_start_url = format!("http://localhost:6775/{endpoint}");
+            let window_url = format!("{since_start_url}?window=60s");
+            let mut result = None;
+            for _attempt in 0..20 {
+                let (since_start_calls, _) = calls(&since_start_url);
+                let (window_calls, window_elapsed) = calls(&window_url);
+                result = Some((since_start_calls, window_calls, window_elapsed));
+                if result.as_ref().is_some_and(|(a, b, _)| a == b) {
+                    break;
+                }
+                sleep(Duration::from_millis(250));
+            }
+            let (since_start_calls, window_calls, window_elapsed) = result.unwrap();
+            assert!(window_calls.as_u64().unwrap() > 0);
+            assert_eq!(window_calls, since_start_calls, "/{endpoint}?window=60s");
+            assert!(window_elapsed <= 65_000_000_000);
+        }
+
+        match ureq::get("http://localhost:6775/functions_timing?window=10m").call() {
+            Err(ureq::Error::StatusCode(code)) => assert_eq!(code, 400),
+            other => panic!("Expected 400 for a window above 5m, got {other:?}"),
+        }
+
+        // Malformed windows, including ones overflowing when converted to seconds
+        for window in ["abc", "10x", "5124095576030431h"] {
+            match ureq::get(format!(
+                "http://localhost:6775/functions_timing?window={window}"
+            ))
+            .call()
+            {
+                Err(ureq::Error::StatusCode(code)) => assert_eq!(code, 400, "window={window}"),
+                other => panic!("Expected 400 for window={window}, got {other:?}"),
+            }
+        }
+
+        // Test function logs endpoints using first function from timing response
+        if let Some(first_function_name) = timing_response.data.0.keys().next() {
+            use base64::Engine;
+            let encoded_name =
+                base64::engine::general_purpose::STANDARD.encode(first_function_name.as_bytes());
+
+            // Test timing logs endpoint
+            let timing_logs_url = format!(
+                "http://localhost:6775/functions_timing/{}/logs",
                 encoded_name
             );
             let timing_logs_response = ureq::get(&timing_logs_url)
@@ -787,10 +1293,581 @@ pub mod tests {
             );
         }
 
+        // sync_function makes two allocations of 76 bytes per call
+        {
+            use base64::Engine;
+            use hotpath::json::FunctionLogsJson;
+
+            let encoded_name =
+                base64::engine::general_purpose::STANDARD.encode("basic::sync_function");
+            let logs: FunctionLogsJson = ureq::get(&format!(
+                "http://localhost:6775/functions_alloc/{}/logs",
+                encoded_name
+            ))
+            .call()
+            .expect("Failed to call /functions_alloc/:name/logs endpoint")
+            .body_mut()
+            .read_json()
+            .expect("Failed to parse alloc logs JSON");
+
+            assert_eq!(logs.size_classes.len(), 1, "{:?}", logs.size_classes);
+            let class = &logs.size_classes[0];
+            assert_eq!(class.min_bytes, 64);
+            assert_eq!(class.max_bytes, Some(128));
+            assert_eq!(class.count, 2 * logs.count as u64);
+        }
+
+        // Test /reset/functions endpoint, all calls were made before the reset
+        let response = ureq::post("http://localhost:6775/reset/functions")
+            .send_empty()
+            .expect("Failed to call /reset/functions endpoint");
+        assert_eq!(response.status(), 204);
+
+        let (reset_calls, _) = calls("http://localhost:6775/functions_timing");
+        assert!(reset_calls.is_null(), "Got {reset_calls} calls after reset");
+
         let _ = child.kill();
         let _ = child.wait();
     }
 
+    #[test]
+    fn test_call_tree_output() {
+        use hotpath::json::FunctionsJson;
+
+        let output = Command::new("cargo")
+            .args([
+                "run",
+                "-p",
+                "test-tokio-async",
+                "--example",
+                "call_tree",
+                "--features",
+                "hotpath",
+            ])
+            .env("HOTPATH_DISABLE_HTTP", "1")
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(
+            output.status.success(),
+            "Process did not exit successfully.\n\nstderr:\n{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let json_line = stdout
+            .lines()
+            .find(|line| line.starts_with('{'))
+            .unwrap_or_else(|| panic!("Expected JSON output, got:\n{stdout}"));
+        let metrics: FunctionsJson =
+            serde_json::from_str(json_line).expect("Failed to parse JSON output");
+
+        let find = |nodes: &[hotpath::CallTreeNode], name: &str| {
+            nodes
+                .iter()
+                .find(|n| n.name == format!("call_tree::{name}"))
+                .cloned()
+                .unwrap_or_else(|| panic!("Expected {name} in call tree:\n{stdout}"))
+        };
+
+        // Spawned tasks are not nested in main
+        let spawned = find(&metrics.call_tree, "handle_request");
+        assert_eq!(spawned.calls, 10);
+        let spawned_parse = find(&spawned.children, "parse");
+        assert_eq!(spawned_parse.calls, 20);
+        assert_eq!(find(&spawned_parse.children, "decode").calls, 20);
+
+        let main = find(&metrics.call_tree, "main");
+        let awaited = find(&main.children, "handle_request");
+        assert_eq!(awaited.calls, 5);
+        assert!(awaited.self_ns < awaited.total_ns);
+        let awaited_parse = find(&awaited.children, "parse");
+        assert_eq!(awaited_parse.calls, 10);
+        assert_eq!(find(&awaited_parse.children, "decode").calls, 10);
+
+        // parse only holds the calls made in each context
+        let batch = find(&main.children, "batch_import");
+        let batch_parse = find(&batch.children, "parse");
+        assert_eq!(batch_parse.calls, 20);
+        assert_eq!(find(&batch_parse.children, "decode").calls, 20);
+        assert!(batch.self_ns < batch.total_ns);
+        assert!(batch_parse.total_ns <= batch.total_ns);
+        assert!(awaited_parse.total_ns <= awaited.total_ns);
+    }
+
+    #[test]
+    fn test_folded_output() {
+        let features = ["hotpath", "hotpath,hotpath-alloc"];
+        for features in features {
+            let output = Command::new("cargo")
+                .args([
+                    "run",
+                    "-p",
+                    "test-tokio-async",
+                    "--example",
+                    "folded",
+                    "--features",
+                    features,
+                ])
+                .env("HOTPATH_DISABLE_HTTP", "1")
+                .output()
+                .expect("Failed to execute command");
+
+            assert!(
+                output.status.success(),
+                "Process did not exit successfully.\n\nstderr:\n{}",
+                String::from_utf8_lossy(&output.stderr)
+            );
+
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            let stacks = [
+                "folded::main",
+                "folded::main;folded::handle_request",
+                "folded::main;folded::handle_request;folded::parse",
+                "folded::main;folded::batch_import",
+                "folded::main;folded::batch_import;folded::parse",
+            ];
+
+            for stack in stacks {
+                let line = stdout
+                    .lines()
+                    .find(|line| line.rsplit_once(' ').map(|(s, _)| s) == Some(stack))
+                    .unwrap_or_else(|| panic!("Expected {stack} stack, got:\n{stdout}"));
+                let value: u64 = line
+                    .rsplit_once(' ')
+                    .and_then(|(_, v)| v.parse().ok())
+                    .unwrap_or_else(|| panic!("Expected numeric value in {line:?}"));
+                assert!(value > 0, "Expected non-zero self time in {line:?}");
+            }
+
+            assert!(
+                stdout
+                    .lines()
+                    .any(|line| line.starts_with("folded::main;folded::walk ")),
+                "Expected walk stack, got:\n{stdout}"
+            );
+            assert!(
+                !stdout.contains("folded::walk;folded::walk"),
+                "Expected recursion to be collapsed, got:\n{stdout}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_trace_file_output() {
+        let features = ["hotpath", "hotpath,hotpath-alloc"];
+        for features in features {
+            let trace_path =
+                std::env::temp_dir().join(format!("hotpath_trace_{}.json", std::process::id()));
+
+            let output = Command::new("cargo")
+                .args([
+                    "run",
+                    "-p",
+                    "test-tokio-async",
+                    "--example",
+                    "trace",
+                    "--features",
+                    features,
+                ])
+                .env("HOTPATH_DISABLE_HTTP", "1")
+                .env("HOTPATH_TRACE_FILE", &trace_path)
+                .output()
+                .expect("Failed to execute command");
+
+            assert!(
+                output.status.success(),
+                "Process did not exit successfully.\n\nstderr:\n{}",
+                String::from_utf8_lossy(&output.stderr)
+            );
+
+            let contents = std::fs::read_to_string(&trace_path).expect("Trace file not written");
+            let _ = std::fs::remove_file(&trace_path);
+            let events: Vec<serde_json::Value> =
+                serde_json::from_str(&contents).expect("Trace file is not a JSON array");
+
+            let lane = |name: &str| {
+                events
+                    .iter()
+                    .find(|e| e["ph"] == "M" && e["args"]["name"] == name)
+                    .and_then(|e| e["tid"].as_u64())
+                    .unwrap_or_else(|| panic!("Expected {name} lane in:\n{contents}"))
+            };
+            let spans = |name: &str, tid: u64| {
+                events
+                    .iter()
+                    .filter(|e| e["ph"] == "X" && e["name"] == name && e["tid"] == tid)
+                    .collect::<Vec<_>>()
+            };
+
+            let main_tid = lane("main");
+            let worker_tid = lane("worker");
+            lane("channel requests");
+
+            assert_eq!(spans("trace::main", main_tid).len(), 1);
+            assert_eq!(spans("trace::handle_request", main_tid).len(), 3);
+            assert_eq!(spans("trace::parse", main_tid).len(), 3);
+            assert_eq!(spans("trace::parse", worker_tid).len(), 3);
+
+            let polls = spans("examples/trace.rs:28", main_tid);
+            let ready_polls = polls
+                .iter()
+                .filter(|e| e["args"]["result"] == "ready")
+                .count();
+            assert_eq!(ready_polls, 3, "Expected 3 completed future polls");
+
+            for event in events.iter().filter(|e| e["ph"] == "X") {
+                assert!(
+                    event["ts"].as_f64().is_some() && event["dur"].as_f64().is_some(),
+                    "Expected ts and dur in {event}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_otlp_export() {
+        use std::io::{BufRead, BufReader, Read, Write};
+        use std::net::TcpListener;
+        use std::sync::mpsc;
+
+        // Minimal stand-in for an OTLP/HTTP collector
+        let listener = TcpListener::bind("127.0.0.1:0").expect("Failed to bind receiver");
+        let port = listener.local_addr().unwrap().port();
+        let (body_tx, body_rx) = mpsc::channel::<(String, String)>();
+
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let Ok(mut stream) = stream else { break };
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+
+                let mut request_line = String::new();
+                reader.read_line(&mut request_line).unwrap();
+                let mut content_length = 0;
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if line.trim().is_empty() {
+                        break;
+                    }
+                    if let Some((name, value)) = line.split_once(':') {
+                        if name.eq_ignore_ascii_case("content-length") {
+                            content_length = value.trim().parse().unwrap();
+                        }
+                    }
+                }
+                let mut body = vec![0; content_length];
+                reader.read_exact(&mut body).unwrap();
+
+                let _ = stream.write_all(
+                    b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\nConnection: close\r\n\r\n{}",
+                );
+                let _ = body_tx.send((request_line, String::from_utf8(body).unwrap()));
+            }
+        });
+
+        let output = Command::new("cargo")
+            .args([
+                "run",
+                "-p",
+                "test-tokio-async",
+                "--example",
+                "otlp",
+                "--features",
+                "hotpath,otlp",
+            ])
+            .env("HOTPATH_DISABLE_HTTP", "1")
+            .env("OTLP_ENDPOINT", format!("http://127.0.0.1:{port}"))
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(
+            output.status.success(),
+            "Process did not exit successfully.\n\nstderr:\n{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+
+        let requests: Vec<(String, String)> = body_rx.try_iter().collect();
+        assert!(
+            requests.len() >= 2,
+            "Expected periodic and final exports, got {} requests",
+            requests.len()
+        );
+
+        for (request_line, _) in &requests {
+            assert!(
+                request_line.starts_with("POST /v1/metrics "),
+                "Unexpected request: {request_line}"
+            );
+        }
+
+        let (_, last_body) = requests.last().unwrap();
+        let request: serde_json::Value =
+            serde_json::from_str(last_body).expect("Export body is not JSON");
+        let resource = &request["resourceMetrics"][0];
+        assert!(
+            resource["resource"]["attributes"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .any(|a| a["key"] == "service.name" && a["value"]["stringValue"] == "otlp-example"),
+            "Missing service.name in {request}"
+        );
+
+        let metrics = resource["scopeMetrics"][0]["metrics"].as_array().unwrap();
+        let calls = metrics
+            .iter()
+            .find(|m| m["name"] == "hotpath.function.calls")
+            .unwrap_or_else(|| panic!("Missing hotpath.function.calls in {request}"));
+        let handle_request_calls = calls["sum"]["dataPoints"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|p| p["attributes"][0]["value"]["stringValue"] == "otlp::handle_request")
+            .unwrap_or_else(|| panic!("Missing otlp::handle_request data point in {calls}"));
+        assert_eq!(handle_request_calls["asInt"], "20");
+
+        assert!(
+            metrics
+                .iter()
+                .any(|m| m["name"] == "hotpath.function.duration" && m["unit"] == "ns"),
+            "Missing hotpath.function.duration in {request}"
+        );
+    }
+
+    #[test]
+    fn test_report_interval_output() {
+        // (example, report every interval only covers calls since the previous one)
+        let cases = [("report_interval", false), ("report_delta", true)];
+        for (example, delta) in cases {
+            let output = Command::new("cargo")
+                .args([
+                    "run",
+                    "-p",
+                    "test-tokio-async",
+                    "--example",
+                    example,
+                    "--features",
+                    "hotpath",
+                ])
+                .env("HOTPATH_DISABLE_HTTP", "1")
+                .output()
+                .expect("Failed to execute command");
+
+            assert!(
+                output.status.success(),
+                "Process did not exit successfully.\n\nstderr:\n{}",
+                String::from_utf8_lossy(&output.stderr)
+            );
+
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            let calls: Vec<u64> = stdout
+                .lines()
+                .filter(|line| line.starts_with('{'))
+                .map(|line| {
+                    let report: serde_json::Value =
+                        serde_json::from_str(line).expect("Failed to parse JSON report");
+                    report["output"][format!("{example}::handle_request")]["calls"]
+                        .as_u64()
+                        .unwrap_or(0)
+                })
+                .collect();
+
+            assert!(
+                calls.len() >= 3,
+                "Expected periodic reports before the final one, got:\n{stdout}"
+            );
+
+            let (final_calls, periodic_calls) = calls.split_last().unwrap();
+            assert_eq!(*final_calls, 20);
+            if delta {
+                assert!(periodic_calls.iter().sum::<u64>() <= 20);
+                assert!(periodic_calls.iter().all(|c| *c < 20));
+            } else {
+                assert!(periodic_calls.windows(2).all(|w| w[0] <= w[1]));
+                assert!(periodic_calls.iter().any(|c| *c > 0));
+            }
+        }
+    }
+
+    #[test]
+    fn test_reset_output() {
+        let output = Command::new("cargo")
+            .args([
+                "run",
+                "-p",
+                "test-tokio-async",
+                "--example",
+                "reset",
+                "--features",
+                "hotpath",
+            ])
+            .env("HOTPATH_DISABLE_HTTP", "1")
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(
+            output.status.success(),
+            "Process did not exit successfully.\n\nstderr:\n{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let report: serde_json::Value = stdout
+            .lines()
+            .find(|line| line.starts_with('{'))
+            .and_then(|line| serde_json::from_str(line).ok())
+            .unwrap_or_else(|| panic!("Expected a JSON report, got:\n{stdout}"));
+
+        for function in ["reset::handle_request", "reset::parse"] {
+            assert_eq!(
+                report["output"][function]["calls"], 5,
+                "Expected only calls after the reset for {function}, got:\n{stdout}"
+            );
+        }
+
+        let total_elapsed = report["total_elapsed"].as_u64().unwrap();
+        assert!(
+            total_elapsed < 300_000_000,
+            "Expected elapsed time since the reset, got {total_elapsed}ns"
+        );
+    }
+
+    #[test]
+    fn test_dyn_labels_output() {
+        let output = Command::new("cargo")
+            .args([
+                "run",
+                "-p",
+                "test-tokio-async",
+                "--example",
+                "dyn_labels",
+                "--features",
+                "hotpath",
+            ])
+            .env("HOTPATH_DISABLE_HTTP", "1")
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(
+            output.status.success(),
+            "Process did not exit successfully.\n\nstderr:\n{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let report: serde_json::Value = stdout
+            .lines()
+            .find(|line| line.starts_with('{'))
+            .and_then(|line| serde_json::from_str(line).ok())
+            .unwrap_or_else(|| panic!("Expected a JSON report, got:\n{stdout}"));
+
+        let expected_calls = [
+            ("plugin::thumbnails", 10),
+            ("plugin::search", 10),
+            ("plugin::audit", 10),
+            ("sql::0", 1),
+            ("sql::252", 1),
+            // 3 plugins and 253 statements fill the 256 labels
+            ("hotpath::other", 47),
+        ];
+        for (label, calls) in expected_calls {
+            assert_eq!(
+                report["output"][label]["calls"], calls,
+                "Unexpected calls of {label}, got:\n{stdout}"
+            );
+        }
+        assert!(
+            report["output"].get("sql::253").is_none(),
+            "Expected sql::253 measured as hotpath::other, got:\n{stdout}"
+        );
+    }
+
+    #[test]
+    fn test_spans_output() {
+        let output = Command::new("cargo")
+            .args([
+                "run",
+                "-p",
+                "test-tokio-async",
+                "--example",
+                "spans",
+                "--features",
+                "hotpath",
+            ])
+            .env("HOTPATH_DISABLE_HTTP", "1")
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(
+            output.status.success(),
+            "Process did not exit successfully.\n\nstderr:\n{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let report: serde_json::Value = stdout
+            .lines()
+            .find(|line| line.starts_with('{'))
+            .and_then(|line| serde_json::from_str(line).ok())
+            .unwrap_or_else(|| panic!("Expected a JSON report, got:\n{stdout}"));
+
+        for (span, calls, min_avg_ns) in [
+            ("upload", 4, 5_000_000),
+            ("session", 1, 2_000_000),
+            ("request", 1, 1_000_000),
+        ] {
+            let stats = &report["output"][span];
+            assert_eq!(
+                stats["calls"], calls,
+                "Unexpected calls of {span}:\n{stdout}"
+            );
+            assert!(
+                stats["avg"].as_u64().unwrap_or(0) >= min_avg_ns,
+                "Expected {span} to last until finished, got:\n{stdout}"
+            );
+        }
+
+        // Spans are attributed to the measured function that started them
+        let main_node = report["call_tree"]
+            .as_array()
+            .and_then(|roots| roots.iter().find(|n| n["name"] == "spans::main"))
+            .unwrap_or_else(|| panic!("Expected spans::main root, got:\n{stdout}"));
+        let child = |node: &serde_json::Value, name: &str| {
+            node["children"]
+                .as_array()
+                .and_then(|children| children.iter().find(|n| n["name"] == name))
+                .cloned()
+                .unwrap_or_else(|| {
+                    panic!("Expected {name} nested in {}, got:\n{stdout}", node["name"])
+                })
+        };
+        child(main_node, "session");
+        let start_upload = child(main_node, "spans::start_upload");
+        assert_eq!(child(&start_upload, "upload")["calls"], 4);
+
+        // The example drops a span during thread teardown, which must not abort
+        let output = Command::new("cargo")
+            .args([
+                "run",
+                "-p",
+                "test-tokio-async",
+                "--example",
+                "spans",
+                "--features",
+                "hotpath,hotpath-alloc",
+            ])
+            .env("HOTPATH_DISABLE_HTTP", "1")
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(
+            output.status.success(),
+            "Process did not exit successfully.\n\nstderr:\n{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+
     #[test]
     fn test_main_timeout_output() {
         let output = Command::new("cargo")
@@ -864,6 +1941,108 @@ pub mod tests {
         }
     }
 
+    #[test]
+    fn test_alloc_retained_endpoint() {
+        use hotpath::json::FunctionsRetainedJson;
+        use std::io::{BufRead, BufReader};
+        use std::process::Stdio;
+        use std::{thread::sleep, time::Duration};
+
+        let mut child = Command::new("cargo")
+            .args([
+                "run",
+                "-p",
+                "test-tokio-async",
+                "--example",
+                "retained",
+                "--features",
+                "hotpath,hotpath-alloc-retained",
+            ])
+            .env("HOTPATH_HTTP_PORT", "6777")
+            .env("TEST_SLEEP_SECONDS", "10")
+            .stdout(Stdio::piped())
+            .spawn()
+            .expect("Failed to spawn command");
+
+        // The example prints a line once its loop is done, however long the build takes
+        let stdout = child.stdout.take().expect("Failed to capture stdout");
+        // Kept open until the child is killed, so its later output doesn't hit a closed pipe
+        let mut lines = BufReader::new(stdout).lines();
+        let ready = lines
+            .by_ref()
+            .map_while(Result::ok)
+            .any(|line| line.contains("[retained] ready"));
+        assert!(ready, "Example exited before it was ready");
+
+        let mut retained: Option<FunctionsRetainedJson> = None;
+        let mut last_error = None;
+
+        // Measurements still have to be drained by the profiler worker
+        for _attempt in 0..20 {
+            sleep(Duration::from_millis(100));
+
+            match ureq::get("http://localhost:6777/functions_alloc/retained").call() {
+                Ok(mut response) => {
+                    let body = response
+                        .body_mut()
+                        .read_to_string()
+                        .expect("Failed to read response body");
+                    let json: FunctionsRetainedJson =
+                        serde_json::from_str(&body).expect("Failed to parse retained JSON");
+                    // Wait for the worker to process all calls of the loop
+                    let cache_entries = json
+                        .live
+                        .iter()
+                        .find(|l| l.name.ends_with("::cache_entry"))
+                        .map_or(0, |l| l.live_count);
+                    if json.functions.len() >= 3 && cache_entries >= 100 {
+                        retained = Some(json);
+                        break;
+                    }
+                }
+                Err(e) => {
+                    last_error = Some(format!("Request error: {}", e));
+                }
+            }
+        }
+
+        let _ = child.kill();
+        let _ = child.wait();
+        drop(lines);
+
+        let retained = retained.unwrap_or_else(|| {
+            panic!(
+                "Failed to fetch complete /functions_alloc/retained: {:?}",
+                last_error
+            )
+        });
+
+        let avg_retained = |name: &str| {
+            retained
+                .functions
+                .iter()
+                .find(|f| f.name.ends_with(name))
+                .unwrap_or_else(|| panic!("Missing {name} in {:?}", retained.functions))
+                .avg_retained_bytes
+        };
+        assert_eq!(avg_retained("::cache_entry"), 1024);
+        assert_eq!(avg_retained("::checksum"), 0);
+        assert_eq!(avg_retained("::load_entry"), 2048);
+
+        let cache_entry = retained
+            .live
+            .iter()
+            .find(|l| l.name.ends_with("::cache_entry"))
+            .unwrap_or_else(|| panic!("Missing cache_entry in {:?}", retained.live));
+        assert_eq!(cache_entry.live_bytes, 102400);
+        assert_eq!(cache_entry.live_count, 100);
+        assert!(
+            !retained.live.iter().any(|l| l.name.ends_with("::checksum")),
+            "checksum should not hold live memory: {:?}",
+            retained.live
+        );
+    }
+
     #[test]
     fn test_disable_http_server() {
         use std::{thread::sleep, time::Duration};
@@ -0,0 +1,53 @@
+use smol::Timer;
+use std::time::Duration;
+
+fn main() {
+    smol::block_on(async {
+        let _channels_guard = hotpath::channels::ChannelsGuard::new();
+
+        println!("Creating channels in loops...\n");
+
+        println!("Creating 3 unbounded channels:");
+        let mut handles = vec![];
+        for i in 0..3 {
+            let (tx, rx) = hotpath::channel!(async_channel::unbounded::<i32>());
+
+            println!("  - Created unbounded channel {}", i);
+
+            let handle = smol::spawn(async move {
+                tx.send(i).await.expect("Failed to send");
+                rx.recv().await.expect("Failed to recv");
+            });
+            handles.push(handle);
+        }
+
+        println!("\nCreating 3 bounded channels:");
+        for i in 0..3 {
+            let (tx, rx) = hotpath::channel!(
+                async_channel::bounded::<i32>(10),
+                capacity = 10,
+                label = "bounded"
+            );
+
+            println!("  - Created bounded channel {}", i);
+
+            let handle = smol::spawn(async move {
+                tx.send(i).await.expect("Failed to send");
+                rx.recv().await.expect("Failed to recv");
+            });
+            handles.push(handle);
+        }
+
+        for handle in handles {
+            handle.await;
+        }
+
+        Timer::after(Duration::from_millis(100)).await;
+
+        println!("\nAll channels created and used!");
+
+        drop(_channels_guard);
+
+        println!("\nExample completed!");
+    })
+}
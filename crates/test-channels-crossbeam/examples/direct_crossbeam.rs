@@ -0,0 +1,50 @@
+use std::thread;
+use std::time::Duration;
+
+fn main() {
+    let _channels_guard = hotpath::channels::ChannelsGuardBuilder::new()
+        .format(hotpath::Format::Json)
+        .build();
+
+    // Capacity is read from the crossbeam channel, and stays exact without proxies
+    let (tx, rx) = hotpath::channel!(
+        crossbeam_channel::bounded::<u32>(3),
+        proxy = false,
+        label = "direct-bounded"
+    );
+
+    let mut accepted = 0;
+    while !tx.is_full() {
+        accepted += 1;
+        tx.send(accepted).expect("Failed to send");
+    }
+    println!("[Sender] Accepted: {}", accepted);
+
+    // Receivers can be cloned, the channel stays open until all of them are dropped
+    let rx2 = rx.clone();
+    let msg = rx.recv().expect("Failed to receive");
+    println!("[Receiver] Received: {}", msg);
+    drop(rx);
+    println!("[Receiver] Queued: {}", rx2.len());
+
+    let (tx, rx) = hotpath::channel!(
+        crossbeam_channel::unbounded::<u32>(),
+        proxy = false,
+        label = "direct-unbounded"
+    );
+
+    let receiver_handle = thread::spawn(move || {
+        for msg in rx.iter() {
+            println!("[Receiver] Received: {}", msg);
+        }
+    });
+
+    for i in 1..=5 {
+        tx.send(i).expect("Failed to send");
+    }
+    drop(tx);
+    receiver_handle.join().unwrap();
+
+    thread::sleep(Duration::from_millis(100));
+    println!("\nExample completed!");
+}
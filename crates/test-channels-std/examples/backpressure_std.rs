@@ -0,0 +1,52 @@
+use std::thread;
+use std::time::Duration;
+
+fn main() {
+    let _channels_guard = hotpath::channels::ChannelsGuardBuilder::new()
+        .format(hotpath::Format::Json)
+        .build();
+
+    let (tx, rx) = hotpath::channel!(
+        std::sync::mpsc::sync_channel::<u32>(2),
+        proxy = false,
+        label = "direct",
+        capacity = 2
+    );
+
+    // The consumer is slower than the producer, so sends wait for space
+    let consumer = thread::spawn(move || {
+        for msg in rx.iter() {
+            thread::sleep(Duration::from_millis(20));
+            println!("[Direct] Received: {}", msg);
+        }
+    });
+
+    for i in 1..=6 {
+        tx.send(i).expect("Failed to send");
+    }
+    while tx.try_send(0).is_ok() {}
+    drop(tx);
+    consumer.join().unwrap();
+
+    let (tx, rx) = hotpath::channel!(
+        std::sync::mpsc::sync_channel::<u32>(1),
+        label = "proxied",
+        capacity = 1
+    );
+
+    let consumer = thread::spawn(move || {
+        for msg in rx.iter() {
+            thread::sleep(Duration::from_millis(20));
+            println!("[Proxied] Received: {}", msg);
+        }
+    });
+
+    for i in 1..=6 {
+        tx.send(i).expect("Failed to send");
+    }
+    drop(tx);
+    consumer.join().unwrap();
+
+    thread::sleep(Duration::from_millis(100));
+    println!("\nExample completed!");
+}
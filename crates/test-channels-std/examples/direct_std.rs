@@ -0,0 +1,58 @@
+use std::sync::mpsc::TrySendError;
+use std::thread;
+use std::time::Duration;
+
+fn main() {
+    let _channels_guard = hotpath::channels::ChannelsGuardBuilder::new()
+        .format(hotpath::Format::Json)
+        .build();
+
+    // Without proxies the channel keeps its exact capacity, so only 3 messages fit in
+    let (tx, _rx) = hotpath::channel!(
+        std::sync::mpsc::sync_channel::<u32>(3),
+        proxy = false,
+        label = "direct-bounded",
+        capacity = 3
+    );
+
+    let mut accepted = 0;
+    for i in 1..=5 {
+        match tx.try_send(i) {
+            Ok(()) => accepted += 1,
+            Err(TrySendError::Full(_)) => println!("[Sender] Channel full at: {}", i),
+            Err(err) => panic!("Unexpected error: {:?}", err),
+        }
+    }
+    println!("[Sender] Accepted: {}", accepted);
+
+    let (tx, rx) = hotpath::channel!(
+        std::sync::mpsc::channel::<String>(),
+        label = "direct-unbounded",
+        proxy = false,
+        log = true
+    );
+
+    let sender_handle = thread::spawn(move || {
+        for i in 1..=5 {
+            tx.send(format!("msg-{}", i)).expect("Failed to send");
+        }
+    });
+    sender_handle.join().unwrap();
+
+    for msg in rx.iter().take(2) {
+        println!("[Receiver] Received: {}", msg);
+    }
+    drop(rx);
+
+    // `proxy = true` keeps the default proxies
+    let (tx, rx) = hotpath::channel!(
+        std::sync::mpsc::channel::<u32>(),
+        label = "proxied",
+        proxy = true
+    );
+    tx.send(1).expect("Failed to send");
+    rx.recv().expect("Failed to receive");
+
+    thread::sleep(Duration::from_millis(100));
+    println!("\nExample completed!");
+}
@@ -0,0 +1,29 @@
+use std::thread;
+use std::time::Duration;
+
+fn main() {
+    let _channels_guard = hotpath::channels::ChannelsGuardBuilder::new()
+        .format(hotpath::Format::Json)
+        .build();
+
+    // Without proxies messages stay in the channel until received, so latency is exact
+    let (tx, rx) = hotpath::channel!(
+        std::sync::mpsc::channel::<u32>(),
+        proxy = false,
+        label = "slow-consumer"
+    );
+
+    for i in 1..=5 {
+        tx.send(i).expect("Failed to send");
+    }
+    drop(tx);
+
+    // Each message waits in the queue until the consumer gets to it
+    for msg in rx.iter() {
+        thread::sleep(Duration::from_millis(50));
+        println!("[Receiver] Received: {}", msg);
+    }
+
+    thread::sleep(Duration::from_millis(100));
+    println!("\nExample completed!");
+}
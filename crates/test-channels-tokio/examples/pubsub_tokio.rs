@@ -0,0 +1,59 @@
+use tokio::sync::broadcast::error::RecvError;
+use tokio::time::{sleep, Duration};
+
+#[tokio::main]
+async fn main() {
+    let _channels_guard = hotpath::channels::ChannelsGuardBuilder::new()
+        .format(hotpath::Format::Json)
+        .build();
+
+    let (tx, mut fast_rx) = hotpath::channel!(
+        tokio::sync::broadcast::channel::<u32>(4),
+        capacity = 4,
+        label = "events"
+    );
+    let mut slow_rx = hotpath::channel!(tx.subscribe(), label = "slow");
+
+    // The slow subscriber doesn't read until all messages are sent, so it lags behind
+    for i in 1..=10 {
+        tx.send(i).expect("Failed to send");
+        let msg = fast_rx.recv().await.expect("Failed to receive");
+        println!("[Fast] Received: {}", msg);
+    }
+    sleep(Duration::from_millis(50)).await;
+
+    loop {
+        match slow_rx.try_recv() {
+            Ok(msg) => println!("[Slow] Received: {}", msg),
+            Err(tokio::sync::broadcast::error::TryRecvError::Lagged(n)) => {
+                println!("[Slow] Lagged by {} messages", n)
+            }
+            Err(_) => break,
+        }
+    }
+
+    drop(tx);
+    match fast_rx.recv().await {
+        Err(RecvError::Closed) => println!("[Fast] Channel closed"),
+        other => panic!("Expected closed channel, got {:?}", other),
+    }
+
+    let (config_tx, mut config_rx) =
+        hotpath::channel!(tokio::sync::watch::channel::<u32>(0), label = "config");
+    let mut watcher_rx = hotpath::channel!(config_tx.subscribe(), label = "watcher");
+
+    for version in 1..=3 {
+        config_tx.send(version).expect("Failed to send");
+        config_rx.changed().await.expect("Failed to receive");
+        watcher_rx.changed().await.expect("Failed to receive");
+        println!("[Watch] Version: {}", *config_rx.borrow_and_update());
+    }
+
+    // Closing one receiver keeps the channel open for the other one
+    drop(watcher_rx);
+    config_tx.send(4).expect("Failed to send");
+    config_rx.changed().await.expect("Failed to receive");
+
+    sleep(Duration::from_millis(100)).await;
+    println!("\nExample completed!");
+}
@@ -0,0 +1,43 @@
+use std::time::Duration;
+
+#[hotpath::measure]
+fn decode(len: u64) -> u64 {
+    std::thread::sleep(Duration::from_micros(len));
+    len
+}
+
+#[hotpath::measure]
+fn parse(len: u64) -> u64 {
+    decode(len) * 2
+}
+
+#[hotpath::measure]
+async fn handle_request(id: u64) -> u64 {
+    let header = parse(50);
+    tokio::time::sleep(Duration::from_millis(1)).await;
+    let body = parse(200);
+    header + body + id
+}
+
+#[hotpath::measure]
+fn batch_import() -> u64 {
+    (0..20).map(|_| parse(10)).sum()
+}
+
+#[tokio::main(flavor = "multi_thread", worker_threads = 2)]
+#[hotpath::main(format = "json", limit = 0)]
+async fn main() {
+    let mut handles = Vec::new();
+    for id in 0..10 {
+        handles.push(tokio::spawn(handle_request(id)));
+    }
+    for handle in handles {
+        handle.await.unwrap();
+    }
+
+    for id in 0..5 {
+        handle_request(id).await;
+    }
+
+    batch_import();
+}
@@ -0,0 +1,38 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+static SHIPPED_BYTES: AtomicU64 = AtomicU64::new(0);
+
+// Stands in for jemalloc or mimalloc
+struct ShippedAllocator;
+
+unsafe impl GlobalAlloc for ShippedAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        SHIPPED_BYTES.fetch_add(layout.size() as u64, Ordering::Relaxed);
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+}
+
+hotpath::global_allocator!(ShippedAllocator);
+
+#[hotpath::measure]
+fn allocate() -> usize {
+    let buffer = vec![1u8; 4096];
+    std::hint::black_box(&buffer).len()
+}
+
+#[hotpath::main]
+fn main() {
+    for _ in 0..10 {
+        allocate();
+    }
+
+    println!(
+        "shipped allocator used: {}",
+        SHIPPED_BYTES.load(Ordering::Relaxed) >= 40960
+    );
+}
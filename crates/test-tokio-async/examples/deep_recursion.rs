@@ -0,0 +1,29 @@
+// Recurses deeper than the allocation stack, the innermost calls are charged to their callers
+#[hotpath::measure]
+fn descend(depth: u32) -> usize {
+    let buffer = vec![0u8; 16];
+    let len = std::hint::black_box(&buffer).len();
+    if depth == 0 {
+        len
+    } else {
+        len + descend(depth - 1)
+    }
+}
+
+// Spawns more short-lived threads than there are per-thread stats slots
+#[hotpath::measure]
+fn churn_threads() -> usize {
+    (0..300)
+        .map(|_| {
+            std::thread::spawn(|| std::hint::black_box(vec![0u8; 64]).len())
+                .join()
+                .unwrap()
+        })
+        .sum()
+}
+
+#[hotpath::main]
+fn main() {
+    descend(100);
+    churn_threads();
+}
@@ -0,0 +1,25 @@
+use std::time::Duration;
+
+fn run_plugin(name: &str) {
+    hotpath::measure_block_dyn!(format!("plugin::{name}"), {
+        std::thread::sleep(Duration::from_micros(100));
+    });
+}
+
+fn execute(statement_id: u64) {
+    hotpath::measure_block_dyn!(format!("sql::{statement_id}"), {
+        std::hint::black_box(statement_id);
+    });
+}
+
+// Labels past the first 256 distinct ones are all measured as "hotpath::other"
+#[hotpath::main(format = "json", limit = 0)]
+fn main() {
+    for i in 0..30 {
+        run_plugin(["thumbnails", "search", "audit"][i % 3]);
+    }
+
+    for statement_id in 0..300 {
+        execute(statement_id);
+    }
+}
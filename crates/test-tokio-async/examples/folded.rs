@@ -0,0 +1,41 @@
+use std::time::Duration;
+
+#[hotpath::measure]
+fn parse(len: u64) -> u64 {
+    std::thread::sleep(Duration::from_micros(len));
+    len * 2
+}
+
+#[hotpath::measure]
+async fn handle_request(id: u64) -> u64 {
+    let header = parse(50);
+    tokio::time::sleep(Duration::from_millis(1)).await;
+    header + id
+}
+
+#[hotpath::measure]
+fn batch_import() -> u64 {
+    (0..5).map(|_| parse(10)).sum()
+}
+
+#[hotpath::measure]
+fn walk(depth: u64) -> u64 {
+    std::thread::sleep(Duration::from_micros(20));
+    if depth == 0 {
+        return 0;
+    }
+    walk(depth - 1) + 1
+}
+
+#[tokio::main(flavor = "current_thread")]
+#[hotpath::main(format = "folded")]
+async fn main() {
+    for id in 0..3 {
+        handle_request(id).await;
+    }
+
+    batch_import();
+
+    // Recursive calls are folded into the outermost one
+    walk(50);
+}
@@ -0,0 +1,33 @@
+use std::time::Duration;
+
+#[hotpath::measure]
+fn parse(len: u64) -> u64 {
+    std::thread::sleep(Duration::from_micros(len));
+    len * 2
+}
+
+#[hotpath::measure]
+async fn handle_request(id: u64) -> u64 {
+    let header = parse(50);
+    tokio::time::sleep(Duration::from_millis(20)).await;
+    header + id
+}
+
+// Point OTLP_ENDPOINT at an OpenTelemetry collector, e.g. http://localhost:4318
+#[tokio::main(flavor = "current_thread")]
+async fn main() {
+    let endpoint =
+        std::env::var("OTLP_ENDPOINT").unwrap_or_else(|_| "http://localhost:4318".to_string());
+
+    let _guard = hotpath::FunctionsGuardBuilder::new("otlp::main")
+        .otlp(
+            hotpath::OtlpReporter::new(endpoint)
+                .service_name("otlp-example")
+                .interval(Duration::from_millis(100)),
+        )
+        .build();
+
+    for id in 0..20 {
+        handle_request(id).await;
+    }
+}
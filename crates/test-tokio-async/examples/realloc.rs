@@ -0,0 +1,24 @@
+// Grows from 8 to 64 bytes with 3 reallocations
+#[hotpath::measure]
+fn grow_vec() -> usize {
+    let mut buffer: Vec<u8> = Vec::with_capacity(8);
+    for i in 0..64 {
+        buffer.push(i);
+    }
+    std::hint::black_box(&buffer).len()
+}
+
+// Zeroed allocation without reallocations
+#[hotpath::measure]
+fn zeroed_vec() -> usize {
+    let buffer = vec![0u8; 1024];
+    std::hint::black_box(&buffer).len()
+}
+
+#[hotpath::main(format = "json")]
+fn main() {
+    for _ in 0..10 {
+        grow_vec();
+        zeroed_vec();
+    }
+}
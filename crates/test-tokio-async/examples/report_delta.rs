@@ -0,0 +1,28 @@
+use std::time::Duration;
+
+#[hotpath::measure]
+fn parse(len: u64) -> u64 {
+    std::thread::sleep(Duration::from_micros(len));
+    len * 2
+}
+
+#[hotpath::measure]
+async fn handle_request(id: u64) -> u64 {
+    let header = parse(50);
+    tokio::time::sleep(Duration::from_millis(20)).await;
+    header + id
+}
+
+// Every periodic report covers only the calls since the previous one
+#[tokio::main(flavor = "current_thread")]
+async fn main() {
+    let _guard = hotpath::FunctionsGuardBuilder::new("report_delta::main")
+        .format(hotpath::Format::Json)
+        .report_interval(Duration::from_millis(100))
+        .report_delta(true)
+        .build();
+
+    for id in 0..20 {
+        handle_request(id).await;
+    }
+}
@@ -0,0 +1,23 @@
+use std::time::Duration;
+
+#[hotpath::measure]
+fn parse(len: u64) -> u64 {
+    std::thread::sleep(Duration::from_micros(len));
+    len * 2
+}
+
+#[hotpath::measure]
+async fn handle_request(id: u64) -> u64 {
+    let header = parse(50);
+    tokio::time::sleep(Duration::from_millis(20)).await;
+    header + id
+}
+
+// Prints a JSON report every 100ms and a final one on exit
+#[tokio::main(flavor = "current_thread")]
+#[hotpath::main(format = "json", report_interval = 100)]
+async fn main() {
+    for id in 0..20 {
+        handle_request(id).await;
+    }
+}
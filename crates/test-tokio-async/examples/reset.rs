@@ -0,0 +1,31 @@
+use std::time::Duration;
+
+#[hotpath::measure]
+fn parse(len: u64) -> u64 {
+    std::thread::sleep(Duration::from_micros(len));
+    len * 2
+}
+
+#[hotpath::measure]
+async fn handle_request(id: u64) -> u64 {
+    let header = parse(50);
+    tokio::time::sleep(Duration::from_millis(1)).await;
+    header + id
+}
+
+// Warm-up calls are cleared, the report only covers the calls made after the reset
+#[tokio::main(flavor = "current_thread")]
+#[hotpath::main(format = "json")]
+async fn main() {
+    for id in 0..50 {
+        handle_request(id).await;
+    }
+    // Time before the reset isn't part of the report's elapsed time either
+    std::thread::sleep(Duration::from_millis(300));
+
+    hotpath::reset();
+
+    for id in 0..5 {
+        handle_request(id).await;
+    }
+}
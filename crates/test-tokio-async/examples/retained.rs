@@ -0,0 +1,46 @@
+use std::sync::Mutex;
+use std::time::Duration;
+
+static CACHE: Mutex<Vec<Vec<u8>>> = Mutex::new(Vec::new());
+
+// Every call keeps its buffer alive in the cache
+#[hotpath::measure]
+fn cache_entry(len: usize) {
+    CACHE.lock().unwrap().push(vec![1; len]);
+}
+
+// The buffer is freed before returning
+#[hotpath::measure]
+fn checksum(len: usize) -> u64 {
+    let buffer = vec![1u8; len];
+    std::hint::black_box(&buffer)
+        .iter()
+        .map(|b| *b as u64)
+        .sum()
+}
+
+#[hotpath::measure]
+async fn load_entry(len: usize) {
+    let buffer = vec![1u8; len];
+    tokio::time::sleep(Duration::from_millis(1)).await;
+    CACHE.lock().unwrap().push(buffer);
+}
+
+#[tokio::main(flavor = "multi_thread", worker_threads = 2)]
+#[hotpath::main]
+async fn main() {
+    CACHE.lock().unwrap().reserve(256);
+
+    for _ in 0..100 {
+        cache_entry(1024);
+        checksum(1024);
+        load_entry(2048).await;
+    }
+    println!("[retained] ready");
+
+    if let Ok(sleep_seconds) = std::env::var("TEST_SLEEP_SECONDS") {
+        if let Ok(seconds) = sleep_seconds.parse::<u64>() {
+            tokio::time::sleep(Duration::from_secs(seconds)).await;
+        }
+    }
+}
@@ -0,0 +1,47 @@
+// Measured once every 100 calls
+#[hotpath::measure(sample = 100)]
+fn hot_path(i: u64) -> usize {
+    let buffer = vec![i as u8; 64];
+    std::hint::black_box(&buffer).len()
+}
+
+// Measured as often as HOTPATH_SAMPLE_RATE says
+#[hotpath::measure]
+fn warm_path(i: u64) -> usize {
+    let buffer = vec![i as u8; 256];
+    std::hint::black_box(&buffer).len()
+}
+
+#[hotpath::measure(sample = 10, log = true)]
+async fn sampled_async(i: u64) -> u64 {
+    tokio::task::yield_now().await;
+    i
+}
+
+// Skipped calls still nest the calls made inside them
+#[hotpath::measure(sample = 10)]
+fn sampled_parent(i: u64) -> u64 {
+    measured_child(i)
+}
+
+#[hotpath::measure(sample = 1)]
+fn measured_child(i: u64) -> u64 {
+    std::hint::black_box(i)
+}
+
+#[tokio::main(flavor = "current_thread")]
+#[hotpath::main(format = "json")]
+async fn main() {
+    for i in 0..10_000 {
+        hot_path(i);
+    }
+    for i in 0..1_000 {
+        warm_path(i);
+    }
+    for i in 0..100 {
+        sampled_async(i).await;
+    }
+    for i in 0..100 {
+        sampled_parent(i);
+    }
+}
@@ -0,0 +1,24 @@
+use std::time::Duration;
+
+#[hotpath::measure]
+fn inner() {
+    std::thread::sleep(Duration::from_millis(2));
+}
+
+#[hotpath::measure]
+fn outer() {
+    for _ in 0..5 {
+        inner();
+    }
+}
+
+fn main() {
+    let _hotpath = hotpath::FunctionsGuardBuilder::new("sort_self::main")
+        .sort_by(hotpath::SortBy::SelfTime)
+        .limit(1)
+        .build();
+
+    for _ in 0..3 {
+        outer();
+    }
+}
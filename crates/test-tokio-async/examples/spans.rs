@@ -0,0 +1,74 @@
+use std::cell::RefCell;
+use std::time::Duration;
+
+thread_local! {
+    // Initialized before hotpath's thread-locals, so it's destroyed after them
+    static LINGERING: RefCell<Option<hotpath::Span>> = const { RefCell::new(None) };
+}
+
+struct Connection {
+    session: Option<hotpath::Span>,
+}
+
+impl Connection {
+    fn open() -> Self {
+        Self {
+            session: Some(hotpath::span_start("session")),
+        }
+    }
+
+    fn close(&mut self) {
+        if let Some(session) = self.session.take() {
+            session.finish();
+        }
+    }
+}
+
+#[hotpath::measure]
+fn shutdown() {}
+
+#[hotpath::measure]
+fn start_upload() -> hotpath::Span {
+    hotpath::span_start("upload").with_tag("s3")
+}
+
+// Spans are finished later, from another thread or task than the one that started them
+#[tokio::main(flavor = "multi_thread", worker_threads = 2)]
+#[hotpath::main(format = "json")]
+async fn main() {
+    let uploads: Vec<_> = (0..4)
+        .map(|_| {
+            let span = start_upload();
+            std::thread::spawn(move || {
+                std::thread::sleep(Duration::from_millis(5));
+                span.finish();
+            })
+        })
+        .collect();
+    for upload in uploads {
+        upload.join().unwrap();
+    }
+
+    let mut connection = Connection::open();
+    tokio::time::sleep(Duration::from_millis(2)).await;
+    connection.close();
+
+    let (tx, rx) = tokio::sync::oneshot::channel::<hotpath::Span>();
+    let handler = tokio::spawn(async move {
+        let span = rx.await.unwrap();
+        tokio::time::sleep(Duration::from_millis(1)).await;
+        span.finish();
+    });
+    tx.send(hotpath::span_start("request")).ok();
+    handler.await.unwrap();
+
+    // A span dropped during thread teardown is not recorded
+    std::thread::spawn(|| {
+        LINGERING.with(|lingering| {
+            *lingering.borrow_mut() = Some(hotpath::span_start("lingering"));
+        });
+        shutdown();
+    })
+    .join()
+    .unwrap();
+}
@@ -0,0 +1,51 @@
+use std::time::Duration;
+
+#[hotpath::measure(tag = endpoint)]
+fn handle_request(endpoint: &str) -> usize {
+    std::thread::sleep(Duration::from_micros(100));
+    endpoint.len()
+}
+
+#[hotpath::measure(tag = tenant, log = true)]
+async fn load_account(tenant: String) -> usize {
+    tokio::time::sleep(Duration::from_millis(1)).await;
+    tenant.len()
+}
+
+async fn serve() {
+    for i in 0..30 {
+        let endpoint = if i % 3 == 0 { "/checkout" } else { "/search" };
+        handle_request(endpoint);
+    }
+
+    for tenant in ["acme", "acme", "globex"] {
+        load_account(tenant.to_string()).await;
+    }
+
+    for region in ["eu", "us", "us"] {
+        hotpath::measure_block!("replicate", tag = region, {
+            std::thread::sleep(Duration::from_micros(50));
+        });
+    }
+}
+
+// Stats of tagged calls are kept per tag too, e.g. `/functions_timing?tag=%2Fcheckout`
+#[hotpath::main]
+fn main() {
+    // Measurements are sent in batches, the serving thread flushes its batch on exit
+    std::thread::spawn(|| {
+        tokio::runtime::Builder::new_current_thread()
+            .enable_time()
+            .build()
+            .unwrap()
+            .block_on(serve());
+    })
+    .join()
+    .unwrap();
+
+    if let Ok(sleep_seconds) = std::env::var("TEST_SLEEP_SECONDS") {
+        if let Ok(seconds) = sleep_seconds.parse::<u64>() {
+            std::thread::sleep(Duration::from_secs(seconds));
+        }
+    }
+}
@@ -0,0 +1,34 @@
+use std::time::Duration;
+
+#[hotpath::measure]
+fn parse(len: u64) -> u64 {
+    std::thread::sleep(Duration::from_micros(len));
+    len * 2
+}
+
+#[hotpath::measure]
+async fn handle_request(id: u64) -> u64 {
+    let header = parse(50);
+    tokio::time::sleep(Duration::from_millis(1)).await;
+    header + id
+}
+
+// Run with HOTPATH_TRACE_FILE=trace.json and open the file in https://ui.perfetto.dev
+#[tokio::main(flavor = "current_thread")]
+#[hotpath::main]
+async fn main() {
+    let (tx, mut rx) = hotpath::channel!(tokio::sync::mpsc::channel::<u64>(10), label = "requests");
+
+    let worker = std::thread::Builder::new()
+        .name("worker".into())
+        .spawn(|| (0..3).map(|_| parse(20)).sum::<u64>())
+        .unwrap();
+
+    for id in 0..3 {
+        let value = hotpath::future!(handle_request(id)).await;
+        tx.send(value).await.unwrap();
+        rx.recv().await.unwrap();
+    }
+
+    worker.join().unwrap();
+}